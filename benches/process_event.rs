@@ -0,0 +1,102 @@
+//! Measures the cost of processing mouse movement and key presses as the amount of bound actions grows.
+//! Run via `cargo bench --bench process_event`.
+use input_actions::{
+	action::BehaviorBinding,
+	binding::{self, ActionMap, ActionSet, ActionSetId, LayoutId},
+	device, event, source, Config, DeviceCache, User,
+};
+use std::{
+	sync::{Arc, RwLock},
+	time::Instant,
+};
+
+const KEYS: [source::Key; 4] = [
+	source::Key::A,
+	source::Key::S,
+	source::Key::D,
+	source::Key::W,
+];
+const EVENTS_PER_FRAME: usize = 5000;
+const FRAMES: usize = 100;
+
+fn mouse_move(axis: binding::MouseAxis) -> event::Event {
+	event::Event::Input(
+		device::Id::Mouse,
+		binding::Source::Mouse(binding::Mouse::Move(axis)),
+		event::State::MouseMove(1.0),
+	)
+}
+
+fn key(key: source::Key, state: event::ButtonState) -> event::Event {
+	event::Event::Input(
+		device::Id::Keyboard,
+		binding::Source::Keyboard(key),
+		event::State::ButtonState(state),
+	)
+}
+
+fn bench(action_count: usize) {
+	let mut config = Config::default().add_layout(LayoutId::default());
+	let mut map = ActionMap::default().bind(
+		"look",
+		BehaviorBinding::from(binding::Source::Mouse(binding::Mouse::Move(
+			binding::MouseAxis::MouseX,
+		))),
+	);
+	config = config.add_action("look", source::Kind::Axis);
+	for i in 0..action_count {
+		let id: &'static str = Box::leak(format!("action{}", i).into_boxed_str());
+		config = config.add_action(id, source::Kind::Button);
+		map = map.bind(id, binding::Source::Keyboard(KEYS[i % KEYS.len()]));
+	}
+	let config = Arc::new(RwLock::new(config.add_action_set(
+		ActionSetId::default(),
+		ActionSet::default().with(LayoutId::default(), map),
+	)));
+
+	let mut cache = DeviceCache::default();
+	let user = User::new("bench".to_owned())
+		.with_config(Arc::downgrade(&config))
+		.with_consts(cache.consts())
+		.arclocked();
+	user.write()
+		.unwrap()
+		.enable_action_set(ActionSetId::default());
+	cache.add_user(Arc::downgrade(&user));
+	cache.send_event(event::Event::Window(event::WindowEvent::ResolutionChanged(
+		1920, 1080,
+	)));
+	cache.update();
+
+	// The per-frame cost of updating every action is measured separately,
+	// so that only the cost of routing and processing events is reported.
+	let mut run = |events_per_frame: usize| {
+		let start = Instant::now();
+		for _ in 0..FRAMES {
+			for _ in 0..events_per_frame {
+				cache.send_event(mouse_move(binding::MouseAxis::MouseX));
+				cache.send_event(mouse_move(binding::MouseAxis::MouseY));
+				cache.send_event(key(KEYS[0], event::ButtonState::Pressed));
+				cache.send_event(key(KEYS[0], event::ButtonState::Released));
+			}
+			cache.update();
+			user.write().unwrap().update(&Instant::now());
+		}
+		start.elapsed()
+	};
+	let update_only = run(0);
+	let with_events = run(EVENTS_PER_FRAME);
+	let event_count = FRAMES * EVENTS_PER_FRAME * 4;
+	println!(
+		"{:>6} actions: {:>8.1} ns/event ({:>10.1} ns/update)",
+		action_count,
+		with_events.saturating_sub(update_only).as_nanos() as f64 / event_count as f64,
+		update_only.as_nanos() as f64 / FRAMES as f64,
+	);
+}
+
+fn main() {
+	for &action_count in [10, 100, 1000, 10000].iter() {
+		bench(action_count);
+	}
+}
@@ -3,5 +3,11 @@ pub type Id = &'static str;
 pub mod behavior;
 mod behavior_binding;
 pub use behavior_binding::*;
+mod observer;
+pub use observer::*;
+mod published;
+pub use published::*;
 mod state;
 pub use state::*;
+mod time;
+pub use time::*;
@@ -1,24 +1,50 @@
-use crate::binding::Source;
-use std::time::Instant;
+use crate::{action::Time, binding::Source, Error, Settings};
 
 pub enum Kind {
 	Map,
 	Fold,
 }
 
+/// The input a [`Behavior`] is mapping a value for.
+#[derive(Debug, Clone, Copy)]
+pub struct Context<'a> {
+	/// The source which sent the value.
+	pub source: Source,
+	pub time: &'a Time,
+	/// The size of the screen in logical pixels, or zero if it is not yet known.
+	pub screen_size: (f64, f64),
+	/// The settings of the user the input was sent to.
+	pub settings: &'a Settings,
+}
+
 pub trait Behavior {
 	fn cloned(&self) -> Box<dyn Behavior + Send + Sync>;
 	fn debug_string(&self) -> String {
 		std::any::type_name::<Self>().to_owned()
 	}
+	/// Returns the behavior as it is written in the [`binding syntax`](crate::dsl),
+	/// or `None` if it has no representation there (in which case the [`debug_string`](Behavior::debug_string) is printed).
+	fn dsl_string(&self) -> Option<String> {
+		None
+	}
 	fn kind(&self) -> Kind {
 		Kind::Map
 	}
-	fn map(&self, _source: Source, _value: f64, _time: &Instant, _screen_size: &(f64, f64)) -> f64 {
-		unimplemented!()
+	/// Returns an error if the behavior cannot process input from the provided source.
+	/// Called for every source a behavior is bound to when an action set is added via
+	/// [`Config::try_add_action_set`](crate::Config::try_add_action_set).
+	fn validate(&self, _source: Source) -> Result<(), Error> {
+		Ok(())
+	}
+	/// Transforms the value of a source. Behaviors of kind [`Map`](Kind::Map) should override this;
+	/// by default the value is unchanged.
+	fn map(&self, value: f64, _context: &Context) -> f64 {
+		value
 	}
-	fn fold(&self, _values: &[f64]) -> f64 {
-		unimplemented!()
+	/// Combines the values of multiple sources. Behaviors of kind [`Fold`](Kind::Fold) should override this;
+	/// by default the first value is used.
+	fn fold(&self, values: &[f64]) -> f64 {
+		values.first().cloned().unwrap_or_default()
 	}
 }
 
@@ -30,9 +56,13 @@ impl Clone for Box<dyn Behavior + Send + Sync> {
 
 mod average;
 pub use average::*;
+mod deadzone;
+pub use deadzone::*;
 mod multiplier;
 pub use multiplier::*;
 mod screen_position_delta;
 pub use screen_position_delta::*;
+mod setting;
+pub use setting::*;
 mod virtual_axis;
 pub use virtual_axis::*;
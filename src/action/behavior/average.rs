@@ -11,6 +11,10 @@ impl Behavior for Average {
 		format!("{:?}", self)
 	}
 
+	fn dsl_string(&self) -> Option<String> {
+		Some("average".to_owned())
+	}
+
 	fn kind(&self) -> Kind {
 		Kind::Fold
 	}
@@ -0,0 +1,28 @@
+use crate::action::behavior::{Behavior, Context};
+
+/// Ignores values whose magnitude is below a threshold (like the resting noise of a thumbstick),
+/// rescaling the remaining range so the value still reaches 1.0 at full actuation.
+#[derive(Debug, Clone, Copy)]
+pub struct Deadzone(pub f32);
+impl Behavior for Deadzone {
+	fn cloned(&self) -> Box<dyn Behavior + Send + Sync> {
+		Box::new(*self)
+	}
+
+	fn debug_string(&self) -> String {
+		format!("{:?}", self)
+	}
+
+	fn dsl_string(&self) -> Option<String> {
+		Some(format!("deadzone({})", self.0))
+	}
+
+	fn map(&self, value: f64, _context: &Context) -> f64 {
+		let threshold = (self.0 as f64).clamp(0.0, 1.0);
+		if value.abs() <= threshold || threshold >= 1.0 {
+			0.0
+		} else {
+			value.signum() * (value.abs() - threshold) / (1.0 - threshold)
+		}
+	}
+}
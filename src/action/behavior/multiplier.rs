@@ -1,5 +1,4 @@
-use crate::{action::behavior::Behavior, binding::Source};
-use std::time::Instant;
+use crate::action::behavior::{Behavior, Context};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Multiplier(pub f32);
@@ -12,7 +11,11 @@ impl Behavior for Multiplier {
 		format!("{:?}", self)
 	}
 
-	fn map(&self, _source: Source, value: f64, _time: &Instant, _screen_size: &(f64, f64)) -> f64 {
+	fn dsl_string(&self) -> Option<String> {
+		Some(format!("{}", self.0))
+	}
+
+	fn map(&self, value: f64, _context: &Context) -> f64 {
 		value * (self.0 as f64)
 	}
 }
@@ -1,5 +1,8 @@
-use crate::{action::behavior::Behavior, binding::Source};
-use std::time::Instant;
+use crate::{
+	action::behavior::{Behavior, Context},
+	binding::{Mouse, MouseAxis::*, Source},
+	Error,
+};
 
 /// A value from -1.0 to 1.0 that represents the distance moved across
 /// the screen over the update cycle. The value is normalized based on
@@ -16,16 +19,32 @@ impl Behavior for ScreenPositionDelta {
 		format!("{:?}", self)
 	}
 
-	fn map(&self, source: Source, value: f64, _time: &Instant, screen_size: &(f64, f64)) -> f64 {
-		use crate::binding::{Mouse, MouseAxis::*};
-		let axis = match source {
-			Source::Mouse(Mouse::Move(axis)) => axis,
-			_ => unimplemented!(),
-		};
-		let size = match axis {
-			MouseX => screen_size.0,
-			MouseY => screen_size.1,
+	fn dsl_string(&self) -> Option<String> {
+		Some("screen_delta".to_owned())
+	}
+
+	fn validate(&self, source: Source) -> Result<(), Error> {
+		match source {
+			Source::Mouse(Mouse::Move(_)) => Ok(()),
+			_ => Err(Error::UnsupportedSource {
+				behavior: self.debug_string(),
+				source,
+			}),
+		}
+	}
+
+	fn map(&self, value: f64, context: &Context) -> f64 {
+		let size = match context.source {
+			Source::Mouse(Mouse::Move(MouseX)) => context.screen_size.0,
+			Source::Mouse(Mouse::Move(MouseY)) => context.screen_size.1,
+			// Unsupported sources are rejected by `validate`.
+			_ => return value,
 		};
-		value / size
+		// The screen size is unknown until the first window event is received.
+		if size > 0.0 {
+			value / size
+		} else {
+			0.0
+		}
 	}
 }
@@ -0,0 +1,63 @@
+use crate::action::behavior::{Behavior, Context};
+use std::borrow::Cow;
+
+/// Multiplies the value by the float [`setting`](Settings) with the provided name
+/// (for example, `look_sensitivity`). If the user does not have the setting, the value is unchanged.
+#[derive(Debug, Clone)]
+pub struct SettingMultiplier(pub Cow<'static, str>);
+
+impl SettingMultiplier {
+	pub fn new<T: Into<Cow<'static, str>>>(name: T) -> Self {
+		Self(name.into())
+	}
+}
+
+impl Behavior for SettingMultiplier {
+	fn cloned(&self) -> Box<dyn Behavior + Send + Sync> {
+		Box::new(self.clone())
+	}
+
+	fn debug_string(&self) -> String {
+		format!("{:?}", self)
+	}
+
+	fn dsl_string(&self) -> Option<String> {
+		Some(format!("setting({})", self.0))
+	}
+
+	fn map(&self, value: f64, context: &Context) -> f64 {
+		value * context.settings.float(&self.0).unwrap_or(1.0)
+	}
+}
+
+/// Negates the value when the bool [`setting`](Settings) with the provided name (for example, `invert_y`) is true.
+#[derive(Debug, Clone)]
+pub struct SettingInvert(pub Cow<'static, str>);
+
+impl SettingInvert {
+	pub fn new<T: Into<Cow<'static, str>>>(name: T) -> Self {
+		Self(name.into())
+	}
+}
+
+impl Behavior for SettingInvert {
+	fn cloned(&self) -> Box<dyn Behavior + Send + Sync> {
+		Box::new(self.clone())
+	}
+
+	fn debug_string(&self) -> String {
+		format!("{:?}", self)
+	}
+
+	fn dsl_string(&self) -> Option<String> {
+		Some(format!("invert({})", self.0))
+	}
+
+	fn map(&self, value: f64, context: &Context) -> f64 {
+		if context.settings.bool(&self.0).unwrap_or(false) {
+			-value
+		} else {
+			value
+		}
+	}
+}
@@ -1,9 +1,9 @@
 use crate::{
-	action::behavior::{Behavior, Kind},
+	action::behavior::{Behavior, Context, Kind},
 	binding::Source,
-	device,
+	device, Error,
 };
-use std::{collections::HashMap, time::Instant};
+use std::collections::HashMap;
 
 type BehaviorList = Vec<Box<dyn Behavior + 'static + Send + Sync>>;
 
@@ -82,17 +82,19 @@ impl SourceBehavior {
 		self.behaviors.push(Box::new(behavior));
 	}
 
-	pub(crate) fn process(
-		&mut self,
-		source: Source,
-		value: f64,
-		time: &Instant,
-		screen_size: &(f64, f64),
-	) -> f64 {
-		if self.source == source {
+	pub fn source(&self) -> Source {
+		self.source
+	}
+
+	pub(crate) fn behaviors(&self) -> &BehaviorList {
+		&self.behaviors
+	}
+
+	pub(crate) fn process(&mut self, value: f64, context: &Context) -> f64 {
+		if self.source == context.source {
 			self.latest_value = value;
 			for behavior in self.behaviors.iter() {
-				self.latest_value = behavior.map(source, self.latest_value, &time, &screen_size);
+				self.latest_value = behavior.map(self.latest_value, context);
 			}
 		}
 		self.latest_value
@@ -107,14 +109,29 @@ impl BehaviorBinding {
 		Self::Select(options.collect())
 	}
 
-	fn is_directly_applicable(&self, source: Source) -> bool {
-		if let Self::Source(binding) = &self {
-			return binding.source == source;
+	/// Returns true if the source is bound anywhere in the binding.
+	fn receives(&self, source: Source) -> bool {
+		match self {
+			Self::Source(binding) => binding.source == source,
+			Self::Container(bindings, _) => bindings.iter().any(|binding| binding.receives(source)),
+			Self::Select(bindings) => bindings.values().any(|binding| binding.receives(source)),
 		}
-		false
 	}
 
-	pub fn with_behavior<TBehavior>(mut self, behavior: TBehavior) -> Self
+	/// Adds a behavior to the binding.
+	///
+	/// # Panics
+	/// If the binding is a [`Select`](BehaviorBinding::Select). See [`try_with_behavior`](BehaviorBinding::try_with_behavior).
+	pub fn with_behavior<TBehavior>(self, behavior: TBehavior) -> Self
+	where
+		TBehavior: Behavior + 'static + Send + Sync + Clone,
+	{
+		self.try_with_behavior(behavior)
+			.unwrap_or_else(|error| panic!("{}", error))
+	}
+
+	/// Adds a behavior to the binding, failing if the binding is a [`Select`](BehaviorBinding::Select).
+	pub fn try_with_behavior<TBehavior>(mut self, behavior: TBehavior) -> Result<Self, Error>
 	where
 		TBehavior: Behavior + 'static + Send + Sync + Clone,
 	{
@@ -125,20 +142,59 @@ impl BehaviorBinding {
 			Self::Source(src_behavior) => {
 				src_behavior.add_behavior(behavior);
 			}
-			Self::Select(_) => unimplemented!(),
+			Self::Select(_) => return Err(Error::BehaviorOnSelect),
 		}
-		self
+		Ok(self)
+	}
+
+	/// Adds a child binding to a [`Container`](BehaviorBinding::Container).
+	///
+	/// # Panics
+	/// If the binding is not a container. See [`try_with_binding`](BehaviorBinding::try_with_binding).
+	pub fn with_binding(self, binding: BehaviorBinding) -> Self {
+		self.try_with_binding(binding)
+			.unwrap_or_else(|error| panic!("{}", error))
 	}
 
-	pub fn with_binding(mut self, binding: BehaviorBinding) -> Self {
+	/// Adds a child binding to a [`Container`](BehaviorBinding::Container),
+	/// failing if the binding is not a container.
+	pub fn try_with_binding(mut self, binding: BehaviorBinding) -> Result<Self, Error> {
 		match &mut self {
 			Self::Container(bindings, _) => {
 				bindings.push(binding);
 			}
-			Self::Source(_) => unimplemented!(),
-			Self::Select(_) => unimplemented!(),
+			Self::Source(_) | Self::Select(_) => return Err(Error::NotAContainer),
 		}
-		self
+		Ok(self)
+	}
+
+	/// Returns an error if any behavior in the binding does not support a source it will receive input from.
+	pub fn validate(&self) -> Result<(), Error> {
+		match self {
+			Self::Source(SourceBehavior {
+				source, behaviors, ..
+			}) => {
+				for behavior in behaviors.iter() {
+					behavior.validate(*source)?;
+				}
+			}
+			Self::Container(bindings, behaviors) => {
+				for binding in bindings.iter() {
+					binding.validate()?;
+				}
+				for source in self.sources() {
+					for behavior in behaviors.iter() {
+						behavior.validate(source)?;
+					}
+				}
+			}
+			Self::Select(bindings) => {
+				for binding in bindings.values() {
+					binding.validate()?;
+				}
+			}
+		}
+		Ok(())
 	}
 
 	pub(crate) fn sources(&self) -> Vec<Source> {
@@ -157,47 +213,70 @@ impl BehaviorBinding {
 		}
 	}
 
-	pub(crate) fn process(
-		&mut self,
-		source: Source,
-		mut value: f64,
-		time: &Instant,
-		screen_size: &(f64, f64),
-	) -> f64 {
+	/// Replaces the sources in the binding with their replacement in `(original, replacement)` pairs, keeping their behaviors.
+	/// All sources are replaced at once, so pairs can swap sources (like `(W, S)` and `(S, W)`).
+	pub(crate) fn replace_sources(&mut self, replacements: &[(Source, Source)]) {
+		match self {
+			Self::Source(binding) => {
+				if let Some((_, replacement)) = replacements
+					.iter()
+					.find(|(original, _)| *original == binding.source)
+				{
+					binding.source = *replacement;
+				}
+			}
+			Self::Container(bindings, _) => {
+				for binding in bindings.iter_mut() {
+					binding.replace_sources(replacements);
+				}
+			}
+			Self::Select(bindings) => {
+				for binding in bindings.values_mut() {
+					binding.replace_sources(replacements);
+				}
+			}
+		}
+	}
+
+	pub(crate) fn process(&mut self, value: f64, context: &Context) -> f64 {
 		match self {
-			Self::Source(src_behavior) => src_behavior.process(source, value, &time, &screen_size),
+			Self::Source(src_behavior) => src_behavior.process(value, context),
 			Self::Select(bindings) => {
-				if let Some(binding) = bindings.get_mut(&source.device_kind()) {
-					binding.process(source, value, &time, &screen_size)
+				if let Some(binding) = bindings.get_mut(&context.source.device_kind()) {
+					binding.process(value, context)
 				} else {
 					0.0
 				}
 			}
 			Self::Container(bindings, behaviors) => {
 				let mut values = Vec::with_capacity(bindings.len());
-				for behavior_binding in bindings.iter_mut() {
-					let v = behavior_binding.process(source, value, &time, &screen_size);
-					values.push(v);
-					if behaviors.is_empty() && behavior_binding.is_directly_applicable(source) {
-						value = v;
+				let mut received = None;
+				for (i, behavior_binding) in bindings.iter_mut().enumerate() {
+					values.push(behavior_binding.process(value, context));
+					if received.is_none() && behavior_binding.receives(context.source) {
+						received = Some(i);
 					}
 				}
-				if behaviors.is_empty() {
-					value
-				} else {
-					for behavior in behaviors.iter() {
-						match behavior.kind() {
-							Kind::Map => {
-								for value in values.iter_mut() {
-									*value = behavior.map(source, *value, &time, &screen_size);
-								}
-							}
-							Kind::Fold => {
-								values = vec![behavior.fold(&values[..])];
+				// Maps apply to the value of each child, such that the result is that of the child which received the input,
+				// until a fold combines all of the values into one.
+				let mut folded = false;
+				for behavior in behaviors.iter() {
+					match behavior.kind() {
+						Kind::Map => {
+							for value in values.iter_mut() {
+								*value = behavior.map(*value, context);
 							}
 						}
+						Kind::Fold => {
+							values = vec![behavior.fold(&values[..])];
+							folded = true;
+						}
 					}
-					values[0]
+				}
+				match (folded, received) {
+					(true, _) => values[0],
+					(false, Some(i)) => values[i],
+					(false, None) => value,
 				}
 			}
 		}
@@ -240,15 +319,15 @@ impl std::ops::Add<BehaviorBinding> for BehaviorBinding {
 
 impl std::ops::Add<Source> for BehaviorBinding {
 	type Output = Self;
-	fn add(mut self, rhs: Source) -> Self {
-		match &mut self {
-			Self::Container(bindings, _) => {
-				bindings.push(Self::from(rhs));
+	/// Adds the source to a container, or groups the binding and the source in a new container.
+	fn add(self, rhs: Source) -> Self {
+		match self {
+			Self::Container(mut bindings, behaviors) => {
+				bindings.push(rhs.into());
+				Self::Container(bindings, behaviors)
 			}
-			Self::Source(_) => unimplemented!(),
-			Self::Select(_) => unimplemented!(),
+			Self::Source(_) | Self::Select(_) => Self::Container(vec![self, rhs.into()], vec![]),
 		}
-		self
 	}
 }
 
@@ -257,3 +336,61 @@ impl<const N: usize> From<[(device::Kind, BehaviorBinding); N]> for BehaviorBind
 		Self::select(other.iter().cloned())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{action::Time, dsl::parse_binding, source::Key, Settings};
+
+	fn send(binding: &mut BehaviorBinding, source: Source, value: f64) -> f64 {
+		let context = Context {
+			source,
+			time: &Time::now(),
+			screen_size: (0.0, 0.0),
+			settings: &Settings::default(),
+		};
+		binding.process(value, &context)
+	}
+
+	#[test]
+	fn group_behaviors_apply_to_the_source_which_sent_input() {
+		let (d, a) = (Source::Keyboard(Key::D), Source::Keyboard(Key::A));
+		let mut binding = parse_binding("(Key(D) - Key(A)) * 2").unwrap();
+		assert_eq!(send(&mut binding, d, 1.0), 2.0);
+		assert_eq!(send(&mut binding, d, 0.0), 0.0);
+		assert_eq!(send(&mut binding, a, 1.0), -2.0);
+
+		let mut binding = parse_binding("-(Key(A) | Key(D))").unwrap();
+		assert_eq!(send(&mut binding, d, 1.0), -1.0);
+	}
+
+	#[test]
+	fn adding_sources_groups_them() {
+		let (w, s, d) = (
+			Source::Keyboard(Key::W),
+			Source::Keyboard(Key::S),
+			Source::Keyboard(Key::D),
+		);
+		let binding = BehaviorBinding::from(w) + s;
+		assert!(matches!(binding, BehaviorBinding::Container(_, _)));
+		assert_eq!(binding.sources(), vec![w, s]);
+		let binding = binding + d;
+		assert_eq!(binding.sources(), vec![w, s, d]);
+	}
+
+	#[test]
+	fn nested_groups_find_the_source() {
+		let (d, a) = (Source::Keyboard(Key::D), Source::Keyboard(Key::A));
+		let mut binding = parse_binding("(Key(W) | (Key(D) - Key(A)) * 3) * 0.5").unwrap();
+		assert_eq!(send(&mut binding, a, 1.0), -1.5);
+		assert_eq!(send(&mut binding, d, 1.0), 1.5);
+	}
+
+	#[test]
+	fn folds_combine_the_group() {
+		let (a, b) = (Source::Keyboard(Key::A), Source::Keyboard(Key::B));
+		let mut binding = parse_binding("(Key(A) | Key(B)) * average * 4").unwrap();
+		assert_eq!(send(&mut binding, a, 1.0), 2.0);
+		assert_eq!(send(&mut binding, b, 1.0), 4.0);
+	}
+}
@@ -0,0 +1,46 @@
+use crate::action;
+use crossbeam_channel::{Receiver, Sender};
+
+/// A high-level change in the state of an action, emitted by [`User::update`](crate::User::update)
+/// to [`subscribers`](crate::User::subscribe) and [`observers`](crate::User::observe).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Event {
+	/// The action was actuated (a button was pressed, or an axis moved away from zero).
+	Started,
+	/// The action was released after being actuated (a button was released, or an axis returned to zero).
+	Performed,
+	/// The action was actuated when it was removed from the user
+	/// (because its action set was disabled or the user's layout changed).
+	Canceled,
+	/// The [`axis value`](action::State::axis_value) of the action changed.
+	ValueChanged(f64),
+}
+
+pub type EventSender = Sender<(action::Id, Event)>;
+pub type EventReceiver = Receiver<(action::Id, Event)>;
+pub type EventCallback = Box<dyn FnMut(action::Id, Event) + Send + Sync>;
+
+pub(crate) enum Observer {
+	Channel(Option<action::Id>, EventSender),
+	Callback(Option<action::Id>, EventCallback),
+}
+
+impl Observer {
+	/// Sends an event to the observer if it is interested in the action.
+	/// Returns false if the observer can no longer receive events and should be dropped.
+	pub(crate) fn notify(&mut self, id: action::Id, event: Event) -> bool {
+		match self {
+			Self::Channel(filter, sender) => {
+				if filter.is_none_or(|filter| filter == id) {
+					return sender.send((id, event)).is_ok();
+				}
+			}
+			Self::Callback(filter, callback) => {
+				if filter.is_none_or(|filter| filter == id) {
+					callback(id, event);
+				}
+			}
+		}
+		true
+	}
+}
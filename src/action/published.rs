@@ -0,0 +1,103 @@
+use crate::action;
+use arc_swap::ArcSwap;
+use std::{collections::HashMap, sync::Arc};
+
+/// The readable values of a single [`action state`](action::State), copied at the end of an update.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StateValue {
+	pub(crate) down: bool,
+	pub(crate) pressed: bool,
+	pub(crate) released: bool,
+	pub(crate) value: f64,
+	pub(crate) axis_value: f64,
+}
+
+impl StateValue {
+	/// See [`State::on_button_pressed`](action::State::on_button_pressed).
+	pub fn on_button_pressed(&self) -> bool {
+		self.pressed
+	}
+
+	/// See [`State::is_button_down`](action::State::is_button_down).
+	pub fn is_button_down(&self) -> bool {
+		self.down
+	}
+
+	/// See [`State::on_button_released`](action::State::on_button_released).
+	pub fn on_button_released(&self) -> bool {
+		self.released
+	}
+
+	/// See [`State::axis_value`](action::State::axis_value).
+	pub fn axis_value(&self) -> f64 {
+		self.axis_value
+	}
+
+	/// See [`State::value`](action::State::value).
+	pub fn value(&self) -> f64 {
+		self.value
+	}
+}
+
+/// An immutable copy of all of a user's action states, published at the end of each
+/// [`update`](crate::User::update) and readable from any thread without locking the user.
+#[derive(Debug, Clone, Default)]
+pub struct StateSnapshot {
+	states: HashMap<action::Id, StateValue>,
+}
+
+impl StateSnapshot {
+	pub(crate) fn new(states: HashMap<action::Id, StateValue>) -> Self {
+		Self { states }
+	}
+
+	pub fn get(&self, id: action::Id) -> Option<&StateValue> {
+		self.states.get(id)
+	}
+
+	pub fn iter(&self) -> std::collections::hash_map::Iter<'_, action::Id, StateValue> {
+		self.states.iter()
+	}
+
+	/// Returns true if the action exists and its button was pressed during the update.
+	pub fn pressed(&self, id: action::Id) -> bool {
+		self.get(id).is_some_and(StateValue::on_button_pressed)
+	}
+
+	/// Returns true if the action exists and its button is held.
+	pub fn down(&self, id: action::Id) -> bool {
+		self.get(id).is_some_and(StateValue::is_button_down)
+	}
+
+	/// Returns true if the action exists and its button was released during the update.
+	pub fn released(&self, id: action::Id) -> bool {
+		self.get(id).is_some_and(StateValue::on_button_released)
+	}
+
+	/// Returns the axis value of the action, or 0.0 if the action does not exist.
+	pub fn axis(&self, id: action::Id) -> f64 {
+		self.get(id).map_or(0.0, StateValue::axis_value)
+	}
+}
+
+/// A cheaply cloneable handle to the latest [`StateSnapshot`] of a user.
+/// Acquired via [`User::snapshot_handle`](crate::User::snapshot_handle).
+#[derive(Clone)]
+pub struct SnapshotHandle(Arc<ArcSwap<StateSnapshot>>);
+
+impl Default for SnapshotHandle {
+	fn default() -> Self {
+		Self(Arc::new(ArcSwap::from_pointee(StateSnapshot::default())))
+	}
+}
+
+impl SnapshotHandle {
+	/// Returns the most recently published snapshot. This never blocks on the user's lock.
+	pub fn latest(&self) -> Arc<StateSnapshot> {
+		self.0.load_full()
+	}
+
+	pub(crate) fn publish(&self, snapshot: StateSnapshot) {
+		self.0.store(Arc::new(snapshot));
+	}
+}
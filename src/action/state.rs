@@ -1,38 +1,76 @@
-use crate::{action::BehaviorBinding, binding, event};
+use crate::{
+	action::{self, behavior::Context, BehaviorBinding, Event, Time},
+	binding, event,
+	snapshot::{self, ButtonSnapshot},
+	Settings,
+};
 use std::{
 	sync::{Arc, RwLock, Weak},
-	time::Instant,
+	time::Duration,
 };
 
 pub type ArcLockState = Arc<RwLock<State>>;
 pub type WeakLockState = Weak<RwLock<State>>;
 
+/// How long after the last mouse movement or scroll an [`axis value`](State::axis_value) is considered relevant,
+/// when updated with wall-clock time. When updated by ticks, the value is only relevant for the tick it arrived in.
+const AXIS_RELEVANCE: Duration = Duration::from_millis(100);
+
 /// The state of an active action for a given user.
 #[derive(Debug, Clone)]
 pub struct State {
 	behaviors: BehaviorBinding,
 	/// Used to indicate if a button is pressed or released
-	prev_frame_active: bool,
 	active: bool,
-	active_state_changed_this_frame: bool,
+	/// Set when the button is pressed/released, and consumed by the next update.
+	/// This ensures that edges are never lost, even if the button is pressed and released between updates.
+	press_latched: bool,
+	release_latched: bool,
+	pressed_this_update: bool,
+	released_this_update: bool,
 	value: f64,
-	modified_at: Instant,
-	last_update_time: Instant,
+	/// Set when the value came from a mouse movement or scroll, which only lasts until the mouse stops sending input,
+	/// unlike buttons and gamepad axes which keep their value until they change.
+	is_transient: bool,
+	modified_at: Time,
+	last_update_time: Time,
+	/// The actuation and axis value as of the last time [`events`](Event) were emitted.
+	reported_actuated: bool,
+	reported_value: f64,
 }
 
 impl State {
 	pub(crate) fn new(behaviors: BehaviorBinding) -> Self {
 		Self {
 			behaviors,
-			prev_frame_active: false,
 			active: false,
-			active_state_changed_this_frame: false,
+			press_latched: false,
+			release_latched: false,
+			pressed_this_update: false,
+			released_this_update: false,
 			value: 0.0,
-			modified_at: Instant::now(),
-			last_update_time: Instant::now(),
+			is_transient: false,
+			modified_at: Time::now(),
+			last_update_time: Time::now(),
+			reported_actuated: false,
+			reported_value: 0.0,
 		}
 	}
 
+	/// Replaces the bindings of the action, keeping its current state.
+	pub(crate) fn set_behaviors(&mut self, behaviors: BehaviorBinding) {
+		self.behaviors = behaviors;
+	}
+
+	/// Discards the state of the action as if it had never received input, keeping its bindings.
+	pub(crate) fn reset(&mut self) {
+		*self = Self::new(self.behaviors.clone());
+	}
+
+	pub(crate) fn behaviors(&self) -> &BehaviorBinding {
+		&self.behaviors
+	}
+
 	pub(crate) fn arclocked(self) -> ArcLockState {
 		Arc::new(RwLock::new(self))
 	}
@@ -41,27 +79,41 @@ impl State {
 		&mut self,
 		source: binding::Source,
 		event: event::State,
-		time: &Instant,
+		time: &Time,
 		screen_size: (f64, f64),
+		settings: &Settings,
 	) {
+		let context = Context {
+			source,
+			time,
+			screen_size,
+			settings,
+		};
+		self.is_transient = matches!(
+			event,
+			event::State::MouseMove(_) | event::State::MouseScroll(_)
+		);
 		if match event {
 			event::State::ButtonState(btn_state) => {
 				let is_active = btn_state == event::ButtonState::Pressed;
 
 				let value = if is_active { 1.0 } else { 0.0 };
-				self.value = self.behaviors.process(source, value, &time, &screen_size);
+				self.value = self.behaviors.process(value, &context);
 
 				if self.active != is_active {
 					self.active = is_active;
+					if is_active {
+						self.press_latched = true;
+					} else {
+						self.release_latched = true;
+					}
 					true
 				} else {
 					false
 				}
 			}
 			event::State::MouseMove(delta_pixels) => {
-				self.value = self
-					.behaviors
-					.process(source, delta_pixels, &time, &screen_size);
+				self.value = self.behaviors.process(delta_pixels, &context);
 				true
 			}
 			event::State::MouseScroll(delta) => {
@@ -69,7 +121,7 @@ impl State {
 				true
 			}
 			event::State::ValueChanged(value) => {
-				self.value = value as f64;
+				self.value = self.behaviors.process(value as f64, &context);
 				true
 			}
 		} {
@@ -81,37 +133,86 @@ impl State {
 		true //self.behavior.digital_axis().is_some()
 	}
 
-	pub(crate) fn update(&mut self, time: &Instant) {
+	pub(crate) fn update(&mut self, time: &Time) {
 		self.last_update_time = *time;
+		// Ticks may be sent to other machines as snapshots, so values are kept at the precision snapshots have.
+		if let Time::Tick { .. } = time {
+			self.value = snapshot::quantize_axis(self.value);
+		}
+
+		self.pressed_this_update = self.press_latched;
+		self.released_this_update = self.release_latched;
+		self.press_latched = false;
+		self.release_latched = false;
+	}
+
+	/// Returns the [`events`](Event) which describe how the action has changed since the last time this was called.
+	pub(crate) fn take_events(&mut self) -> Vec<Event> {
+		let mut events = Vec::new();
+		let value = self.axis_value();
+		let actuated = self.active || value != 0.0;
+		if self.pressed_this_update || (actuated && !self.reported_actuated) {
+			events.push(Event::Started);
+		}
+		if value != self.reported_value {
+			events.push(Event::ValueChanged(value));
+		}
+		if self.released_this_update || (!actuated && self.reported_actuated) {
+			events.push(Event::Performed);
+		}
+		self.reported_actuated = actuated;
+		self.reported_value = value;
+		events
+	}
 
-		self.active_state_changed_this_frame = self.active != self.prev_frame_active;
-		if self.active_state_changed_this_frame {
-			self.prev_frame_active = self.active;
+	/// Copies the readable values of the state for a [`StateSnapshot`](action::StateSnapshot).
+	pub(crate) fn value_snapshot(&self) -> action::StateValue {
+		action::StateValue {
+			down: self.active,
+			pressed: self.pressed_this_update,
+			released: self.released_this_update,
+			value: self.value,
+			axis_value: self.axis_value(),
 		}
 	}
 
-	/// Returns true when a [`button binding`](crate::source::Kind::Button) is pressed,
-	/// and this function is called in the same update frame as the input event which pressed the button.
+	/// Returns true if the action is held or has a non-zero value, as of the last time events were emitted.
+	pub(crate) fn is_actuated(&self) -> bool {
+		self.reported_actuated
+	}
+
+	/// Returns true when a [`button binding`](crate::source::Kind::Button) was pressed since the previous update
+	/// (frame or tick), and this function is called before the next update.
 	pub fn on_button_pressed(&self) -> bool {
-		self.active && self.active_state_changed_this_frame
+		self.pressed_this_update
 	}
 
 	pub fn is_button_down(&self) -> bool {
 		self.active
 	}
 
-	/// Returns true when a [`button binding`](crate::source::Kind::Button) is not pressed,
-	/// and this function is called in the same update frame as the input event which released the button.
+	/// Returns true when a [`button binding`](crate::source::Kind::Button) was released since the previous update
+	/// (frame or tick), and this function is called before the next update.
 	pub fn on_button_released(&self) -> bool {
-		!self.active && self.active_state_changed_this_frame
+		self.released_this_update
 	}
 
 	// TODO: Mouse inputs should have behavioral options similar to those described in:
 	// https://guavaman.com/projects/rewired/docs/RewiredEditor.html#InputBehaviors
+	/// Returns the value of the action as of the last update.
+	/// Buttons and gamepad axes keep their value until they change,
+	/// while mouse movement and scrolling return to 0 shortly after the mouse stops (or on the next tick).
 	pub fn axis_value(&self) -> f64 {
-		let time_since_modified = self.last_update_time.duration_since(self.modified_at);
-		let secs_since_modified = time_since_modified.as_secs_f64();
-		let is_relevant = secs_since_modified < 0.1;
+		let is_relevant = match (self.last_update_time, self.modified_at) {
+			_ if !self.is_transient => true,
+			(
+				Time::Tick { index, .. },
+				Time::Tick {
+					index: modified, ..
+				},
+			) => index == modified,
+			(now, modified) => now.duration_since(&modified) < AXIS_RELEVANCE,
+		};
 		if is_relevant {
 			self.value
 		} else {
@@ -119,6 +220,40 @@ impl State {
 		}
 	}
 
+	/// Returns the state of the button (as of the last update) for a [`snapshot`](crate::snapshot::Snapshot).
+	pub(crate) fn button_snapshot(&self) -> ButtonSnapshot {
+		ButtonSnapshot {
+			down: self.active,
+			pressed: self.pressed_this_update,
+			released: self.released_this_update,
+		}
+	}
+
+	/// Overwrites the button state with one from a [`snapshot`](crate::snapshot::Snapshot),
+	/// as if the input had been processed and updated locally.
+	pub(crate) fn apply_button_snapshot(&mut self, button: &ButtonSnapshot, time: &Time) {
+		if self.active != button.down {
+			self.modified_at = *time;
+		}
+		self.active = button.down;
+		self.value = if button.down { 1.0 } else { 0.0 };
+		self.is_transient = false;
+		self.press_latched = false;
+		self.release_latched = false;
+		self.pressed_this_update = button.pressed;
+		self.released_this_update = button.released;
+		self.last_update_time = *time;
+	}
+
+	/// Overwrites the axis value with one from a [`snapshot`](crate::snapshot::Snapshot),
+	/// as if the input had been processed and updated locally.
+	pub(crate) fn apply_axis_snapshot(&mut self, value: f64, time: &Time) {
+		self.value = value;
+		self.is_transient = false;
+		self.modified_at = *time;
+		self.last_update_time = *time;
+	}
+
 	pub fn value(&self) -> f64 {
 		self.value
 	}
@@ -129,3 +264,128 @@ impl State {
 		v
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		device::GamepadKind,
+		source::{Axis, Key},
+	};
+	use std::time::Instant;
+
+	fn process(state: &mut State, source: binding::Source, event: event::State, time: &Time) {
+		state.process_event(source, event, time, (1920.0, 1080.0), &Settings::default());
+	}
+
+	fn stick() -> binding::Source {
+		binding::Source::Gamepad(
+			GamepadKind::DualAxisGamepad,
+			binding::Gamepad::Axis(Axis::LThumbstickX),
+		)
+	}
+
+	#[test]
+	fn held_axes_and_buttons_keep_their_value() {
+		let start = Instant::now();
+		let at = |millis| Time::Instant(start + Duration::from_millis(millis));
+		let mut axis = State::new(stick().into());
+		process(&mut axis, stick(), event::State::ValueChanged(1.0), &at(0));
+		let space = binding::Source::Keyboard(Key::Space);
+		let mut button = State::new(space.into());
+		let pressed = event::State::ButtonState(event::ButtonState::Pressed);
+		process(&mut button, space, pressed, &at(0));
+
+		let mut axis_events = Vec::new();
+		let mut button_events = Vec::new();
+		for millis in (0..=300).step_by(50) {
+			axis.update(&at(millis));
+			axis_events.extend(axis.take_events());
+			button.update(&at(millis));
+			button_events.extend(button.take_events());
+		}
+		assert_eq!(axis.axis_value(), 1.0);
+		assert!(matches!(
+			axis_events.as_slice(),
+			[Event::Started, Event::ValueChanged(value)] if *value == 1.0
+		));
+		assert!(matches!(
+			button_events.as_slice(),
+			[Event::Started, Event::ValueChanged(value)] if *value == 1.0
+		));
+	}
+
+	#[test]
+	fn mouse_movement_stops_when_the_mouse_does() {
+		let start = Instant::now();
+		let at = |millis| Time::Instant(start + Duration::from_millis(millis));
+		let mouse = binding::Source::Mouse(binding::Mouse::Move(binding::MouseAxis::MouseX));
+		let mut state = State::new(mouse.into());
+		process(&mut state, mouse, event::State::MouseMove(5.0), &at(0));
+		state.update(&at(0));
+		assert_ne!(state.axis_value(), 0.0);
+		assert!(matches!(state.take_events().first(), Some(Event::Started)));
+		state.update(&at(200));
+		assert_eq!(state.axis_value(), 0.0);
+		let events = state.take_events();
+		assert!(matches!(events.last(), Some(Event::Performed)));
+	}
+
+	fn tick(index: u64) -> Time {
+		Time::Tick {
+			index,
+			delta: Duration::from_millis(16),
+		}
+	}
+
+	#[test]
+	fn edges_between_ticks_are_latched_until_the_next_tick() {
+		let space = binding::Source::Keyboard(Key::Space);
+		let mut state = State::new(space.into());
+		let pressed = event::State::ButtonState(event::ButtonState::Pressed);
+		let released = event::State::ButtonState(event::ButtonState::Released);
+		process(&mut state, space, pressed, &tick(1));
+		process(&mut state, space, released, &tick(1));
+		state.update(&tick(1));
+		assert!(state.on_button_pressed() && state.on_button_released());
+		assert!(!state.is_button_down());
+
+		state.update(&tick(2));
+		assert!(!state.on_button_pressed() && !state.on_button_released());
+	}
+
+	#[test]
+	fn held_buttons_only_press_on_the_first_tick() {
+		let space = binding::Source::Keyboard(Key::Space);
+		let mut state = State::new(space.into());
+		let pressed = event::State::ButtonState(event::ButtonState::Pressed);
+		process(&mut state, space, pressed, &tick(1));
+		let mut presses = 0;
+		for index in 1..=5 {
+			state.update(&tick(index));
+			assert!(state.is_button_down());
+			if state.on_button_pressed() {
+				presses += 1;
+			}
+		}
+		assert_eq!(presses, 1);
+	}
+
+	#[test]
+	fn mouse_movement_lasts_for_the_tick_it_arrived_in() {
+		let mouse = binding::Source::Mouse(binding::Mouse::Move(binding::MouseAxis::MouseX));
+		let mut state = State::new(mouse.into());
+		process(&mut state, mouse, event::State::MouseMove(5.0), &tick(3));
+		state.update(&tick(3));
+		assert_ne!(state.axis_value(), 0.0);
+		state.update(&tick(4));
+		assert_eq!(state.axis_value(), 0.0);
+	}
+
+	#[test]
+	fn tick_durations_are_measured_in_deltas() {
+		assert_eq!(tick(5).duration_since(&tick(2)), Duration::from_millis(48));
+		assert_eq!(tick(2).duration_since(&tick(5)), Duration::ZERO);
+		assert_eq!(tick(5).duration_since(&Time::now()), Duration::ZERO);
+	}
+}
@@ -0,0 +1,39 @@
+use std::time::{Duration, Instant};
+
+/// The point in time at which inputs are processed and action states are updated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Time {
+	/// Wall-clock time, used when a user is updated once per render frame via [`User::update`](crate::User::update).
+	Instant(Instant),
+	/// A fixed simulation step, used when a user is updated via [`User::update_tick`](crate::User::update_tick).
+	Tick {
+		index: u64,
+		/// The amount of simulated time that passes each tick.
+		delta: Duration,
+	},
+}
+
+impl Time {
+	pub fn now() -> Self {
+		Self::Instant(Instant::now())
+	}
+
+	/// Returns the amount of time which has passed since `earlier`.
+	/// For ticks, this is the number of ticks multiplied by the tick delta.
+	/// Returns zero if `earlier` is later than `self` or if the two times are not measured in the same way.
+	pub fn duration_since(&self, earlier: &Time) -> Duration {
+		match (*self, *earlier) {
+			(Self::Instant(now), Self::Instant(earlier)) => now.saturating_duration_since(earlier),
+			(Self::Tick { index, delta }, Self::Tick { index: earlier, .. }) => {
+				delta.mul_f64(index.saturating_sub(earlier) as f64)
+			}
+			_ => Duration::ZERO,
+		}
+	}
+}
+
+impl From<Instant> for Time {
+	fn from(instant: Instant) -> Self {
+		Self::Instant(instant)
+	}
+}
@@ -0,0 +1,548 @@
+use crate::{
+	action, binding, device, event, join,
+	keymap::{self, key_from_code, key_from_named, key_from_native, PIXELS_PER_LINE},
+	source::{Key, MouseButton},
+	text, ArcLockConfig, ArcLockUser, Config, DeviceCache, User, UserId,
+};
+use bevy::{
+	app::{App, Plugin, PreUpdate},
+	ecs::{
+		component::Component,
+		message::MessageReader,
+		query::With,
+		resource::Resource,
+		schedule::{IntoScheduleConfigs, SystemSet},
+		system::{NonSendMut, Query, ResMut},
+	},
+	input::{
+		keyboard::{Key as LogicalKey, KeyCode, KeyboardInput, NativeKeyCode},
+		mouse::{MouseButtonInput, MouseMotion, MouseScrollUnit, MouseWheel},
+		ButtonState, InputSystems,
+	},
+	window::{Ime, PrimaryWindow, Window, WindowFocused, WindowResized, WindowScaleFactorChanged},
+};
+use std::{
+	sync::{Arc, Mutex, RwLock},
+	time::Instant,
+};
+
+/// Adds a [`DeviceCache`] and users to a Bevy app.
+///
+/// Each frame (in `PreUpdate`, after Bevy has read its input) Bevy's keyboard, mouse, and window messages
+/// are sent to the device cache, and then the users are updated and their action states are published
+/// to the [`ActionStates`] resource and each [`ActionUser`] component.
+/// Requires Bevy's `InputPlugin` and `WindowPlugin` (both part of `DefaultPlugins`).
+///
+/// Gilrs is not thread-safe, so the device cache is a non-send resource (accessed via `NonSendMut<DeviceCache>`).
+///
+/// ```rust,ignore
+/// App::new()
+///     .add_plugins(DefaultPlugins)
+///     .add_plugins(InputActionsPlugin::new(config).with_action_set(ActionSetId::default()))
+///     .add_systems(Update, |states: Res<ActionStates>| {
+///         if states.user(0).pressed("jump") {
+///             // ...
+///         }
+///     });
+/// ```
+pub struct InputActionsPlugin {
+	config: ArcLockConfig,
+	user_names: Vec<String>,
+	action_sets: Vec<binding::ActionSetId>,
+	gamepad_backend: device::GamepadBackend,
+	/// Taken when the plugin is built, since the user factory can't be cloned.
+	join: Mutex<Option<join::Join>>,
+}
+
+impl InputActionsPlugin {
+	pub fn new(config: Config) -> Self {
+		Self {
+			config: Arc::new(RwLock::new(config)),
+			user_names: Vec::new(),
+			action_sets: Vec::new(),
+			gamepad_backend: device::GamepadBackend::default(),
+			join: Mutex::new(None),
+		}
+	}
+
+	/// Adds a user, whose [`UserId`] is the number of users added before it.
+	/// If no users are added, a single user is created.
+	pub fn with_user(mut self, name: impl Into<String>) -> Self {
+		self.user_names.push(name.into());
+		self
+	}
+
+	/// Enables an action set for every user.
+	pub fn with_action_set(mut self, id: binding::ActionSetId) -> Self {
+		self.action_sets.push(id);
+		self
+	}
+
+	/// Sets where the device cache gets gamepad input from (gilrs by default).
+	pub fn with_gamepad_backend(mut self, backend: device::GamepadBackend) -> Self {
+		self.gamepad_backend = backend;
+		self
+	}
+
+	/// Puts the device cache in [`join mode`](DeviceCache::with_join). Users created for players who join
+	/// are added to [`InputUsers`] and [`ActionStates`] after the users added via [`with_user`](InputActionsPlugin::with_user).
+	pub fn with_join(self, join: join::Join) -> Self {
+		*self.join.lock().unwrap() = Some(join);
+		self
+	}
+}
+
+impl Plugin for InputActionsPlugin {
+	/// # Panics
+	/// If the gamepad backend could not be initialized. See [`DeviceCache::with_gamepad_backend`].
+	fn build(&self, app: &mut App) {
+		let mut device_cache = DeviceCache::with_gamepad_backend(self.gamepad_backend)
+			.unwrap_or_else(|error| panic!("{}", error));
+		if let Some(join) = self.join.lock().unwrap().take() {
+			device_cache = device_cache.with_join(join);
+		}
+		let user_names = match self.user_names.is_empty() {
+			true => vec!["user".to_owned()],
+			false => self.user_names.clone(),
+		};
+		let users = user_names
+			.into_iter()
+			.map(|name| {
+				let mut user = User::new(name)
+					.with_config(Arc::downgrade(&self.config))
+					.with_consts(device_cache.consts());
+				for id in self.action_sets.iter() {
+					user.enable_action_set(*id);
+				}
+				let arc_user = user.arclocked();
+				device_cache.add_user(Arc::downgrade(&arc_user));
+				arc_user
+			})
+			.collect::<Vec<_>>();
+		app.insert_non_send_resource(device_cache)
+			.insert_resource(ActionStates::new(users.len()))
+			.insert_resource(InputUsers {
+				config: self.config.clone(),
+				users: users.into_iter().map(Some).collect(),
+			})
+			.add_systems(
+				PreUpdate,
+				(send_window_events, send_input_events, update_users)
+					.chain()
+					.in_set(InputActionsSystems)
+					.after(InputSystems),
+			);
+	}
+}
+
+/// The systems which send Bevy's input to the [`DeviceCache`] and update the users, in `PreUpdate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
+pub struct InputActionsSystems;
+
+/// The configuration and users owned by the [`InputActionsPlugin`], including users created for players who join.
+/// Lock a user to change its settings, rebind actions, or start text input.
+#[derive(Resource)]
+pub struct InputUsers {
+	config: ArcLockConfig,
+	/// By [`UserId`]. Users who were dropped before the plugin saw them keep their place as `None`,
+	/// so the ids of later users still match the device cache's.
+	users: Vec<Option<ArcLockUser>>,
+}
+
+impl InputUsers {
+	pub fn config(&self) -> &ArcLockConfig {
+		&self.config
+	}
+
+	pub fn get(&self, id: UserId) -> Option<&ArcLockUser> {
+		self.users.get(id).and_then(Option::as_ref)
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &ArcLockUser> {
+		self.users.iter().flatten()
+	}
+}
+
+/// The state of every user's actions as of the most recent update, by [`UserId`].
+#[derive(Resource, Default)]
+pub struct ActionStates {
+	users: Vec<Arc<action::StateSnapshot>>,
+	/// Returned for users which do not exist, so their actions are never pressed.
+	empty: action::StateSnapshot,
+}
+
+impl ActionStates {
+	fn new(user_count: usize) -> Self {
+		Self {
+			users: vec![Arc::default(); user_count],
+			empty: action::StateSnapshot::default(),
+		}
+	}
+
+	/// Returns the action states of a user, which are all released if the user does not exist.
+	pub fn user(&self, id: UserId) -> &action::StateSnapshot {
+		self.users
+			.get(id)
+			.map_or(&self.empty, |states| states.as_ref())
+	}
+}
+
+/// The action states of a user, for an entity controlled by that user (like a player character).
+/// Updated alongside [`ActionStates`].
+#[derive(Component, Clone)]
+pub struct ActionUser {
+	id: UserId,
+	states: Arc<action::StateSnapshot>,
+}
+
+impl ActionUser {
+	pub fn new(id: UserId) -> Self {
+		Self {
+			id,
+			states: Arc::default(),
+		}
+	}
+
+	pub fn id(&self) -> UserId {
+		self.id
+	}
+}
+
+impl std::ops::Deref for ActionUser {
+	type Target = action::StateSnapshot;
+	fn deref(&self) -> &Self::Target {
+		&self.states
+	}
+}
+
+/// Sends the size of the primary window (in physical pixels) whenever it is resized or rescaled,
+/// and whether any window is focused.
+fn send_window_events(
+	mut device_cache: NonSendMut<DeviceCache>,
+	mut resized: MessageReader<WindowResized>,
+	mut rescaled: MessageReader<WindowScaleFactorChanged>,
+	mut focused: MessageReader<WindowFocused>,
+	primary_window: Query<&Window, With<PrimaryWindow>>,
+) {
+	let resized = resized.read().count() > 0;
+	let rescaled = rescaled.read().count() > 0;
+	if resized || rescaled {
+		if let Ok(window) = primary_window.single() {
+			device_cache.send_event(event::Event::Window(
+				event::WindowEvent::ScaleFactorChanged(
+					window.physical_width(),
+					window.physical_height(),
+					window.scale_factor() as f64,
+				),
+			));
+		}
+	}
+	for message in focused.read() {
+		device_cache.send_event(event::Event::Window(event::WindowEvent::Focused(
+			message.focused,
+		)));
+	}
+}
+
+fn send_input_events(
+	mut device_cache: NonSendMut<DeviceCache>,
+	mut keyboard: MessageReader<KeyboardInput>,
+	mut ime: MessageReader<Ime>,
+	mut mouse_buttons: MessageReader<MouseButtonInput>,
+	mut mouse_motion: MessageReader<MouseMotion>,
+	mut mouse_wheel: MessageReader<MouseWheel>,
+) {
+	for message in keyboard.read() {
+		for input_event in key_events(message) {
+			device_cache.send_event(input_event);
+		}
+	}
+	for message in ime.read() {
+		let text_input = match message {
+			Ime::Preedit { value, cursor, .. } => text::TextInput::Preedit {
+				text: value.clone(),
+				cursor: *cursor,
+			},
+			Ime::Commit { value, .. } => text::TextInput::Commit(value.clone()),
+			_ => continue,
+		};
+		device_cache.send_event(event::Event::Text(text_input));
+	}
+	for message in mouse_buttons.read() {
+		let button = match message.button {
+			bevy::input::mouse::MouseButton::Left => MouseButton::Left,
+			bevy::input::mouse::MouseButton::Middle => MouseButton::Center,
+			bevy::input::mouse::MouseButton::Right => MouseButton::Right,
+			_ => continue,
+		};
+		device_cache.send_event(mouse_event(
+			binding::Mouse::Button(button),
+			event::State::ButtonState(button_state(message.state)),
+		));
+	}
+	for message in mouse_motion.read() {
+		if message.delta.x != 0.0 {
+			device_cache.send_event(mouse_event(
+				binding::Mouse::Move(binding::MouseAxis::MouseX),
+				event::State::MouseMove(message.delta.x as f64),
+			));
+		}
+		if message.delta.y != 0.0 {
+			device_cache.send_event(mouse_event(
+				binding::Mouse::Move(binding::MouseAxis::MouseY),
+				event::State::MouseMove(message.delta.y as f64),
+			));
+		}
+	}
+	for message in mouse_wheel.read() {
+		let (horizontal, vertical) = match message.unit {
+			MouseScrollUnit::Line => (message.x, message.y),
+			MouseScrollUnit::Pixel => (
+				message.x / PIXELS_PER_LINE as f32,
+				message.y / PIXELS_PER_LINE as f32,
+			),
+		};
+		if horizontal != 0.0 {
+			device_cache.send_event(mouse_event(
+				binding::Mouse::Scroll(binding::MouseAxis::MouseX),
+				event::State::MouseScroll(horizontal),
+			));
+		}
+		if vertical != 0.0 {
+			device_cache.send_event(mouse_event(
+				binding::Mouse::Scroll(binding::MouseAxis::MouseY),
+				event::State::MouseScroll(vertical),
+			));
+		}
+	}
+}
+
+fn update_users(
+	mut device_cache: NonSendMut<DeviceCache>,
+	mut users: ResMut<InputUsers>,
+	mut action_states: ResMut<ActionStates>,
+	mut action_users: Query<&mut ActionUser>,
+) {
+	device_cache.update();
+	// Users added to the device cache after the plugin's users (like those created for players who join)
+	// are added in order, even if they were dropped, so that each user's index is its id.
+	let known_users = users.users.len();
+	for weak_user in device_cache.users().into_iter().skip(known_users) {
+		users.users.push(weak_user.upgrade());
+	}
+	action_states
+		.users
+		.resize(users.users.len(), Arc::default());
+	let now = Instant::now();
+	for (id, arc_user) in users.users.iter().enumerate() {
+		// Dropped users' actions stay released.
+		let arc_user = match arc_user {
+			Some(arc_user) => arc_user,
+			None => continue,
+		};
+		let mut user = arc_user.write().unwrap();
+		user.update(&now);
+		action_states.users[id] = user.snapshot_handle().latest();
+	}
+	for mut action_user in action_users.iter_mut() {
+		if let Some(states) = action_states.users.get(action_user.id) {
+			action_user.states = states.clone();
+		}
+	}
+}
+
+fn key_events(message: &KeyboardInput) -> Vec<event::Event> {
+	let physical = physical_key(message.key_code);
+	keymap::key_events(
+		physical,
+		logical_key(&message.logical_key, physical),
+		None,
+		button_state(message.state),
+		message.repeat,
+		message.text.as_deref(),
+	)
+}
+
+fn button_state(state: ButtonState) -> event::ButtonState {
+	match state {
+		ButtonState::Pressed => event::ButtonState::Pressed,
+		ButtonState::Released => event::ButtonState::Released,
+	}
+}
+
+fn mouse_event(mouse: binding::Mouse, state: event::State) -> event::Event {
+	event::Event::Input(device::Id::Mouse, binding::Source::Mouse(mouse), state)
+}
+
+/// Returns the key which typed the logical key on a US-QWERTY keyboard.
+/// Bevy does not report which side of the keyboard a key is on, so modifier and numpad keys are always their physical key.
+fn logical_key(key: &LogicalKey, physical: Option<Key>) -> Option<Key> {
+	if physical.is_some_and(is_numpad) {
+		return physical;
+	}
+	match key {
+		LogicalKey::Character(text) => keymap::character_key(text),
+		LogicalKey::Alt
+		| LogicalKey::AltGraph
+		| LogicalKey::Control
+		| LogicalKey::Shift
+		| LogicalKey::Super
+		| LogicalKey::Meta => physical,
+		_ => key_from_named!(LogicalKey, key),
+	}
+}
+
+fn is_numpad(key: Key) -> bool {
+	matches!(
+		key,
+		Key::Numpad0
+			| Key::Numpad1
+			| Key::Numpad2
+			| Key::Numpad3
+			| Key::Numpad4
+			| Key::Numpad5
+			| Key::Numpad6
+			| Key::Numpad7
+			| Key::Numpad8
+			| Key::Numpad9
+			| Key::NumpadPlus
+			| Key::NumpadMinus
+			| Key::NumpadAsterisk
+			| Key::NumpadSlash
+			| Key::NumpadDecimal
+			| Key::NumpadComma
+			| Key::NumpadEquals
+			| Key::NumpadEnter
+	)
+}
+
+/// Returns the key at the position of a Bevy key code on a US-QWERTY keyboard.
+fn physical_key(code: KeyCode) -> Option<Key> {
+	match code {
+		KeyCode::Unidentified(native) => key_from_native!(NativeKeyCode, native),
+		_ => key_from_code!(KeyCode, code),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		binding::{ActionMap, ActionSet, ActionSetId, LayoutId},
+		source::{self, Button},
+	};
+	use bevy::ecs::message::Messages;
+
+	fn gamepad_button(button: Button) -> binding::Source {
+		binding::Source::Gamepad(
+			device::GamepadKind::DualAxisGamepad,
+			binding::Gamepad::Button(button),
+		)
+	}
+
+	fn press(app: &mut App, device: device::Id, source: binding::Source) {
+		let mut device_cache = app.world_mut().non_send_resource_mut::<DeviceCache>();
+		device_cache.send_event(event::Event::Input(
+			device,
+			source,
+			event::State::ButtonState(event::ButtonState::Pressed),
+		));
+	}
+
+	/// Returns an app with a "host" user, in which gamepads join by pressing `RSpecial`.
+	fn joining_app() -> App {
+		let config = Config::default()
+			.add_action("jump", source::Kind::Button)
+			.add_layout(LayoutId::default())
+			.add_action_set(
+				ActionSetId::default(),
+				ActionSet::default().with(
+					LayoutId::default(),
+					ActionMap::default().bind("jump", gamepad_button(Button::FaceBottom)),
+				),
+			);
+		let plugin = InputActionsPlugin::new(config)
+			.with_user("host")
+			.with_action_set(ActionSetId::default())
+			.with_gamepad_backend(device::GamepadBackend::External);
+		let config = plugin.config.clone();
+		let plugin = plugin.with_join(
+			join::Join::default()
+				.with_join_source(gamepad_button(Button::RSpecial))
+				.with_new_users(move |_| {
+					let mut user =
+						User::new("guest".to_owned()).with_config(Arc::downgrade(&config));
+					user.enable_action_set(ActionSetId::default());
+					user
+				}),
+		);
+		let mut app = App::new();
+		app.init_resource::<Messages<KeyboardInput>>()
+			.init_resource::<Messages<Ime>>()
+			.init_resource::<Messages<MouseButtonInput>>()
+			.init_resource::<Messages<MouseMotion>>()
+			.init_resource::<Messages<MouseWheel>>()
+			.init_resource::<Messages<WindowResized>>()
+			.init_resource::<Messages<WindowScaleFactorChanged>>()
+			.init_resource::<Messages<WindowFocused>>()
+			.add_plugins(plugin);
+		app
+	}
+
+	fn join(app: &mut App, gamepad: device::Id) {
+		app.world_mut()
+			.non_send_resource_mut::<DeviceCache>()
+			.send_event(event::Event::DeviceConnected(gamepad));
+		app.update();
+		press(app, gamepad, gamepad_button(Button::RSpecial));
+		app.update();
+	}
+
+	#[test]
+	fn joined_users_have_action_states() {
+		let mut app = joining_app();
+		let (first, second) = (
+			device::Id::Gamepad(device::GamepadKind::DualAxisGamepad, 0),
+			device::Id::Gamepad(device::GamepadKind::DualAxisGamepad, 1),
+		);
+		for gamepad in [first, second] {
+			join(&mut app, gamepad);
+		}
+		assert_eq!(app.world().resource::<InputUsers>().iter().count(), 2);
+
+		press(&mut app, second, gamepad_button(Button::FaceBottom));
+		app.update();
+		let action_states = app.world().resource::<ActionStates>();
+		assert!(action_states.user(1).pressed("jump"));
+		assert!(!action_states.user(0).pressed("jump"));
+	}
+
+	#[test]
+	fn dropped_users_keep_their_id() {
+		let mut app = joining_app();
+		let dropped = User::new("dropped".to_owned()).arclocked();
+		app.world_mut()
+			.non_send_resource_mut::<DeviceCache>()
+			.add_user(Arc::downgrade(&dropped));
+		drop(dropped);
+
+		let (first, second) = (
+			device::Id::Gamepad(device::GamepadKind::DualAxisGamepad, 0),
+			device::Id::Gamepad(device::GamepadKind::DualAxisGamepad, 1),
+		);
+		for gamepad in [first, second] {
+			join(&mut app, gamepad);
+		}
+		let users = app.world().resource::<InputUsers>();
+		assert_eq!(users.iter().count(), 2);
+		assert!(users.get(1).is_none());
+		assert_eq!(users.get(2).unwrap().read().unwrap().name(), "guest");
+
+		press(&mut app, second, gamepad_button(Button::FaceBottom));
+		app.update();
+		let action_states = app.world().resource::<ActionStates>();
+		assert!(action_states.user(2).pressed("jump"));
+		assert!(!action_states.user(1).pressed("jump"));
+		assert!(!action_states.user(0).pressed("jump"));
+	}
+}
@@ -5,14 +5,20 @@ use crate::{
 
 /// Enumeration containing all the possible input sources across all kinds of devices.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Source {
 	Mouse(binding::Mouse),
+	/// A key by its label, which moves with the keyboard layout (the key labeled `Z` is bottom-left on QWERTY but top-left on AZERTY).
 	Keyboard(Key),
+	/// A key by its position, named by the key at that position on a US-QWERTY keyboard.
+	/// `PhysicalKey(W)` is the key above `S` on any layout, so movement bindings stay in place for AZERTY or QWERTZ players.
+	PhysicalKey(Key),
 	Gamepad(device::GamepadKind, binding::Gamepad),
 }
 
 /// All possible inputs from a mouse device.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Mouse {
 	Button(MouseButton),
 	Move(MouseAxis),
@@ -21,13 +27,27 @@ pub enum Mouse {
 
 /// The axes a mouse movement or scroll could be bound to.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MouseAxis {
 	MouseX,
 	MouseY,
 }
 
+impl std::str::FromStr for MouseAxis {
+	type Err = ();
+	/// Parses the name of a variant (as written in the source code).
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		match name {
+			"MouseX" => Ok(Self::MouseX),
+			"MouseY" => Ok(Self::MouseY),
+			_ => Err(()),
+		}
+	}
+}
+
 /// All possible inputs from a gamepad device.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Gamepad {
 	Button(Button),
 	Axis(Axis),
@@ -39,6 +59,7 @@ impl Source {
 		match *self {
 			Self::Mouse(_) => source::Kind::Button,
 			Self::Keyboard(_) => source::Kind::Button,
+			Self::PhysicalKey(_) => source::Kind::Button,
 			Self::Gamepad(_, binding::Gamepad::Button(_)) => source::Kind::Button,
 			Self::Gamepad(_, binding::Gamepad::Axis(_)) => source::Kind::Axis,
 		}
@@ -48,7 +69,7 @@ impl Source {
 	pub fn device_kind(&self) -> device::Kind {
 		match *self {
 			Self::Mouse(_) => device::Kind::Mouse,
-			Self::Keyboard(_) => device::Kind::Keyboard,
+			Self::Keyboard(_) | Self::PhysicalKey(_) => device::Kind::Keyboard,
 			Self::Gamepad(gamepad, _) => device::Kind::Gamepad(gamepad),
 		}
 	}
@@ -32,4 +32,8 @@ impl ActionSet {
 	pub(crate) fn get(&self, layout: &LayoutId) -> Option<&ActionMap> {
 		self.0.get(layout)
 	}
+
+	pub(crate) fn iter(&self) -> std::collections::hash_map::Iter<'_, LayoutId, ActionMap> {
+		self.0.iter()
+	}
 }
@@ -0,0 +1,433 @@
+use crate::{
+	binding, device, event,
+	source::{Key, MouseButton},
+	text,
+};
+use crossterm::event::{
+	Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MediaKeyCode,
+	ModifierKeyCode, MouseEvent, MouseEventKind,
+};
+use std::{
+	collections::HashMap,
+	time::{Duration, Instant},
+};
+
+/// Converts crossterm's terminal events into events for the [`DeviceCache`](crate::DeviceCache).
+///
+/// Most terminals only report when a key is pressed (and again as it repeats), not when it is released.
+/// Until a release is reported, keys are released once they have not been pressed or repeated
+/// for the [`release delay`](Terminal::with_release_delay), so call [`update`](Terminal::update) each frame.
+///
+/// The terminal is measured in cells, so the resolution is its size in columns and rows,
+/// and the mouse moves by the number of cells it crossed.
+#[derive(Debug)]
+pub struct Terminal {
+	/// The keys which are pressed, and when they were last pressed or repeated.
+	held_keys: HashMap<Key, Instant>,
+	release_delay: Duration,
+	/// True once the terminal has reported a key release, so releases are no longer synthesized.
+	reports_releases: bool,
+	/// True once the terminal has reported a modifier key, so they are no longer pressed via the modifiers of other keys.
+	reports_modifiers: bool,
+	mouse_position: Option<(u16, u16)>,
+}
+
+impl Default for Terminal {
+	fn default() -> Self {
+		Self {
+			held_keys: HashMap::new(),
+			release_delay: Duration::from_millis(500),
+			reports_releases: false,
+			reports_modifiers: false,
+			mouse_position: None,
+		}
+	}
+}
+
+impl Terminal {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets how long a key is held after being pressed, if the terminal does not report releases.
+	/// Should be longer than the delay before the terminal starts repeating keys, so held keys are not released.
+	pub fn with_release_delay(mut self, delay: Duration) -> Self {
+		self.release_delay = delay;
+		self
+	}
+
+	/// Parses a terminal event. A single terminal event can be sent as several events
+	/// (like a key with modifiers, or a mouse moving along both axes).
+	pub fn parse_event(&mut self, event: &Event) -> Vec<event::Event> {
+		match event {
+			Event::Key(key_event) => self.parse_key_event(key_event),
+			Event::Mouse(mouse_event) => self.parse_mouse_event(mouse_event),
+			Event::Resize(columns, rows) => vec![event::Event::Window(
+				event::WindowEvent::ResolutionChanged(*columns as u32, *rows as u32),
+			)],
+			Event::FocusGained => vec![event::Event::Window(event::WindowEvent::Focused(true))],
+			Event::FocusLost => vec![event::Event::Window(event::WindowEvent::Focused(false))],
+			Event::Paste(text) => vec![event::Event::Text(text::TextInput::Commit(text.clone()))],
+		}
+	}
+
+	/// Releases the keys which have been held longer than the release delay,
+	/// if the terminal does not report releases. Call once per frame, before updating the users.
+	pub fn update(&mut self, time: &Instant) -> Vec<event::Event> {
+		if self.reports_releases {
+			return Vec::new();
+		}
+		let release_delay = self.release_delay;
+		let mut events = Vec::new();
+		self.held_keys.retain(|key, pressed_at| {
+			if time.saturating_duration_since(*pressed_at) < release_delay {
+				return true;
+			}
+			events.push(key_state(*key, event::ButtonState::Released));
+			false
+		});
+		events
+	}
+
+	fn parse_key_event(&mut self, key_event: &KeyEvent) -> Vec<event::Event> {
+		let now = Instant::now();
+		let mut events = Vec::new();
+		if let KeyCode::Modifier(_) = key_event.code {
+			self.reports_modifiers = true;
+		}
+		if !self.reports_modifiers {
+			self.press_modifiers(key_event.modifiers, now, &mut events);
+		}
+		let key = key_code_key(key_event.code, key_event.state);
+		match key_event.kind {
+			KeyEventKind::Release => {
+				self.reports_releases = true;
+				if let Some(key) = key {
+					if self.held_keys.remove(&key).is_some() {
+						events.push(key_state(key, event::ButtonState::Released));
+					}
+				}
+			}
+			// Terminals which don't report event kinds send repeats as presses.
+			KeyEventKind::Press | KeyEventKind::Repeat => {
+				if let Some(key) = key {
+					if self.held_keys.insert(key, now).is_none() {
+						events.push(key_state(key, event::ButtonState::Pressed));
+					}
+				}
+				let typing = !key_event
+					.modifiers
+					.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+				if let KeyCode::Char(c) = key_event.code {
+					if typing && !c.is_control() {
+						events.push(event::Event::Text(text::TextInput::Char(c)));
+					}
+				}
+			}
+		}
+		events
+	}
+
+	/// Presses the modifier keys which are held during a key event, and releases those which are not,
+	/// for terminals which only report modifiers alongside other keys.
+	fn press_modifiers(
+		&mut self,
+		modifiers: KeyModifiers,
+		now: Instant,
+		events: &mut Vec<event::Event>,
+	) {
+		for (modifier, key) in [
+			(KeyModifiers::SHIFT, Key::LShift),
+			(KeyModifiers::CONTROL, Key::LControl),
+			(KeyModifiers::ALT, Key::LAlt),
+		] {
+			if modifiers.contains(modifier) {
+				if self.held_keys.insert(key, now).is_none() {
+					events.push(key_state(key, event::ButtonState::Pressed));
+				}
+			} else if self.held_keys.remove(&key).is_some() {
+				events.push(key_state(key, event::ButtonState::Released));
+			}
+		}
+	}
+
+	fn parse_mouse_event(&mut self, mouse_event: &MouseEvent) -> Vec<event::Event> {
+		let mut events = Vec::new();
+		let position = (mouse_event.column, mouse_event.row);
+		if let Some((column, row)) = self.mouse_position.replace(position) {
+			let (delta_x, delta_y) = (
+				position.0 as f64 - column as f64,
+				position.1 as f64 - row as f64,
+			);
+			if delta_x != 0.0 {
+				events.push(mouse_event_state(
+					binding::Mouse::Move(binding::MouseAxis::MouseX),
+					event::State::MouseMove(delta_x),
+				));
+			}
+			if delta_y != 0.0 {
+				events.push(mouse_event_state(
+					binding::Mouse::Move(binding::MouseAxis::MouseY),
+					event::State::MouseMove(delta_y),
+				));
+			}
+		}
+		let (mouse, state) = match mouse_event.kind {
+			MouseEventKind::Down(button) => (
+				binding::Mouse::Button(mouse_button(button)),
+				event::State::ButtonState(event::ButtonState::Pressed),
+			),
+			MouseEventKind::Up(button) => (
+				binding::Mouse::Button(mouse_button(button)),
+				event::State::ButtonState(event::ButtonState::Released),
+			),
+			MouseEventKind::Drag(_) | MouseEventKind::Moved => return events,
+			// Scrolling away from the user is positive, like a line delta in winit.
+			MouseEventKind::ScrollUp => (
+				binding::Mouse::Scroll(binding::MouseAxis::MouseY),
+				event::State::MouseScroll(1.0),
+			),
+			MouseEventKind::ScrollDown => (
+				binding::Mouse::Scroll(binding::MouseAxis::MouseY),
+				event::State::MouseScroll(-1.0),
+			),
+			MouseEventKind::ScrollRight => (
+				binding::Mouse::Scroll(binding::MouseAxis::MouseX),
+				event::State::MouseScroll(1.0),
+			),
+			MouseEventKind::ScrollLeft => (
+				binding::Mouse::Scroll(binding::MouseAxis::MouseX),
+				event::State::MouseScroll(-1.0),
+			),
+		};
+		events.push(mouse_event_state(mouse, state));
+		events
+	}
+}
+
+fn key_state(key: Key, state: event::ButtonState) -> event::Event {
+	// Terminals only report the character of a key, so the physical key is unknown.
+	event::Event::Key {
+		logical: Some(key),
+		scancode: 0,
+		state,
+	}
+}
+
+fn mouse_event_state(mouse: binding::Mouse, state: event::State) -> event::Event {
+	event::Event::Input(device::Id::Mouse, binding::Source::Mouse(mouse), state)
+}
+
+fn mouse_button(button: crossterm::event::MouseButton) -> MouseButton {
+	match button {
+		crossterm::event::MouseButton::Left => MouseButton::Left,
+		crossterm::event::MouseButton::Middle => MouseButton::Center,
+		crossterm::event::MouseButton::Right => MouseButton::Right,
+	}
+}
+
+/// Returns the key which typed a key code on a US-QWERTY keyboard.
+/// Keys on the keypad are only known if the terminal reports the keypad state.
+fn key_code_key(code: KeyCode, state: KeyEventState) -> Option<Key> {
+	if state.contains(KeyEventState::KEYPAD) {
+		let numpad_key = match code {
+			KeyCode::Char('0') => Some(Key::Numpad0),
+			KeyCode::Char('1') => Some(Key::Numpad1),
+			KeyCode::Char('2') => Some(Key::Numpad2),
+			KeyCode::Char('3') => Some(Key::Numpad3),
+			KeyCode::Char('4') => Some(Key::Numpad4),
+			KeyCode::Char('5') => Some(Key::Numpad5),
+			KeyCode::Char('6') => Some(Key::Numpad6),
+			KeyCode::Char('7') => Some(Key::Numpad7),
+			KeyCode::Char('8') => Some(Key::Numpad8),
+			KeyCode::Char('9') => Some(Key::Numpad9),
+			KeyCode::Char('+') => Some(Key::NumpadPlus),
+			KeyCode::Char('-') => Some(Key::NumpadMinus),
+			KeyCode::Char('*') => Some(Key::NumpadAsterisk),
+			KeyCode::Char('/') => Some(Key::NumpadSlash),
+			KeyCode::Char('.') => Some(Key::NumpadDecimal),
+			KeyCode::Char(',') => Some(Key::NumpadComma),
+			KeyCode::Char('=') => Some(Key::NumpadEquals),
+			KeyCode::Enter => Some(Key::NumpadEnter),
+			_ => None,
+		};
+		if numpad_key.is_some() {
+			return numpad_key;
+		}
+	}
+	match code {
+		KeyCode::Backspace => Some(Key::Back),
+		KeyCode::Enter => Some(Key::Return),
+		KeyCode::Left => Some(Key::Left),
+		KeyCode::Right => Some(Key::Right),
+		KeyCode::Up => Some(Key::Up),
+		KeyCode::Down => Some(Key::Down),
+		KeyCode::Home => Some(Key::Home),
+		KeyCode::End => Some(Key::End),
+		KeyCode::PageUp => Some(Key::PageUp),
+		KeyCode::PageDown => Some(Key::PageDown),
+		KeyCode::Tab | KeyCode::BackTab => Some(Key::Tab),
+		KeyCode::Delete => Some(Key::Delete),
+		KeyCode::Insert => Some(Key::Insert),
+		KeyCode::F(number) => function_key(number),
+		KeyCode::Char(c) => Key::from_char(c),
+		KeyCode::Esc => Some(Key::Escape),
+		KeyCode::CapsLock => Some(Key::CapitalLock),
+		KeyCode::ScrollLock => Some(Key::ScrollLock),
+		KeyCode::NumLock => Some(Key::Numlock),
+		KeyCode::PrintScreen => Some(Key::Snapshot),
+		KeyCode::Pause => Some(Key::Pause),
+		KeyCode::Menu => Some(Key::Apps),
+		KeyCode::Media(media) => match media {
+			MediaKeyCode::Play | MediaKeyCode::Pause | MediaKeyCode::PlayPause => {
+				Some(Key::PlayPause)
+			}
+			MediaKeyCode::Stop => Some(Key::MediaStop),
+			MediaKeyCode::TrackNext => Some(Key::NextTrack),
+			MediaKeyCode::TrackPrevious => Some(Key::PrevTrack),
+			MediaKeyCode::LowerVolume => Some(Key::VolumeDown),
+			MediaKeyCode::RaiseVolume => Some(Key::VolumeUp),
+			MediaKeyCode::MuteVolume => Some(Key::Mute),
+			_ => None,
+		},
+		KeyCode::Modifier(modifier) => match modifier {
+			ModifierKeyCode::LeftShift => Some(Key::LShift),
+			ModifierKeyCode::LeftControl => Some(Key::LControl),
+			ModifierKeyCode::LeftAlt => Some(Key::LAlt),
+			ModifierKeyCode::LeftSuper => Some(Key::LWin),
+			ModifierKeyCode::RightShift => Some(Key::RShift),
+			ModifierKeyCode::RightControl => Some(Key::RControl),
+			ModifierKeyCode::RightAlt => Some(Key::RAlt),
+			ModifierKeyCode::RightSuper => Some(Key::RWin),
+			_ => None,
+		},
+		KeyCode::Null | KeyCode::KeypadBegin => None,
+	}
+}
+
+fn function_key(number: u8) -> Option<Key> {
+	use Key::*;
+	const FUNCTION_KEYS: [Key; 24] = [
+		F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, F13, F14, F15, F16, F17, F18, F19, F20,
+		F21, F22, F23, F24,
+	];
+	FUNCTION_KEYS
+		.get((number as usize).checked_sub(1)?)
+		.copied()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn key_states(events: &[event::Event]) -> Vec<(Key, event::ButtonState)> {
+		events
+			.iter()
+			.filter_map(|event| match event {
+				event::Event::Key {
+					logical: Some(key),
+					state,
+					..
+				} => Some((*key, *state)),
+				_ => None,
+			})
+			.collect()
+	}
+
+	fn press(code: KeyCode, modifiers: KeyModifiers) -> Event {
+		Event::Key(KeyEvent::new(code, modifiers))
+	}
+
+	#[test]
+	fn presses_are_released_after_the_delay() {
+		let mut terminal = Terminal::new().with_release_delay(Duration::from_millis(100));
+		let events = terminal.parse_event(&press(KeyCode::Char('a'), KeyModifiers::NONE));
+		assert_eq!(key_states(&events), [(Key::A, event::ButtonState::Pressed)]);
+
+		assert!(terminal.update(&Instant::now()).is_empty());
+		let later = Instant::now() + Duration::from_millis(100);
+		assert_eq!(
+			key_states(&terminal.update(&later)),
+			[(Key::A, event::ButtonState::Released)]
+		);
+		assert!(terminal.update(&later).is_empty());
+	}
+
+	#[test]
+	fn reported_releases_stop_synthesized_releases() {
+		let mut terminal = Terminal::new().with_release_delay(Duration::from_millis(100));
+		terminal.parse_event(&press(KeyCode::Char('a'), KeyModifiers::NONE));
+		let release = Event::Key(KeyEvent::new_with_kind(
+			KeyCode::Char('a'),
+			KeyModifiers::NONE,
+			KeyEventKind::Release,
+		));
+		assert_eq!(
+			key_states(&terminal.parse_event(&release)),
+			[(Key::A, event::ButtonState::Released)]
+		);
+
+		// Once releases are reported, held keys stay pressed until their release arrives.
+		terminal.parse_event(&press(KeyCode::Char('b'), KeyModifiers::NONE));
+		let later = Instant::now() + Duration::from_secs(1);
+		assert!(terminal.update(&later).is_empty());
+	}
+
+	#[test]
+	fn shift_modifier_presses_left_shift() {
+		let mut terminal = Terminal::new();
+		let events = terminal.parse_event(&press(KeyCode::Char('A'), KeyModifiers::SHIFT));
+		assert_eq!(
+			key_states(&events),
+			[
+				(Key::LShift, event::ButtonState::Pressed),
+				(Key::A, event::ButtonState::Pressed),
+			]
+		);
+
+		let events = terminal.parse_event(&press(KeyCode::Char('b'), KeyModifiers::NONE));
+		assert_eq!(
+			key_states(&events),
+			[
+				(Key::LShift, event::ButtonState::Released),
+				(Key::B, event::ButtonState::Pressed),
+			]
+		);
+	}
+
+	#[test]
+	fn dragging_moves_by_cells() {
+		let mut terminal = Terminal::new();
+		let mouse = |kind: MouseEventKind, column: u16, row: u16| {
+			Event::Mouse(MouseEvent {
+				kind,
+				column,
+				row,
+				modifiers: KeyModifiers::NONE,
+			})
+		};
+		let left = crossterm::event::MouseButton::Left;
+		terminal.parse_event(&mouse(MouseEventKind::Down(left), 10, 5));
+
+		let events = terminal.parse_event(&mouse(MouseEventKind::Drag(left), 13, 3));
+		let moves = events
+			.iter()
+			.filter_map(|event| match event {
+				event::Event::Input(
+					device::Id::Mouse,
+					binding::Source::Mouse(binding::Mouse::Move(axis)),
+					event::State::MouseMove(delta),
+				) => Some((*axis, *delta)),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(
+			moves,
+			[
+				(binding::MouseAxis::MouseX, 3.0),
+				(binding::MouseAxis::MouseY, -2.0),
+			]
+		);
+		assert_eq!(events.len(), 2);
+	}
+}
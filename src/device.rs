@@ -1,6 +1,16 @@
+mod assignment;
+pub use assignment::*;
+mod backend;
+pub use backend::*;
+mod event;
+pub use event::*;
 mod gamepad;
 pub use gamepad::*;
 mod id;
 pub use id::*;
+mod identity;
+pub use identity::*;
+mod info;
+pub use info::*;
 mod kind;
 pub use kind::*;
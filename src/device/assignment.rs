@@ -0,0 +1,16 @@
+/// How the [`DeviceCache`](crate::DeviceCache) gives unassigned devices to users each [`update`](crate::DeviceCache::update).
+/// Devices can always be moved between users via [`assign`](crate::DeviceCache::assign),
+/// and [`join mode`](crate::DeviceCache::with_join) replaces the policy entirely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AssignmentPolicy {
+	/// The keyboard and mouse go to the first user, and each gamepad goes to the first user without a gamepad.
+	#[default]
+	FirstFree,
+	/// Each device goes to the user after the one which was given the previous device, wrapping around to the first user.
+	/// The keyboard and mouse always go to the same user.
+	RoundRobin,
+	/// Every device goes to the first user, for single-player games which accept input from any device.
+	SingleUser,
+	/// Devices are only given to users via [`assign`](crate::DeviceCache::assign).
+	Manual,
+}
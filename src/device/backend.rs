@@ -0,0 +1,10 @@
+/// Where the [`DeviceCache`](crate::DeviceCache) gets gamepad input from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GamepadBackend {
+	/// Gamepads are polled via gilrs each [`update`](crate::DeviceCache::update).
+	#[default]
+	Gilrs,
+	/// Gamepads are connected, disconnected, and send input via [`send_event`](crate::DeviceCache::send_event),
+	/// such as from SDL's game controller events.
+	External,
+}
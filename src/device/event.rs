@@ -0,0 +1,20 @@
+use crate::{device::Id, UserId};
+
+/// A change to a device's connection or owner,
+/// as reported by [`DeviceCache::subscribe_to_devices`](crate::DeviceCache::subscribe_to_devices).
+///
+/// User ids are indices into [`DeviceCache::users`](crate::DeviceCache::users).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+	/// A gamepad was connected for the first time (or its previous user was dropped).
+	Connected(Id),
+	/// A gamepad was disconnected, along with the user it was assigned to (if any).
+	/// The user keeps the gamepad, and will receive its input again if it is [`reconnected`](Event::Reconnected).
+	Disconnected(Id, Option<UserId>),
+	/// A gamepad which was disconnected from a user has been connected again, and given back to the same user.
+	Reconnected(Id, UserId),
+	/// A device was given to a user.
+	Assigned(Id, UserId),
+	/// A device was taken from a user.
+	Unassigned(Id, UserId),
+}
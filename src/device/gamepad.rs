@@ -1,7 +1,22 @@
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GamepadKind {
 	DualAxisGamepad,
 	PS4Dualshock,
 	Xbox360,
 	Joycon,
 }
+
+impl std::str::FromStr for GamepadKind {
+	type Err = ();
+	/// Parses the name of a variant (as written in the source code).
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		match name {
+			"DualAxisGamepad" => Ok(Self::DualAxisGamepad),
+			"PS4Dualshock" => Ok(Self::PS4Dualshock),
+			"Xbox360" => Ok(Self::Xbox360),
+			"Joycon" => Ok(Self::Joycon),
+			_ => Err(()),
+		}
+	}
+}
@@ -1,6 +1,7 @@
 use crate::device::GamepadKind;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Id {
 	Mouse,
 	Keyboard,
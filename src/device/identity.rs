@@ -0,0 +1,88 @@
+/// Identifies a physical gamepad across reconnects and application launches,
+/// unlike [`Id`](crate::device::Id) which is only unique while the gamepad is connected.
+///
+/// The uuid is the SDL-style GUID reported by gilrs, which is derived from the vendor and product of the gamepad,
+/// so two gamepads of the same model have the same uuid. The serial number and system path (when a backend provides them)
+/// tell those gamepads apart. gilrs provides neither, so its gamepads of the same model cannot be told apart,
+/// and a reconnecting gamepad goes back to the user of the most recently disconnected gamepad of its model.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Identity {
+	uuid: [u8; 16],
+	serial: Option<String>,
+	path: Option<String>,
+}
+
+impl Identity {
+	pub fn new(uuid: [u8; 16]) -> Self {
+		Self {
+			uuid,
+			serial: None,
+			path: None,
+		}
+	}
+
+	pub fn with_serial(mut self, serial: String) -> Self {
+		self.serial = Some(serial);
+		self
+	}
+
+	/// Sets the system path of the device (like `/dev/input/event5`), which is stable while it stays plugged into the same port.
+	pub fn with_path(mut self, path: String) -> Self {
+		self.path = Some(path);
+		self
+	}
+
+	pub fn uuid(&self) -> &[u8; 16] {
+		&self.uuid
+	}
+
+	pub fn serial(&self) -> Option<&str> {
+		self.serial.as_deref()
+	}
+
+	pub fn path(&self) -> Option<&str> {
+		self.path.as_deref()
+	}
+
+	/// Returns true if both identities could be the same gamepad.
+	/// The serial numbers (or failing that, the paths) are only compared if both identities have them,
+	/// so gamepads which only have a uuid match any gamepad of the same model.
+	pub fn matches(&self, other: &Identity) -> bool {
+		if self.uuid != other.uuid {
+			return false;
+		}
+		if let (Some(serial), Some(other_serial)) = (&self.serial, &other.serial) {
+			return serial == other_serial;
+		}
+		match (&self.path, &other.path) {
+			(Some(path), Some(other_path)) => path == other_path,
+			_ => true,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn serials_and_paths_tell_the_same_model_apart() {
+		let pad = Identity::new([1; 16]);
+		assert!(pad.matches(&Identity::new([1; 16])));
+		assert!(!pad.matches(&Identity::new([2; 16])));
+
+		let serial_a = pad.clone().with_serial("a".to_owned());
+		let serial_b = pad.clone().with_serial("b".to_owned());
+		assert!(!serial_a.matches(&serial_b));
+		assert!(serial_a.matches(&pad));
+
+		// Serials are compared before paths, since a gamepad may be plugged into another port.
+		let moved = serial_a.clone().with_path("/dev/input/event5".to_owned());
+		let plugged = serial_a.with_path("/dev/input/event6".to_owned());
+		assert!(moved.matches(&plugged));
+		let port_a = pad.clone().with_path("/dev/input/event5".to_owned());
+		let port_b = pad.with_path("/dev/input/event6".to_owned());
+		assert!(!port_a.matches(&port_b));
+	}
+}
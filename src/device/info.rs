@@ -0,0 +1,91 @@
+use crate::{device::Id, device::Identity, device::Kind, UserId};
+
+/// A snapshot of a device, as returned by [`DeviceCache::devices`](crate::DeviceCache::devices).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Info {
+	pub id: Id,
+	pub kind: Kind,
+	/// The stable identity of a gamepad, used to recognize it when it reconnects (or in a later launch).
+	pub identity: Option<Identity>,
+	/// The name of the device as reported by the gamepad backend,
+	/// or a generic name if the backend does not know about the device.
+	pub name: String,
+	/// The USB vendor id of a gamepad, if it could be determined.
+	pub vendor_id: Option<u16>,
+	/// The USB product id of a gamepad, if it could be determined.
+	pub product_id: Option<u16>,
+	pub power: PowerInfo,
+	/// The index of the user (in [`DeviceCache::users`](crate::DeviceCache::users)) who owns the device.
+	pub user: Option<UserId>,
+	/// False if the device was disconnected from its user, who is waiting for it to be reconnected.
+	pub connected: bool,
+}
+
+/// The battery state of a device. Keyboards, mice, and gamepads from an external backend are always `Unknown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PowerInfo {
+	/// The power state could not be determined.
+	#[default]
+	Unknown,
+	/// The device does not have a battery.
+	Wired,
+	/// The device is running on its battery, with the provided charge percentage.
+	Discharging(u8),
+	/// The battery is charging, with the provided charge percentage.
+	Charging(u8),
+	/// The battery is fully charged.
+	Charged,
+}
+
+impl From<gilrs::PowerInfo> for PowerInfo {
+	fn from(info: gilrs::PowerInfo) -> Self {
+		match info {
+			gilrs::PowerInfo::Unknown => Self::Unknown,
+			gilrs::PowerInfo::Wired => Self::Wired,
+			gilrs::PowerInfo::Discharging(charge) => Self::Discharging(charge),
+			gilrs::PowerInfo::Charging(charge) => Self::Charging(charge),
+			gilrs::PowerInfo::Charged => Self::Charged,
+		}
+	}
+}
+
+impl Info {
+	pub(crate) fn new(
+		id: Id,
+		identity: Option<Identity>,
+		user: Option<UserId>,
+		connected: bool,
+		gamepad: Option<gilrs::Gamepad>,
+	) -> Self {
+		let (name, vendor_id, product_id, power) = match gamepad {
+			Some(gamepad) => {
+				// gilrs uuids use the SDL GUID layout, with the (little endian) vendor and product ids at bytes 4 and 8.
+				// Backends which can't query the ids (like XInput) leave them zeroed.
+				let uuid = gamepad.uuid();
+				let usb_id = |index: usize| match u16::from_le_bytes([uuid[index], uuid[index + 1]])
+				{
+					0 => None,
+					usb_id => Some(usb_id),
+				};
+				(
+					gamepad.name().to_owned(),
+					usb_id(4),
+					usb_id(8),
+					gamepad.power_info().into(),
+				)
+			}
+			None => (id.to_string(), None, None, PowerInfo::Unknown),
+		};
+		Self {
+			id,
+			kind: id.into(),
+			identity,
+			name,
+			vendor_id,
+			product_id,
+			power,
+			user,
+			connected,
+		}
+	}
+}
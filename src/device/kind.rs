@@ -1,6 +1,7 @@
 use crate::device::{GamepadKind, Id};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Kind {
 	Mouse,
 	Keyboard,
@@ -16,3 +17,13 @@ impl From<Id> for Kind {
 		}
 	}
 }
+
+impl Kind {
+	/// Returns true if the kinds are the same, or if a player uses both at once (a keyboard and a mouse).
+	pub fn is_used_with(&self, other: &Kind) -> bool {
+		match (self, other) {
+			(Kind::Mouse, Kind::Keyboard) | (Kind::Keyboard, Kind::Mouse) => true,
+			_ => self == other,
+		}
+	}
+}
@@ -0,0 +1,29 @@
+//! Bindings can be written as text instead of nested [`ActionMap`](crate::binding::ActionMap) builders:
+//!
+//! ```text
+//! # Lines before the first header belong to the default action set and default layout.
+//! jump = Key(Space) | Button(FaceBottom)
+//! move_x = Key(D) - Key(A) | Axis(LThumbstickX) * deadzone(0.15)
+//! look_x = MouseMove(MouseX) * screen_delta * setting(look_sensitivity)
+//!
+//! [menu / left_handed]
+//! confirm = select { Keyboard: Key(Return), Gamepad(Xbox360): Button(Xbox360, FaceBottom) }
+//! ```
+//!
+//! - Sources are `Key(..)`, `PhysicalKey(..)` (a key by its US-QWERTY position), `MouseButton(..)`, `MouseMove(..)`, `MouseScroll(..)`, `Button(..)`, and `Axis(..)`,
+//!   where gamepad sources can optionally start with a [`GamepadKind`](crate::device::GamepadKind)
+//!   (defaulting to `DualAxisGamepad`). `Gamepad(..)` resolves to either a button or an axis.
+//! - `a | b` binds both sources to the action, and `a - b` does the same with `b` negated.
+//! - `* behavior` adds a behavior to the source (or parenthesized group) before it:
+//!   a number (multiplier), `deadzone(threshold)`, `average`, `screen_delta`, `setting(name)`, or `invert(name)`.
+//!   Behaviors on a group apply to whichever of its sources sent the input (or to the combined value, after `average`).
+//! - `select { DeviceKind: binding, .. }` picks a binding by the kind of device that sent the input.
+//! - `[set / layout]` headers start the bindings for an action set and layout, where `_` is the default (`None`).
+//!
+//! Any [`BehaviorBinding`](crate::action::BehaviorBinding) can be turned back into text via [`print`].
+
+mod lexer;
+mod parser;
+pub use parser::*;
+mod printer;
+pub use printer::*;
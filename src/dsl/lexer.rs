@@ -0,0 +1,165 @@
+use super::ParseError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Token {
+	Ident(String),
+	Number(f64),
+	Symbol(char),
+	/// The end of a statement. Newlines inside of parenthesis or braces are ignored.
+	Newline,
+	End,
+}
+
+/// A token and the position (1-based line and column) of its first character.
+#[derive(Debug, Clone)]
+pub(crate) struct Spanned {
+	pub token: Token,
+	pub line: usize,
+	pub column: usize,
+}
+
+const SYMBOLS: &[char] = &[
+	'=', '|', '-', '*', '(', ')', '{', '}', ',', ':', '[', ']', '/',
+];
+
+pub(crate) fn tokenize(text: &str) -> Result<Vec<Spanned>, ParseError> {
+	let mut tokens = Vec::new();
+	let mut depth = 0usize;
+	for (line_index, line) in text.lines().enumerate() {
+		let line_number = line_index + 1;
+		let chars = line.char_indices().collect::<Vec<_>>();
+		let mut i = 0;
+		while i < chars.len() {
+			let (_, c) = chars[i];
+			let column = i + 1;
+			if c == '#' {
+				break;
+			} else if c.is_whitespace() {
+				i += 1;
+			} else if c.is_ascii_alphabetic() || c == '_' {
+				let start = i;
+				while i < chars.len() && (chars[i].1.is_ascii_alphanumeric() || chars[i].1 == '_') {
+					i += 1;
+				}
+				let ident = chars[start..i].iter().map(|(_, c)| *c).collect();
+				tokens.push(Spanned {
+					token: Token::Ident(ident),
+					line: line_number,
+					column,
+				});
+			} else if c.is_ascii_digit() || c == '.' {
+				let start = i;
+				while i < chars.len() && (chars[i].1.is_ascii_digit() || chars[i].1 == '.') {
+					i += 1;
+				}
+				let text = chars[start..i].iter().map(|(_, c)| *c).collect::<String>();
+				let value = text.parse::<f64>().map_err(|_| ParseError {
+					line: line_number,
+					column,
+					message: format!("invalid number {:?}", text),
+				})?;
+				tokens.push(Spanned {
+					token: Token::Number(value),
+					line: line_number,
+					column,
+				});
+			} else if SYMBOLS.contains(&c) {
+				match c {
+					'(' | '{' => depth += 1,
+					')' | '}' => depth = depth.saturating_sub(1),
+					_ => {}
+				}
+				tokens.push(Spanned {
+					token: Token::Symbol(c),
+					line: line_number,
+					column,
+				});
+				i += 1;
+			} else {
+				return Err(ParseError {
+					line: line_number,
+					column,
+					message: format!("unexpected character {:?}", c),
+				});
+			}
+		}
+		if depth == 0 {
+			tokens.push(Spanned {
+				token: Token::Newline,
+				line: line_number,
+				column: chars.len() + 1,
+			});
+		}
+	}
+	let line = text.lines().count().max(1);
+	let column = text
+		.lines()
+		.last()
+		.map(|line| line.chars().count())
+		.unwrap_or(0)
+		+ 1;
+	tokens.push(Spanned {
+		token: Token::End,
+		line,
+		column,
+	});
+	Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn tokens(text: &str) -> Vec<Token> {
+		tokenize(text)
+			.unwrap()
+			.into_iter()
+			.map(|spanned| spanned.token)
+			.collect()
+	}
+
+	#[test]
+	fn tokenizes_bindings_and_skips_comments() {
+		assert_eq!(
+			tokens("move = Key(D) * 0.5 # comment"),
+			vec![
+				Token::Ident("move".to_owned()),
+				Token::Symbol('='),
+				Token::Ident("Key".to_owned()),
+				Token::Symbol('('),
+				Token::Ident("D".to_owned()),
+				Token::Symbol(')'),
+				Token::Symbol('*'),
+				Token::Number(0.5),
+				Token::Newline,
+				Token::End,
+			]
+		);
+	}
+
+	#[test]
+	fn newlines_are_ignored_inside_groups() {
+		assert_eq!(
+			tokens("(a |\nb)\nc"),
+			vec![
+				Token::Symbol('('),
+				Token::Ident("a".to_owned()),
+				Token::Symbol('|'),
+				Token::Ident("b".to_owned()),
+				Token::Symbol(')'),
+				Token::Newline,
+				Token::Ident("c".to_owned()),
+				Token::Newline,
+				Token::End,
+			]
+		);
+	}
+
+	#[test]
+	fn errors_have_positions() {
+		let error = tokenize("jump = Key(Space)\nrun = @").unwrap_err();
+		assert_eq!((error.line, error.column), (2, 7));
+		let error = tokenize("x = 1.2.3").unwrap_err();
+		assert_eq!((error.line, error.column), (1, 5));
+	}
+}
@@ -0,0 +1,573 @@
+use super::lexer::{tokenize, Spanned, Token};
+use crate::{
+	action::{
+		behavior::{
+			Average, Deadzone, Multiplier, ScreenPositionDelta, SettingInvert, SettingMultiplier,
+		},
+		BehaviorBinding,
+	},
+	binding::{self, ActionMap, ActionSet, ActionSetId, Source},
+	device::{self, GamepadKind},
+	source::{Axis, Button, Key, MouseButton},
+	Config,
+};
+use std::collections::HashMap;
+
+/// Describes why some binding text could not be parsed, and where (1-based line and column).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+	pub line: usize,
+	pub column: usize,
+	pub message: String,
+}
+
+impl std::fmt::Display for ParseError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}:{}: {}", self.line, self.column, self.message)
+	}
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a single binding expression, like `Key(D) - Key(A) | Axis(LThumbstickX) * deadzone(0.15)`.
+pub fn parse_binding(text: &str) -> Result<BehaviorBinding, ParseError> {
+	let mut parser = Parser::new(text)?;
+	parser.skip_newlines();
+	let binding = parser.expression()?;
+	parser.skip_newlines();
+	parser.expect_end()?;
+	Ok(binding)
+}
+
+/// Parses lines of `action = binding` (without any `[set / layout]` headers),
+/// returning the name of each action and its binding in the order they were written.
+pub fn parse_bindings(text: &str) -> Result<Vec<(String, BehaviorBinding)>, ParseError> {
+	let mut parser = Parser::new(text)?;
+	let sections = parser.sections()?;
+	let mut bindings = Vec::new();
+	for section in sections.into_iter() {
+		if let Some(header) = section.header {
+			return Err(header.error("headers are only supported when parsing action sets"));
+		}
+		for (name, _, binding) in section.bindings.into_iter() {
+			bindings.push((name, binding));
+		}
+	}
+	Ok(bindings)
+}
+
+/// Parses a bindings file into action sets. Action sets, layouts, and actions are referenced by name
+/// and must have already been added to the config (`_` refers to the default action set or layout).
+///
+/// With the `hot-reload` feature, this can be used to reload bindings from a file:
+/// `move |text| parse_action_sets(text, &config.read().unwrap()).map_err(|error| error.to_string())`.
+pub fn parse_action_sets(
+	text: &str,
+	config: &Config,
+) -> Result<Vec<(ActionSetId, ActionSet)>, ParseError> {
+	let mut parser = Parser::new(text)?;
+	let sections = parser.sections()?;
+	let mut sets: Vec<(ActionSetId, ActionSet)> = Vec::new();
+	for section in sections.into_iter() {
+		let set_id = match &section.set_name {
+			None => None,
+			Some(name) => config.find_action_set(Some(name)).ok_or_else(|| {
+				let header = section.header.as_ref().unwrap();
+				header.error(&format!(
+					"action set {:?} was not added to the config",
+					name
+				))
+			})?,
+		};
+		let layout = config
+			.find_layout(section.layout_name.as_deref())
+			.ok_or_else(|| {
+				let message = format!(
+					"layout {:?} was not added to the config",
+					section.layout_name.as_deref().unwrap_or("_")
+				);
+				match &section.header {
+					Some(header) => header.error(&message),
+					None => ParseError {
+						line: 1,
+						column: 1,
+						message,
+					},
+				}
+			})?;
+
+		let mut map = ActionMap::default();
+		for (name, spanned, binding) in section.bindings.into_iter() {
+			let action_id = config.find_action(&name).ok_or_else(|| {
+				spanned.error(&format!("action {:?} was not added to the config", name))
+			})?;
+			map = map.bind(action_id, binding);
+		}
+
+		match sets.iter_mut().find(|(id, _)| *id == set_id) {
+			Some((_, set)) => *set = std::mem::take(set).with(layout, map),
+			None => sets.push((set_id, ActionSet::default().with(layout, map))),
+		}
+	}
+	Ok(sets)
+}
+
+/// The bindings following a `[set / layout]` header (or the start of the text).
+struct Section {
+	header: Option<Spanned>,
+	set_name: Option<String>,
+	layout_name: Option<String>,
+	bindings: Vec<(String, Spanned, BehaviorBinding)>,
+}
+
+impl Spanned {
+	fn error(&self, message: &str) -> ParseError {
+		ParseError {
+			line: self.line,
+			column: self.column,
+			message: message.to_owned(),
+		}
+	}
+}
+
+fn describe(token: &Token) -> String {
+	match token {
+		Token::Ident(ident) => format!("{:?}", ident),
+		Token::Number(value) => format!("{}", value),
+		Token::Symbol(c) => format!("{:?}", c),
+		Token::Newline => "end of line".to_owned(),
+		Token::End => "end of input".to_owned(),
+	}
+}
+
+struct Parser {
+	tokens: Vec<Spanned>,
+	index: usize,
+}
+
+impl Parser {
+	fn new(text: &str) -> Result<Self, ParseError> {
+		Ok(Self {
+			tokens: tokenize(text)?,
+			index: 0,
+		})
+	}
+
+	fn peek(&self) -> &Spanned {
+		&self.tokens[self.index]
+	}
+
+	fn next(&mut self) -> Spanned {
+		let spanned = self.tokens[self.index].clone();
+		if self.index + 1 < self.tokens.len() {
+			self.index += 1;
+		}
+		spanned
+	}
+
+	fn unexpected(&self, expected: &str) -> ParseError {
+		let spanned = self.peek();
+		spanned.error(&format!(
+			"expected {}, found {}",
+			expected,
+			describe(&spanned.token)
+		))
+	}
+
+	fn is_symbol(&self, c: char) -> bool {
+		self.peek().token == Token::Symbol(c)
+	}
+
+	fn eat_symbol(&mut self, c: char) -> bool {
+		if self.is_symbol(c) {
+			self.next();
+			true
+		} else {
+			false
+		}
+	}
+
+	fn expect_symbol(&mut self, c: char) -> Result<Spanned, ParseError> {
+		if self.is_symbol(c) {
+			Ok(self.next())
+		} else {
+			Err(self.unexpected(&format!("{:?}", c)))
+		}
+	}
+
+	fn expect_ident(&mut self, expected: &str) -> Result<(String, Spanned), ParseError> {
+		match &self.peek().token {
+			Token::Ident(ident) => {
+				let ident = ident.clone();
+				Ok((ident, self.next()))
+			}
+			_ => Err(self.unexpected(expected)),
+		}
+	}
+
+	fn expect_number(&mut self) -> Result<f64, ParseError> {
+		let negative = self.eat_symbol('-');
+		match self.peek().token {
+			Token::Number(value) => {
+				self.next();
+				Ok(if negative { -value } else { value })
+			}
+			_ => Err(self.unexpected("a number")),
+		}
+	}
+
+	fn expect_end(&mut self) -> Result<(), ParseError> {
+		match self.peek().token {
+			Token::End => Ok(()),
+			_ => Err(self.unexpected("end of input")),
+		}
+	}
+
+	fn expect_end_of_line(&mut self) -> Result<(), ParseError> {
+		match self.peek().token {
+			Token::Newline => {
+				self.next();
+				Ok(())
+			}
+			Token::End => Ok(()),
+			_ => Err(self.unexpected("end of line")),
+		}
+	}
+
+	fn skip_newlines(&mut self) {
+		while self.peek().token == Token::Newline {
+			self.next();
+		}
+	}
+
+	fn sections(&mut self) -> Result<Vec<Section>, ParseError> {
+		let mut sections = vec![Section {
+			header: None,
+			set_name: None,
+			layout_name: None,
+			bindings: Vec::new(),
+		}];
+		loop {
+			self.skip_newlines();
+			if self.peek().token == Token::End {
+				break;
+			}
+			if self.is_symbol('[') {
+				sections.push(self.header()?);
+				continue;
+			}
+			let (name, spanned) = self.expect_ident("an action name or '['")?;
+			let section = sections.last_mut().unwrap();
+			if section.bindings.iter().any(|(bound, _, _)| *bound == name) {
+				return Err(spanned.error(&format!(
+					"action {:?} is bound more than once in this section",
+					name
+				)));
+			}
+			self.expect_symbol('=')?;
+			let binding = self.expression()?;
+			self.expect_end_of_line()?;
+			section.bindings.push((name, spanned, binding));
+		}
+		Ok(sections)
+	}
+
+	fn header(&mut self) -> Result<Section, ParseError> {
+		let header = self.expect_symbol('[')?;
+		let (set_name, _) = self.expect_ident("an action set name or '_'")?;
+		let layout_name = if self.eat_symbol('/') {
+			Some(self.expect_ident("a layout name or '_'")?.0)
+		} else {
+			None
+		};
+		self.expect_symbol(']')?;
+		self.expect_end_of_line()?;
+		let named = |name: String| if name == "_" { None } else { Some(name) };
+		Ok(Section {
+			header: Some(header),
+			set_name: named(set_name),
+			layout_name: layout_name.and_then(named),
+			bindings: Vec::new(),
+		})
+	}
+
+	/// `term (('|' | '-') term)*`, where a binding can continue on the next line after an operator.
+	fn expression(&mut self) -> Result<BehaviorBinding, ParseError> {
+		let mut bindings = vec![self.term()?];
+		loop {
+			if self.eat_symbol('|') {
+				self.skip_newlines();
+				bindings.push(self.term()?);
+			} else if self.is_symbol('-') {
+				let minus = self.next();
+				self.skip_newlines();
+				let term = self.term()?;
+				bindings.push(negate(term, &minus)?);
+			} else {
+				break;
+			}
+		}
+		Ok(if bindings.len() == 1 {
+			bindings.pop().unwrap()
+		} else {
+			BehaviorBinding::Container(bindings, Vec::new())
+		})
+	}
+
+	/// `'-'? atom ('*' behavior)*`
+	fn term(&mut self) -> Result<BehaviorBinding, ParseError> {
+		if self.is_symbol('-') {
+			let minus = self.next();
+			let term = self.term()?;
+			return negate(term, &minus);
+		}
+		let mut binding = self.atom()?;
+		while self.is_symbol('*') {
+			let star = self.next();
+			self.skip_newlines();
+			binding = self.behavior(binding, &star)?;
+		}
+		Ok(binding)
+	}
+
+	fn atom(&mut self) -> Result<BehaviorBinding, ParseError> {
+		if self.eat_symbol('(') {
+			let binding = self.expression()?;
+			self.expect_symbol(')')?;
+			return Ok(binding);
+		}
+		let (name, spanned) = self.expect_ident("a source, '(' or 'select'")?;
+		if name == "select" {
+			return self.select();
+		}
+		Ok(BehaviorBinding::from(self.source(&name, &spanned)?))
+	}
+
+	fn select(&mut self) -> Result<BehaviorBinding, ParseError> {
+		self.expect_symbol('{')?;
+		let mut options = HashMap::new();
+		while !self.is_symbol('}') {
+			let (kind, spanned) = self.device_kind()?;
+			if options.contains_key(&kind) {
+				return Err(spanned.error(&format!("{:?} is selected more than once", kind)));
+			}
+			self.expect_symbol(':')?;
+			options.insert(kind, self.expression()?);
+			if !self.eat_symbol(',') {
+				break;
+			}
+		}
+		self.expect_symbol('}')?;
+		Ok(BehaviorBinding::Select(options))
+	}
+
+	fn device_kind(&mut self) -> Result<(device::Kind, Spanned), ParseError> {
+		let (name, spanned) = self.expect_ident("a device kind")?;
+		let kind = match name.as_str() {
+			"Mouse" => device::Kind::Mouse,
+			"Keyboard" => device::Kind::Keyboard,
+			"Gamepad" => {
+				if self.eat_symbol('(') {
+					let kind = self.gamepad_kind()?;
+					self.expect_symbol(')')?;
+					device::Kind::Gamepad(kind)
+				} else {
+					device::Kind::Gamepad(GamepadKind::DualAxisGamepad)
+				}
+			}
+			_ => {
+				return Err(spanned.error(&format!(
+					"unknown device kind {:?}, expected Mouse, Keyboard or Gamepad",
+					name
+				)))
+			}
+		};
+		Ok((kind, spanned))
+	}
+
+	fn gamepad_kind(&mut self) -> Result<GamepadKind, ParseError> {
+		let (name, spanned) = self.expect_ident("a gamepad kind")?;
+		name.parse::<GamepadKind>()
+			.map_err(|_| spanned.error(&format!("unknown gamepad kind {:?}", name)))
+	}
+
+	/// Parses the arguments of a source (like `(LThumbstickX)` or `(Xbox360, FaceBottom)`).
+	fn source(&mut self, name: &str, source_span: &Spanned) -> Result<Source, ParseError> {
+		self.expect_symbol('(')?;
+		let (mut arg, mut arg_span) = self.expect_ident("a source name")?;
+		let mut gamepad = GamepadKind::DualAxisGamepad;
+		let is_gamepad = matches!(name, "Button" | "Axis" | "Gamepad");
+		if is_gamepad && self.eat_symbol(',') {
+			gamepad = arg
+				.parse::<GamepadKind>()
+				.map_err(|_| arg_span.error(&format!("unknown gamepad kind {:?}", arg)))?;
+			let (next, next_span) = self.expect_ident("a gamepad button or axis")?;
+			arg = next;
+			arg_span = next_span;
+		}
+		// `Unknown(scancode)` is the only source argument which has arguments of its own.
+		if matches!(name, "Key" | "PhysicalKey") && arg == "Unknown" && self.eat_symbol('(') {
+			let scancode = self.expect_number()?;
+			self.expect_symbol(')')?;
+			arg = format!("Unknown({})", scancode);
+		}
+		self.expect_symbol(')')?;
+
+		let unknown = |kind: &str| arg_span.error(&format!("unknown {} {:?}", kind, arg));
+		Ok(match name {
+			"Key" => Source::Keyboard(arg.parse::<Key>().map_err(|_| unknown("key"))?),
+			"PhysicalKey" => Source::PhysicalKey(arg.parse::<Key>().map_err(|_| unknown("key"))?),
+			"MouseButton" => Source::Mouse(binding::Mouse::Button(
+				arg.parse::<MouseButton>()
+					.map_err(|_| unknown("mouse button"))?,
+			)),
+			"MouseMove" => Source::Mouse(binding::Mouse::Move(
+				arg.parse().map_err(|_| unknown("mouse axis"))?,
+			)),
+			"MouseScroll" => Source::Mouse(binding::Mouse::Scroll(
+				arg.parse().map_err(|_| unknown("mouse axis"))?,
+			)),
+			"Button" => Source::Gamepad(
+				gamepad,
+				binding::Gamepad::Button(
+					arg.parse::<Button>()
+						.map_err(|_| unknown("gamepad button"))?,
+				),
+			),
+			"Axis" => Source::Gamepad(
+				gamepad,
+				binding::Gamepad::Axis(arg.parse::<Axis>().map_err(|_| unknown("gamepad axis"))?),
+			),
+			"Gamepad" => {
+				let input = match (arg.parse::<Button>(), arg.parse::<Axis>()) {
+					(Ok(_), Ok(_)) => {
+						return Err(arg_span.error(&format!(
+							"{:?} is both a gamepad button and axis, use Button({}) or Axis({})",
+							arg, arg, arg
+						)))
+					}
+					(Ok(button), Err(_)) => binding::Gamepad::Button(button),
+					(Err(_), Ok(axis)) => binding::Gamepad::Axis(axis),
+					(Err(_), Err(_)) => return Err(unknown("gamepad button or axis")),
+				};
+				Source::Gamepad(gamepad, input)
+			}
+			_ => {
+				return Err(source_span.error(&format!(
+					"unknown source {:?}, expected Key, PhysicalKey, MouseButton, MouseMove, MouseScroll, Button, Axis or Gamepad",
+					name
+				)))
+			}
+		})
+	}
+
+	/// Parses the behavior after a `*`, adding it to the binding.
+	fn behavior(
+		&mut self,
+		binding: BehaviorBinding,
+		star: &Spanned,
+	) -> Result<BehaviorBinding, ParseError> {
+		let add_error = |error: crate::Error| star.error(&error.to_string());
+		if self.is_symbol('-') || matches!(self.peek().token, Token::Number(_)) {
+			let value = self.expect_number()?;
+			return binding
+				.try_with_behavior(Multiplier(value as f32))
+				.map_err(add_error);
+		}
+		let (name, spanned) = self.expect_ident("a number or behavior")?;
+		match name.as_str() {
+			"average" => binding.try_with_behavior(Average),
+			"screen_delta" => binding.try_with_behavior(ScreenPositionDelta),
+			"deadzone" => {
+				self.expect_symbol('(')?;
+				let threshold = self.expect_number()?;
+				self.expect_symbol(')')?;
+				binding.try_with_behavior(Deadzone(threshold as f32))
+			}
+			"setting" | "invert" => {
+				self.expect_symbol('(')?;
+				let (setting, _) = self.expect_ident("a setting name")?;
+				self.expect_symbol(')')?;
+				if name == "setting" {
+					binding.try_with_behavior(SettingMultiplier::new(setting))
+				} else {
+					binding.try_with_behavior(SettingInvert::new(setting))
+				}
+			}
+			// Virtual axes move toward their target over time, which event-driven bindings cannot do yet.
+			"virtual_axis" => {
+				return Err(
+					spanned.error("virtual_axis is unsupported, bindings only update on input")
+				)
+			}
+			_ => return Err(spanned.error(&format!("unknown behavior {:?}", name))),
+		}
+		.map_err(add_error)
+	}
+}
+
+fn negate(binding: BehaviorBinding, minus: &Spanned) -> Result<BehaviorBinding, ParseError> {
+	binding
+		.try_with_behavior(Multiplier(-1.0))
+		.map_err(|error| minus.error(&error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn shape(binding: &BehaviorBinding) -> String {
+		match binding {
+			BehaviorBinding::Source(source) => format!("{}", source.behaviors().len()),
+			BehaviorBinding::Container(bindings, behaviors) => format!(
+				"({})*{}",
+				bindings.iter().map(shape).collect::<Vec<_>>().join(" "),
+				behaviors.len()
+			),
+			BehaviorBinding::Select(options) => format!("select{}", options.len()),
+		}
+	}
+
+	#[test]
+	fn groups_keep_their_behaviors() {
+		let binding = parse_binding("(Key(D) - Key(A)) * 2").unwrap();
+		assert_eq!(shape(&binding), "(0 1)*1");
+		let binding = parse_binding("-(Key(A) | Key(B))").unwrap();
+		assert_eq!(shape(&binding), "(0 0)*1");
+		let binding =
+			parse_binding("(Axis(LThumbstickX) | Key(D) - Key(A)) * deadzone(0.2) * average")
+				.unwrap();
+		assert_eq!(shape(&binding), "(0 0 1)*2");
+	}
+
+	#[test]
+	fn operators_continue_on_the_next_line() {
+		let bindings =
+			parse_bindings("jump = Key(Space) |\n\tButton(FaceBottom)\nfire = MouseButton(Left)\n")
+				.unwrap();
+		let names = bindings
+			.iter()
+			.map(|(name, _)| name.as_str())
+			.collect::<Vec<_>>();
+		assert_eq!(names, vec!["jump", "fire"]);
+		assert_eq!(shape(&bindings[0].1), "(0 0)*0");
+	}
+
+	#[test]
+	fn errors_point_at_the_problem() {
+		let error = parse_binding("Key(D) * virtual_axis(1, 2)").unwrap_err();
+		assert_eq!((error.line, error.column), (1, 10));
+		assert!(error.message.contains("unsupported"));
+
+		let error = parse_binding("Key(NotAKey)").unwrap_err();
+		assert_eq!((error.line, error.column), (1, 5));
+
+		let error = parse_binding("select { Keyboard: Key(A) } * 2").unwrap_err();
+		assert_eq!((error.line, error.column), (1, 29));
+
+		let error = parse_bindings("jump = Key(A)\njump = Key(B)").unwrap_err();
+		assert_eq!((error.line, error.column), (2, 1));
+
+		assert!(parse_bindings("[menu]\njump = Key(A)").is_err());
+	}
+}
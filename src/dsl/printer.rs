@@ -0,0 +1,142 @@
+use crate::{
+	action::{behavior::Behavior, BehaviorBinding},
+	binding::{self, ActionMap, Source},
+	device::{self, GamepadKind},
+};
+
+/// Writes a binding in the [`binding syntax`](crate::dsl), such that [`parse_binding`](super::parse_binding)
+/// produces an equivalent binding. Custom behaviors which do not implement
+/// [`dsl_string`](Behavior::dsl_string) are written as their debug string, and cannot be parsed.
+pub fn print(binding: &BehaviorBinding) -> String {
+	let mut out = String::new();
+	write_binding(&mut out, binding, false);
+	out
+}
+
+/// Writes each action of a map as an `action = binding` line, sorted by action name.
+pub fn print_action_map(map: &ActionMap) -> String {
+	let mut actions = map.iter().collect::<Vec<_>>();
+	actions.sort_by_key(|(id, _)| **id);
+	actions
+		.into_iter()
+		.map(|(id, binding)| format!("{} = {}\n", id, print(binding)))
+		.collect()
+}
+
+fn write_binding(out: &mut String, binding: &BehaviorBinding, nested: bool) {
+	match binding {
+		BehaviorBinding::Source(source_behavior) => {
+			out.push_str(&source_string(source_behavior.source()));
+			write_behaviors(out, source_behavior.behaviors());
+		}
+		BehaviorBinding::Container(bindings, behaviors) => {
+			// Containers inside of containers (or with behaviors) need parenthesis,
+			// otherwise their bindings would be parsed as part of the outer container.
+			let grouped = nested || !behaviors.is_empty();
+			if grouped {
+				out.push('(');
+			}
+			for (i, child) in bindings.iter().enumerate() {
+				if i > 0 {
+					out.push_str(" | ");
+				}
+				write_binding(out, child, true);
+			}
+			if grouped {
+				out.push(')');
+			}
+			write_behaviors(out, behaviors);
+		}
+		BehaviorBinding::Select(options) => {
+			let mut options = options
+				.iter()
+				.map(|(kind, binding)| (device_kind_string(kind), binding))
+				.collect::<Vec<_>>();
+			options.sort_by(|(a, _), (b, _)| a.cmp(b));
+			out.push_str("select { ");
+			for (i, (kind, binding)) in options.into_iter().enumerate() {
+				if i > 0 {
+					out.push_str(", ");
+				}
+				out.push_str(&kind);
+				out.push_str(": ");
+				write_binding(out, binding, false);
+			}
+			out.push_str(" }");
+		}
+	}
+}
+
+fn write_behaviors(out: &mut String, behaviors: &[Box<dyn Behavior + Send + Sync>]) {
+	for behavior in behaviors.iter() {
+		out.push_str(" * ");
+		out.push_str(
+			&behavior
+				.dsl_string()
+				.unwrap_or_else(|| behavior.debug_string()),
+		);
+	}
+}
+
+fn gamepad_string(kind: GamepadKind, name: String) -> String {
+	match kind {
+		GamepadKind::DualAxisGamepad => name,
+		_ => format!("{:?}, {}", kind, name),
+	}
+}
+
+fn source_string(source: Source) -> String {
+	match source {
+		Source::Keyboard(key) => format!("Key({:?})", key),
+		Source::PhysicalKey(key) => format!("PhysicalKey({:?})", key),
+		Source::Mouse(binding::Mouse::Button(button)) => format!("MouseButton({:?})", button),
+		Source::Mouse(binding::Mouse::Move(axis)) => format!("MouseMove({:?})", axis),
+		Source::Mouse(binding::Mouse::Scroll(axis)) => format!("MouseScroll({:?})", axis),
+		Source::Gamepad(kind, binding::Gamepad::Button(button)) => {
+			format!("Button({})", gamepad_string(kind, format!("{:?}", button)))
+		}
+		Source::Gamepad(kind, binding::Gamepad::Axis(axis)) => {
+			format!("Axis({})", gamepad_string(kind, format!("{:?}", axis)))
+		}
+	}
+}
+
+fn device_kind_string(kind: &device::Kind) -> String {
+	match kind {
+		device::Kind::Mouse => "Mouse".to_owned(),
+		device::Kind::Keyboard => "Keyboard".to_owned(),
+		device::Kind::Gamepad(GamepadKind::DualAxisGamepad) => "Gamepad".to_owned(),
+		device::Kind::Gamepad(kind) => format!("Gamepad({:?})", kind),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::dsl::parse_binding;
+
+	#[test]
+	fn printed_bindings_parse_to_the_same_text() {
+		for text in [
+			"Key(Space) | Button(FaceBottom)",
+			"Key(D) | Key(A) * -1 | Axis(LThumbstickX) * deadzone(0.15)",
+			"(Key(D) | Key(A) * -1) * 2",
+			"(Key(A) | (Key(B) | Key(C)) * average) * setting(speed) * invert(look)",
+			"MouseMove(MouseX) * screen_delta",
+			"PhysicalKey(W) | Key(Unknown(30)) | Button(Xbox360, FaceRight)",
+			"select { Gamepad(Xbox360): Axis(Xbox360, LTrigger), Keyboard: Key(A) | Key(B) }",
+		]
+		.iter()
+		{
+			let printed = print(&parse_binding(text).unwrap());
+			assert_eq!(&printed, text);
+			assert_eq!(print(&parse_binding(&printed).unwrap()), printed);
+		}
+	}
+
+	#[test]
+	fn minus_is_printed_as_a_negative_multiplier() {
+		let binding = parse_binding("Key(D) - Key(A)").unwrap();
+		assert_eq!(print(&binding), "Key(D) | Key(A) * -1");
+	}
+}
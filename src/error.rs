@@ -0,0 +1,112 @@
+use crate::{action, binding};
+
+/// The ways in which configuring or querying input-actions can fail.
+#[derive(Debug)]
+pub enum Error {
+	/// Behaviors cannot be added to a [`Select`](action::BehaviorBinding::Select) binding,
+	/// they must be added to each of its options instead.
+	BehaviorOnSelect,
+	/// Bindings can only be added to a [`Container`](action::BehaviorBinding::Container) binding.
+	NotAContainer,
+	/// A behavior was bound to a source it cannot process.
+	UnsupportedSource {
+		behavior: String,
+		source: binding::Source,
+	},
+	/// An action set binds an action which was never added to the [`Config`](crate::Config).
+	UnknownAction(action::Id),
+	/// An action set has bindings for a layout which was never added to the [`Config`](crate::Config).
+	UnknownLayout(binding::LayoutId),
+	/// A layout inherits from itself (directly or through its parents).
+	LayoutCycle(binding::LayoutId),
+	/// The requested action set was never added to the [`Config`](crate::Config).
+	UnknownActionSet(binding::ActionSetId),
+	/// The user does not have the requested action in any of its enabled action sets.
+	ActionNotEnabled(action::Id),
+	/// The [`Config`](crate::Config) a user was created with has been dropped.
+	ConfigDropped,
+	/// The [`Consts`](crate::Consts) of the [`DeviceCache`](crate::DeviceCache) a user was created with has been dropped.
+	ConstsDropped,
+	/// The gamepad backend (gilrs) could not be initialized.
+	Gamepad(String),
+	/// There is no user at the provided index of [`DeviceCache::users`](crate::DeviceCache::users) (or it has been dropped).
+	UnknownUser(crate::UserId),
+	/// The device is not connected to the [`DeviceCache`](crate::DeviceCache), and has not been connected before.
+	UnknownDevice(crate::device::Id),
+	/// A buffer could not be decoded as a [`Snapshot`](crate::snapshot::Snapshot),
+	/// or a snapshot does not have the same actions as the user it was applied to.
+	MalformedSnapshot,
+	/// A file (such as a [`watched`](crate::DeviceCache::watch_config) bindings file) could not be read.
+	Io(std::io::Error),
+	/// The text of a bindings file could not be parsed, with a description of why.
+	Parse(String),
+	#[cfg(feature = "serde")]
+	Serialization(bincode::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl std::fmt::Display for Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::BehaviorOnSelect => write!(
+				f,
+				"behaviors cannot be added to a select binding, add them to each option instead"
+			),
+			Self::NotAContainer => write!(f, "bindings can only be added to a container binding"),
+			Self::UnsupportedSource { behavior, source } => {
+				write!(
+					f,
+					"behavior {} does not support source {:?}",
+					behavior, source
+				)
+			}
+			Self::UnknownAction(id) => write!(f, "action {:?} was not added to the config", id),
+			Self::UnknownLayout(id) => write!(f, "layout {:?} was not added to the config", id),
+			Self::LayoutCycle(id) => write!(f, "layout {:?} inherits from itself", id),
+			Self::UnknownActionSet(id) => {
+				write!(f, "action set {:?} was not added to the config", id)
+			}
+			Self::ActionNotEnabled(id) => write!(
+				f,
+				"action {:?} is not in any of the user's enabled action sets",
+				id
+			),
+			Self::ConfigDropped => write!(f, "the config has been dropped"),
+			Self::ConstsDropped => write!(f, "the device cache consts have been dropped"),
+			Self::Gamepad(error) => write!(f, "failed to initialize gamepads: {}", error),
+			Self::UnknownUser(id) => write!(f, "there is no user {}", id),
+			Self::UnknownDevice(id) => write!(f, "device {} is not connected", id),
+			Self::MalformedSnapshot => write!(
+				f,
+				"the snapshot is malformed or does not match the user's actions"
+			),
+			Self::Io(error) => write!(f, "{}", error),
+			Self::Parse(error) => write!(f, "failed to parse bindings: {}", error),
+			#[cfg(feature = "serde")]
+			Self::Serialization(error) => write!(f, "{}", error),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<crate::dsl::ParseError> for Error {
+	fn from(error: crate::dsl::ParseError) -> Self {
+		Self::Parse(error.to_string())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl From<bincode::Error> for Error {
+	fn from(error: bincode::Error) -> Self {
+		Self::Serialization(error)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl From<std::io::Error> for Error {
+	fn from(error: std::io::Error) -> Self {
+		Self::Serialization(error.into())
+	}
+}
@@ -1,4 +1,8 @@
-use crate::{binding, device};
+use crate::{
+	binding, device,
+	source::{Key, ScanCode},
+	text::TextInput,
+};
 use crossbeam_channel::{Receiver, Sender};
 
 pub type InputSender = Sender<(binding::Source, State)>;
@@ -8,10 +12,26 @@ pub type InputReceiver = Receiver<(binding::Source, State)>;
 #[derive(Debug, Clone)]
 pub enum Event {
 	Input(device::Id, binding::Source, State),
+	/// A keyboard key changed state. This is sent to users as both a [`Keyboard`](binding::Source::Keyboard)
+	/// input (if the logical key is known) and a [`PhysicalKey`](binding::Source::PhysicalKey) input
+	/// (if the scancode is known), and teaches the [`DeviceCache`](crate::DeviceCache) the user's keyboard layout.
+	Key {
+		logical: Option<Key>,
+		scancode: ScanCode,
+		state: ButtonState,
+	},
 	Window(WindowEvent),
+	/// A device from a [`gamepad backend`](device::GamepadBackend::External) other than gilrs was connected.
+	DeviceConnected(device::Id),
+	/// A device from a [`gamepad backend`](device::GamepadBackend::External) other than gilrs was disconnected.
+	DeviceDisconnected(device::Id),
+	/// Text was typed, which is sent to the user assigned the keyboard while their
+	/// [`text input`](crate::User::start_text_input) is active.
+	Text(TextInput),
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WindowEvent {
 	ResolutionChanged(/*width*/ u32, /*height*/ u32),
 	ScaleFactorChanged(
@@ -19,10 +39,17 @@ pub enum WindowEvent {
 		/*height*/ u32,
 		/*scale factor*/ f64,
 	),
+	/// The window gained (`true`) or lost (`false`) focus.
+	/// Keyboard and mouse input is ignored while the window is not focused (except for releases).
+	Focused(bool),
+	/// The scale factor changed, for backends which send the new resolution separately.
+	/// The screen size keeps its size in pixels until then.
+	ScaleFactor(/*scale factor*/ f64),
 }
 
 /// The state of a [`gamepad`](crate::source::Button) or [`mouse`](crate::source::MouseButton) button.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ButtonState {
 	Pressed,
 	Released,
@@ -38,6 +65,7 @@ pub enum Source {
 /// The data for [`Event`].
 /// Can provide a mouse button/keyboard key state, mouse move, mouse scroll, or mouse button/keyboard key value.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum State {
 	ButtonState(ButtonState),
 	MouseMove(/*delta pixels*/ f64),
@@ -0,0 +1,93 @@
+use crate::{binding, device, event, User, UserId};
+
+/// Creates the user for a new player slot, given the id the user will have.
+pub type UserFactory = Box<dyn FnMut(UserId) -> User + Send + Sync>;
+
+/// Configures how players join and leave a [`DeviceCache`](crate::DeviceCache) in join mode
+/// (see [`with_join`](crate::DeviceCache::with_join)).
+///
+/// In join mode, devices are not given to users automatically. Instead, each unassigned device is held
+/// until it presses one of the join sources, at which point it is assigned to the first user without any devices.
+/// If every user has devices, a new user is created (if a [`user factory`](Join::with_new_users) was provided),
+/// up to the [`maximum number of players`](Join::with_max_users).
+/// The keyboard and mouse join (and leave) together.
+///
+/// A player leaves by pressing one of the leave sources, which returns their devices to the unassigned pool.
+/// Their user is kept, so the next player to join takes their place.
+#[derive(Default)]
+pub struct Join {
+	join_sources: Vec<binding::Source>,
+	leave_sources: Vec<binding::Source>,
+	max_users: Option<usize>,
+	user_factory: Option<UserFactory>,
+}
+
+impl Join {
+	/// Adds a source which joins the game when pressed on an unassigned device
+	/// (like `Button(Start)` on a gamepad, or `Key(Return)` on the keyboard).
+	pub fn with_join_source(mut self, source: binding::Source) -> Self {
+		self.join_sources.push(source);
+		self
+	}
+
+	/// Adds a source which leaves the game when pressed on an assigned device.
+	/// The press is not sent to the user.
+	pub fn with_leave_source(mut self, source: binding::Source) -> Self {
+		self.leave_sources.push(source);
+		self
+	}
+
+	/// Limits how many players can be joined at once. Joins beyond the limit are [`rejected`](Event::Rejected).
+	pub fn with_max_users(mut self, max_users: usize) -> Self {
+		self.max_users = Some(max_users);
+		self
+	}
+
+	/// Creates users for players who join when every existing user has devices.
+	/// Users created this way are owned by the [`DeviceCache`](crate::DeviceCache),
+	/// and are given its [`consts`](crate::DeviceCache::consts).
+	pub fn with_new_users<F>(mut self, factory: F) -> Self
+	where
+		F: FnMut(UserId) -> User + Send + Sync + 'static,
+	{
+		self.user_factory = Some(Box::new(factory));
+		self
+	}
+
+	pub(crate) fn is_join(&self, source: &binding::Source, state: &event::State) -> bool {
+		is_press(state) && self.join_sources.contains(source)
+	}
+
+	pub(crate) fn is_leave(&self, source: &binding::Source, state: &event::State) -> bool {
+		is_press(state) && self.leave_sources.contains(source)
+	}
+
+	pub(crate) fn is_full(&self, joined_users: usize) -> bool {
+		self.max_users
+			.is_some_and(|max_users| joined_users >= max_users)
+	}
+
+	pub(crate) fn user_factory(&mut self) -> Option<&mut UserFactory> {
+		self.user_factory.as_mut()
+	}
+}
+
+fn is_press(state: &event::State) -> bool {
+	matches!(
+		state,
+		event::State::ButtonState(event::ButtonState::Pressed)
+	)
+}
+
+/// A player joining or leaving, as reported by [`DeviceCache::subscribe_to_joins`](crate::DeviceCache::subscribe_to_joins).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+	/// A device pressed a join source, and was assigned to the user at the provided index
+	/// of [`DeviceCache::users`](crate::DeviceCache::users).
+	Joined(UserId, device::Id),
+	/// A user pressed a leave source, and their devices were unassigned.
+	Left(UserId),
+	/// A device pressed a join source, but the maximum number of players had already joined
+	/// (or there was no user for it, and no user factory to create one).
+	Rejected(device::Id),
+}
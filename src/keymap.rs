@@ -0,0 +1,352 @@
+//! Key mappings shared by the winit 0.30 and Bevy integrations, whose key types have the same variants.
+
+use crate::{
+	event,
+	source::{Key, ScanCode},
+	text,
+};
+
+/// How many pixels of a touchpad scroll are equivalent to one line of a mouse wheel.
+pub(crate) const PIXELS_PER_LINE: f64 = 20.0;
+
+/// Returns the events for a key being pressed or released, and the text it typed.
+pub(crate) fn key_events(
+	physical: Option<Key>,
+	logical: Option<Key>,
+	scancode: Option<ScanCode>,
+	state: event::ButtonState,
+	repeat: bool,
+	text: Option<&str>,
+) -> Vec<event::Event> {
+	let mut events = Vec::new();
+	// Held keys are already pressed, but the text they type still repeats.
+	if !repeat {
+		events.push(event::Event::Key {
+			// Keys without a logical key are still bound by their scancode as a physical key.
+			logical,
+			scancode: scancode
+				.or_else(|| physical.and_then(|key| key.scancode()))
+				.unwrap_or_default(),
+			state,
+		});
+	}
+	if state == event::ButtonState::Pressed {
+		if let Some(text) = text {
+			// Control characters (like backspace) are sent as edit keys by the key event instead.
+			for c in text.chars().filter(|c| !c.is_control()) {
+				events.push(event::Event::Text(text::TextInput::Char(c)));
+			}
+		}
+	}
+	events
+}
+
+/// Returns the key typed by a single character on a US-QWERTY keyboard, if the text is one character.
+pub(crate) fn character_key(text: &str) -> Option<Key> {
+	let mut chars = text.chars();
+	match (chars.next(), chars.next()) {
+		(Some(c), None) => Key::from_char(c),
+		_ => None,
+	}
+}
+
+/// Matches a `KeyCode` to the key at its position on a US-QWERTY keyboard, as an `Option<Key>`.
+macro_rules! key_from_code {
+	($KeyCode:ident, $code:expr) => {
+		match $code {
+			$KeyCode::Backquote => Some($crate::source::Key::Grave),
+			$KeyCode::Backslash => Some($crate::source::Key::Backslash),
+			$KeyCode::BracketLeft => Some($crate::source::Key::LBracket),
+			$KeyCode::BracketRight => Some($crate::source::Key::RBracket),
+			$KeyCode::Comma => Some($crate::source::Key::Comma),
+			$KeyCode::Digit0 => Some($crate::source::Key::Key0),
+			$KeyCode::Digit1 => Some($crate::source::Key::Key1),
+			$KeyCode::Digit2 => Some($crate::source::Key::Key2),
+			$KeyCode::Digit3 => Some($crate::source::Key::Key3),
+			$KeyCode::Digit4 => Some($crate::source::Key::Key4),
+			$KeyCode::Digit5 => Some($crate::source::Key::Key5),
+			$KeyCode::Digit6 => Some($crate::source::Key::Key6),
+			$KeyCode::Digit7 => Some($crate::source::Key::Key7),
+			$KeyCode::Digit8 => Some($crate::source::Key::Key8),
+			$KeyCode::Digit9 => Some($crate::source::Key::Key9),
+			$KeyCode::Equal => Some($crate::source::Key::Equals),
+			$KeyCode::IntlBackslash => Some($crate::source::Key::OEM102),
+			$KeyCode::IntlRo => Some($crate::source::Key::AbntC1),
+			$KeyCode::IntlYen => Some($crate::source::Key::Yen),
+			$KeyCode::KeyA => Some($crate::source::Key::A),
+			$KeyCode::KeyB => Some($crate::source::Key::B),
+			$KeyCode::KeyC => Some($crate::source::Key::C),
+			$KeyCode::KeyD => Some($crate::source::Key::D),
+			$KeyCode::KeyE => Some($crate::source::Key::E),
+			$KeyCode::KeyF => Some($crate::source::Key::F),
+			$KeyCode::KeyG => Some($crate::source::Key::G),
+			$KeyCode::KeyH => Some($crate::source::Key::H),
+			$KeyCode::KeyI => Some($crate::source::Key::I),
+			$KeyCode::KeyJ => Some($crate::source::Key::J),
+			$KeyCode::KeyK => Some($crate::source::Key::K),
+			$KeyCode::KeyL => Some($crate::source::Key::L),
+			$KeyCode::KeyM => Some($crate::source::Key::M),
+			$KeyCode::KeyN => Some($crate::source::Key::N),
+			$KeyCode::KeyO => Some($crate::source::Key::O),
+			$KeyCode::KeyP => Some($crate::source::Key::P),
+			$KeyCode::KeyQ => Some($crate::source::Key::Q),
+			$KeyCode::KeyR => Some($crate::source::Key::R),
+			$KeyCode::KeyS => Some($crate::source::Key::S),
+			$KeyCode::KeyT => Some($crate::source::Key::T),
+			$KeyCode::KeyU => Some($crate::source::Key::U),
+			$KeyCode::KeyV => Some($crate::source::Key::V),
+			$KeyCode::KeyW => Some($crate::source::Key::W),
+			$KeyCode::KeyX => Some($crate::source::Key::X),
+			$KeyCode::KeyY => Some($crate::source::Key::Y),
+			$KeyCode::KeyZ => Some($crate::source::Key::Z),
+			$KeyCode::Minus => Some($crate::source::Key::Minus),
+			$KeyCode::Period => Some($crate::source::Key::Period),
+			$KeyCode::Quote => Some($crate::source::Key::Apostrophe),
+			$KeyCode::Semicolon => Some($crate::source::Key::Semicolon),
+			$KeyCode::Slash => Some($crate::source::Key::Slash),
+			$KeyCode::AltLeft => Some($crate::source::Key::LAlt),
+			$KeyCode::AltRight => Some($crate::source::Key::RAlt),
+			$KeyCode::Backspace => Some($crate::source::Key::Back),
+			$KeyCode::CapsLock => Some($crate::source::Key::CapitalLock),
+			$KeyCode::ContextMenu => Some($crate::source::Key::Apps),
+			$KeyCode::ControlLeft => Some($crate::source::Key::LControl),
+			$KeyCode::ControlRight => Some($crate::source::Key::RControl),
+			$KeyCode::Enter => Some($crate::source::Key::Return),
+			$KeyCode::SuperLeft => Some($crate::source::Key::LWin),
+			$KeyCode::SuperRight => Some($crate::source::Key::RWin),
+			$KeyCode::ShiftLeft => Some($crate::source::Key::LShift),
+			$KeyCode::ShiftRight => Some($crate::source::Key::RShift),
+			$KeyCode::Space => Some($crate::source::Key::Space),
+			$KeyCode::Tab => Some($crate::source::Key::Tab),
+			$KeyCode::Convert => Some($crate::source::Key::Convert),
+			$KeyCode::KanaMode => Some($crate::source::Key::Kana),
+			$KeyCode::NonConvert => Some($crate::source::Key::NoConvert),
+			$KeyCode::Delete => Some($crate::source::Key::Delete),
+			$KeyCode::End => Some($crate::source::Key::End),
+			$KeyCode::Home => Some($crate::source::Key::Home),
+			$KeyCode::Insert => Some($crate::source::Key::Insert),
+			$KeyCode::PageDown => Some($crate::source::Key::PageDown),
+			$KeyCode::PageUp => Some($crate::source::Key::PageUp),
+			$KeyCode::ArrowDown => Some($crate::source::Key::Down),
+			$KeyCode::ArrowLeft => Some($crate::source::Key::Left),
+			$KeyCode::ArrowRight => Some($crate::source::Key::Right),
+			$KeyCode::ArrowUp => Some($crate::source::Key::Up),
+			$KeyCode::NumLock => Some($crate::source::Key::Numlock),
+			$KeyCode::Numpad0 => Some($crate::source::Key::Numpad0),
+			$KeyCode::Numpad1 => Some($crate::source::Key::Numpad1),
+			$KeyCode::Numpad2 => Some($crate::source::Key::Numpad2),
+			$KeyCode::Numpad3 => Some($crate::source::Key::Numpad3),
+			$KeyCode::Numpad4 => Some($crate::source::Key::Numpad4),
+			$KeyCode::Numpad5 => Some($crate::source::Key::Numpad5),
+			$KeyCode::Numpad6 => Some($crate::source::Key::Numpad6),
+			$KeyCode::Numpad7 => Some($crate::source::Key::Numpad7),
+			$KeyCode::Numpad8 => Some($crate::source::Key::Numpad8),
+			$KeyCode::Numpad9 => Some($crate::source::Key::Numpad9),
+			$KeyCode::NumpadAdd => Some($crate::source::Key::NumpadPlus),
+			$KeyCode::NumpadComma => Some($crate::source::Key::NumpadComma),
+			$KeyCode::NumpadDecimal => Some($crate::source::Key::NumpadDecimal),
+			$KeyCode::NumpadDivide => Some($crate::source::Key::NumpadSlash),
+			$KeyCode::NumpadEnter => Some($crate::source::Key::NumpadEnter),
+			$KeyCode::NumpadEqual => Some($crate::source::Key::NumpadEquals),
+			$KeyCode::NumpadMultiply | $KeyCode::NumpadStar => {
+				Some($crate::source::Key::NumpadAsterisk)
+			}
+			$KeyCode::NumpadSubtract => Some($crate::source::Key::NumpadMinus),
+			$KeyCode::Escape => Some($crate::source::Key::Escape),
+			$KeyCode::PrintScreen => Some($crate::source::Key::Snapshot),
+			$KeyCode::ScrollLock => Some($crate::source::Key::ScrollLock),
+			$KeyCode::Pause => Some($crate::source::Key::Pause),
+			$KeyCode::BrowserBack => Some($crate::source::Key::WebBack),
+			$KeyCode::BrowserFavorites => Some($crate::source::Key::WebFavorites),
+			$KeyCode::BrowserForward => Some($crate::source::Key::WebForward),
+			$KeyCode::BrowserHome => Some($crate::source::Key::WebHome),
+			$KeyCode::BrowserRefresh => Some($crate::source::Key::WebRefresh),
+			$KeyCode::BrowserSearch => Some($crate::source::Key::WebSearch),
+			$KeyCode::BrowserStop => Some($crate::source::Key::WebStop),
+			$KeyCode::LaunchApp1 => Some($crate::source::Key::MyComputer),
+			$KeyCode::LaunchApp2 => Some($crate::source::Key::Calculator),
+			$KeyCode::LaunchMail => Some($crate::source::Key::Mail),
+			$KeyCode::MediaPlayPause => Some($crate::source::Key::PlayPause),
+			$KeyCode::MediaSelect => Some($crate::source::Key::MediaSelect),
+			$KeyCode::MediaStop => Some($crate::source::Key::MediaStop),
+			$KeyCode::MediaTrackNext => Some($crate::source::Key::NextTrack),
+			$KeyCode::MediaTrackPrevious => Some($crate::source::Key::PrevTrack),
+			$KeyCode::Power => Some($crate::source::Key::Power),
+			$KeyCode::Sleep => Some($crate::source::Key::Sleep),
+			$KeyCode::AudioVolumeDown => Some($crate::source::Key::VolumeDown),
+			$KeyCode::AudioVolumeMute => Some($crate::source::Key::Mute),
+			$KeyCode::AudioVolumeUp => Some($crate::source::Key::VolumeUp),
+			$KeyCode::WakeUp => Some($crate::source::Key::Wake),
+			$KeyCode::Copy => Some($crate::source::Key::Copy),
+			$KeyCode::Cut => Some($crate::source::Key::Cut),
+			$KeyCode::Paste => Some($crate::source::Key::Paste),
+			$KeyCode::F1 => Some($crate::source::Key::F1),
+			$KeyCode::F2 => Some($crate::source::Key::F2),
+			$KeyCode::F3 => Some($crate::source::Key::F3),
+			$KeyCode::F4 => Some($crate::source::Key::F4),
+			$KeyCode::F5 => Some($crate::source::Key::F5),
+			$KeyCode::F6 => Some($crate::source::Key::F6),
+			$KeyCode::F7 => Some($crate::source::Key::F7),
+			$KeyCode::F8 => Some($crate::source::Key::F8),
+			$KeyCode::F9 => Some($crate::source::Key::F9),
+			$KeyCode::F10 => Some($crate::source::Key::F10),
+			$KeyCode::F11 => Some($crate::source::Key::F11),
+			$KeyCode::F12 => Some($crate::source::Key::F12),
+			$KeyCode::F13 => Some($crate::source::Key::F13),
+			$KeyCode::F14 => Some($crate::source::Key::F14),
+			$KeyCode::F15 => Some($crate::source::Key::F15),
+			$KeyCode::F16 => Some($crate::source::Key::F16),
+			$KeyCode::F17 => Some($crate::source::Key::F17),
+			$KeyCode::F18 => Some($crate::source::Key::F18),
+			$KeyCode::F19 => Some($crate::source::Key::F19),
+			$KeyCode::F20 => Some($crate::source::Key::F20),
+			$KeyCode::F21 => Some($crate::source::Key::F21),
+			$KeyCode::F22 => Some($crate::source::Key::F22),
+			$KeyCode::F23 => Some($crate::source::Key::F23),
+			$KeyCode::F24 => Some($crate::source::Key::F24),
+			_ => None,
+		}
+	};
+}
+pub(crate) use key_from_code;
+
+/// Matches a `NativeKeyCode` to an unknown key with the scancode it has elsewhere, as an `Option<Key>`.
+macro_rules! key_from_native {
+	($NativeKeyCode:ident, $native:expr) => {
+		match $native {
+			$NativeKeyCode::Unidentified => None,
+			$NativeKeyCode::Android(code) => Some($crate::source::Key::Unknown(code)),
+			$NativeKeyCode::MacOS(code) => Some($crate::source::Key::Unknown(
+				code as $crate::source::ScanCode,
+			)),
+			$NativeKeyCode::Windows(code) => Some($crate::source::Key::Unknown(
+				code as $crate::source::ScanCode,
+			)),
+			// XKB keycodes are offset by 8 from evdev scancodes.
+			$NativeKeyCode::Xkb(code) => Some($crate::source::Key::Unknown(code.saturating_sub(8))),
+		}
+	};
+}
+pub(crate) use key_from_native;
+
+/// Matches a named logical key (other than modifiers, whose side each integration reports differently)
+/// to the key which types it on a US-QWERTY keyboard, as an `Option<Key>`.
+macro_rules! key_from_named {
+	($NamedKey:ident, $key:expr) => {
+		match $key {
+			$NamedKey::CapsLock => Some($crate::source::Key::CapitalLock),
+			$NamedKey::NumLock => Some($crate::source::Key::Numlock),
+			$NamedKey::ScrollLock => Some($crate::source::Key::ScrollLock),
+			$NamedKey::Enter => Some($crate::source::Key::Return),
+			$NamedKey::Tab => Some($crate::source::Key::Tab),
+			$NamedKey::Space => Some($crate::source::Key::Space),
+			$NamedKey::ArrowDown => Some($crate::source::Key::Down),
+			$NamedKey::ArrowLeft => Some($crate::source::Key::Left),
+			$NamedKey::ArrowRight => Some($crate::source::Key::Right),
+			$NamedKey::ArrowUp => Some($crate::source::Key::Up),
+			$NamedKey::End => Some($crate::source::Key::End),
+			$NamedKey::Home => Some($crate::source::Key::Home),
+			$NamedKey::PageDown => Some($crate::source::Key::PageDown),
+			$NamedKey::PageUp => Some($crate::source::Key::PageUp),
+			$NamedKey::Backspace => Some($crate::source::Key::Back),
+			$NamedKey::Delete => Some($crate::source::Key::Delete),
+			$NamedKey::Insert => Some($crate::source::Key::Insert),
+			$NamedKey::Escape => Some($crate::source::Key::Escape),
+			$NamedKey::Pause => Some($crate::source::Key::Pause),
+			$NamedKey::PrintScreen => Some($crate::source::Key::Snapshot),
+			$NamedKey::ContextMenu => Some($crate::source::Key::Apps),
+			$NamedKey::Copy => Some($crate::source::Key::Copy),
+			$NamedKey::Cut => Some($crate::source::Key::Cut),
+			$NamedKey::Paste => Some($crate::source::Key::Paste),
+			$NamedKey::Compose => Some($crate::source::Key::Compose),
+			$NamedKey::Convert => Some($crate::source::Key::Convert),
+			$NamedKey::NonConvert => Some($crate::source::Key::NoConvert),
+			$NamedKey::KanaMode => Some($crate::source::Key::Kana),
+			$NamedKey::KanjiMode => Some($crate::source::Key::Kanji),
+			$NamedKey::Power => Some($crate::source::Key::Power),
+			$NamedKey::Standby => Some($crate::source::Key::Sleep),
+			$NamedKey::WakeUp => Some($crate::source::Key::Wake),
+			$NamedKey::MediaPlayPause => Some($crate::source::Key::PlayPause),
+			$NamedKey::MediaStop => Some($crate::source::Key::MediaStop),
+			$NamedKey::MediaTrackNext => Some($crate::source::Key::NextTrack),
+			$NamedKey::MediaTrackPrevious => Some($crate::source::Key::PrevTrack),
+			$NamedKey::AudioVolumeDown => Some($crate::source::Key::VolumeDown),
+			$NamedKey::AudioVolumeUp => Some($crate::source::Key::VolumeUp),
+			$NamedKey::AudioVolumeMute => Some($crate::source::Key::Mute),
+			$NamedKey::LaunchMail => Some($crate::source::Key::Mail),
+			$NamedKey::LaunchApplication1 => Some($crate::source::Key::MyComputer),
+			$NamedKey::LaunchApplication2 => Some($crate::source::Key::Calculator),
+			$NamedKey::BrowserBack => Some($crate::source::Key::WebBack),
+			$NamedKey::BrowserFavorites => Some($crate::source::Key::WebFavorites),
+			$NamedKey::BrowserForward => Some($crate::source::Key::WebForward),
+			$NamedKey::BrowserHome => Some($crate::source::Key::WebHome),
+			$NamedKey::BrowserRefresh => Some($crate::source::Key::WebRefresh),
+			$NamedKey::BrowserSearch => Some($crate::source::Key::WebSearch),
+			$NamedKey::BrowserStop => Some($crate::source::Key::WebStop),
+			$NamedKey::F1 => Some($crate::source::Key::F1),
+			$NamedKey::F2 => Some($crate::source::Key::F2),
+			$NamedKey::F3 => Some($crate::source::Key::F3),
+			$NamedKey::F4 => Some($crate::source::Key::F4),
+			$NamedKey::F5 => Some($crate::source::Key::F5),
+			$NamedKey::F6 => Some($crate::source::Key::F6),
+			$NamedKey::F7 => Some($crate::source::Key::F7),
+			$NamedKey::F8 => Some($crate::source::Key::F8),
+			$NamedKey::F9 => Some($crate::source::Key::F9),
+			$NamedKey::F10 => Some($crate::source::Key::F10),
+			$NamedKey::F11 => Some($crate::source::Key::F11),
+			$NamedKey::F12 => Some($crate::source::Key::F12),
+			$NamedKey::F13 => Some($crate::source::Key::F13),
+			$NamedKey::F14 => Some($crate::source::Key::F14),
+			$NamedKey::F15 => Some($crate::source::Key::F15),
+			$NamedKey::F16 => Some($crate::source::Key::F16),
+			$NamedKey::F17 => Some($crate::source::Key::F17),
+			$NamedKey::F18 => Some($crate::source::Key::F18),
+			$NamedKey::F19 => Some($crate::source::Key::F19),
+			$NamedKey::F20 => Some($crate::source::Key::F20),
+			$NamedKey::F21 => Some($crate::source::Key::F21),
+			$NamedKey::F22 => Some($crate::source::Key::F22),
+			$NamedKey::F23 => Some($crate::source::Key::F23),
+			$NamedKey::F24 => Some($crate::source::Key::F24),
+			_ => None,
+		}
+	};
+}
+pub(crate) use key_from_named;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn repeated_keys_only_type_text() {
+		let events = key_events(
+			Some(Key::A),
+			Some(Key::Q),
+			None,
+			event::ButtonState::Pressed,
+			true,
+			Some("q"),
+		);
+		assert!(matches!(
+			events.as_slice(),
+			[event::Event::Text(text::TextInput::Char('q'))]
+		));
+
+		let events = key_events(
+			Some(Key::A),
+			None,
+			None,
+			event::ButtonState::Released,
+			false,
+			Some("q"),
+		);
+		assert!(matches!(
+			events.as_slice(),
+			[event::Event::Key {
+				logical: None,
+				scancode,
+				state: event::ButtonState::Released,
+			}] if Some(*scancode) == Key::A.scancode()
+		));
+	}
+}
@@ -106,10 +106,54 @@
 //! 	}
 //! });
 //! ```
+//! Keyboard input is read from window events, so it is ignored while the window is not focused.
+//! To read it regardless of focus, use [`parse_winit_event_with`](crate::winit::parse_winit_event_with)
+//! and [`KeyboardEvents::Device`](crate::winit::KeyboardEvents::Device).
+//!
+//! For winit 0.30, enable the `winit-0_30` feature instead and send the events from your `ApplicationHandler`
+//! via [`winit_0_30::parse_window_event`](crate::winit_0_30::parse_window_event) and
+//! [`winit_0_30::parse_device_event`](crate::winit_0_30::parse_device_event).
+//!
+//! For SDL2, enable the `sdl2` feature and send events via [`sdl2::parse_sdl_event`](crate::sdl2::parse_sdl_event).
+//! SDL can also replace gilrs for gamepads, via [`GamepadBackend::External`](device::GamepadBackend::External)
+//! and [`sdl2::Controllers`](crate::sdl2::Controllers).
+//!
+//! For terminal applications, enable the `crossterm` feature and send events via a
+//! [`crossterm::Terminal`](crate::crossterm::Terminal), which also releases keys for terminals that only report presses.
+//!
+//! For Bevy, enable the `bevy` feature and add the [`bevy::InputActionsPlugin`](crate::bevy::InputActionsPlugin),
+//! which owns the device cache and users, and publishes their action states to the
+//! [`ActionStates`](crate::bevy::ActionStates) resource and [`ActionUser`](crate::bevy::ActionUser) components.
 //!
 //! The input-actions system also supports logging via the `log` feature:
 //! `input-actions = { version = "...", features = ["log"] }`
 //!
+//! Input sent to users can be captured via [`DeviceCache::start_recording`] and replayed via [`DeviceCache::start_playback`].
+//! Enabling the `serde` feature allows [`recordings`](recording::Recording) to be saved to and loaded from disk:
+//! `input-actions = { version = "...", features = ["serde"] }`
+//!
+//! The same feature allows each user's rebinds, layout, and settings to be persisted as a
+//! [`Profile`](profile::Profile) via a [`ProfileStore`](profile::ProfileStore).
+//!
+//! Bindings can also be written in a compact [`text syntax`](dsl), like `move_x = Key(D) - Key(A) | Axis(LThumbstickX)`.
+//!
+//! Text (including IME composition) can be received by a user via [`User::start_text_input`],
+//! which suspends their keyboard actions until [`User::stop_text_input`].
+//!
+//! Devices are given to users according to an [`AssignmentPolicy`](device::AssignmentPolicy)
+//! (see [`DeviceCache::with_assignment_policy`]), and can be moved between users via [`DeviceCache::assign`] and [`DeviceCache::unassign`].
+//!
+//! Devices connecting, disconnecting, and changing users are reported via [`DeviceCache::subscribe_to_devices`]
+//! (like showing "reconnect your controller to continue"), and [`DeviceCache::devices`] describes each device and its owner.
+//! Gamepads are recognized across reconnects (and launches) by their [`Identity`](device::Identity),
+//! which a [`ProfileStore`](profile::ProfileStore) can use to pick the profile a gamepad was last used with.
+//!
+//! For local multiplayer, [`DeviceCache::with_join`] holds each device until it presses a join source (like "press Start to join"),
+//! creating or filling a user for each player up to a maximum, and reporting joins and leaves via [`DeviceCache::subscribe_to_joins`].
+//!
+//! During development, the `hot-reload` feature allows action sets to be reloaded from a file whenever it changes,
+//! via [`DeviceCache::watch_config`].
+//!
 
 #[cfg(feature = "log")]
 extern crate log;
@@ -118,6 +162,25 @@ extern crate log;
 #[cfg(feature = "winit")]
 pub mod winit;
 
+/// Submodule for handling integration with `winit` 0.30 when the `winit-0_30` feature is enabled.
+#[cfg(feature = "winit-0_30")]
+pub mod winit_0_30;
+
+/// Submodule for handling integration with `sdl2` when the feature is enabled.
+#[cfg(feature = "sdl2")]
+pub mod sdl2;
+
+/// Submodule for handling integration with `crossterm` when the feature is enabled.
+#[cfg(feature = "crossterm")]
+pub mod crossterm;
+
+/// Submodule for integrating with `bevy` as a plugin when the feature is enabled.
+#[cfg(feature = "bevy")]
+pub mod bevy;
+
+#[cfg(any(feature = "winit-0_30", feature = "bevy"))]
+mod keymap;
+
 pub static LOG: &'static str = "input-actions";
 pub static DEPENDENCY_LOG_TARGETS: [&'static str; 2] = ["gilrs", "rusty_xinput"];
 
@@ -136,12 +199,43 @@ pub mod source;
 /// Data sent to input-actions when device inputs are detected.
 pub mod event;
 
+/// A compact text syntax for writing bindings.
+pub mod dsl;
+
+/// Capturing and replaying the input sent to users, for bug reports and automated playtests.
+pub mod recording;
+
+/// Names and glyphs of bound inputs, for on-screen prompts.
+pub mod prompt;
+
+/// Saving and loading a player's customized bindings and settings.
+pub mod profile;
+
+/// Reloading binding files while the application is running, for tuning controls during development.
+#[cfg(feature = "hot-reload")]
+pub mod reload;
+
+/// Text typed by a user, separate from their actions, for chat boxes and name entry.
+pub mod text;
+
+/// Compact per-tick copies of a user's action states, for sending input over a network.
+pub mod snapshot;
+
+/// Players joining and leaving by pressing a button on an unassigned device, for local multiplayer.
+pub mod join;
+
+mod error;
+pub use error::*;
+
 mod system;
 pub use system::*;
 
 mod user;
 pub use user::*;
 
+mod settings;
+pub use settings::*;
+
 pub mod prelude {
 	pub use crate::{
 		action::{behavior::*, BehaviorBinding, Id},
@@ -0,0 +1,209 @@
+#[cfg(feature = "serde")]
+use crate::device::{GamepadKind, Identity};
+use crate::{binding, Settings};
+use std::collections::HashMap;
+
+/// A player's customizations on top of the bindings in a [`Config`](crate::Config):
+/// the sources they have rebound, their chosen layout, and their [`settings`](Settings).
+///
+/// Actions and layouts are stored by name, so profiles saved by older versions of an application
+/// can still be [`applied`](crate::User::apply_profile) after actions are added or removed;
+/// anything which no longer exists in the config is dropped and reported via [`Migration`].
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Profile {
+	/// The [`version`](crate::Config::version) of the config the profile was created with.
+	pub version: u32,
+	pub layout: Option<String>,
+	/// For each action (by name), the pairs of `(original, replacement)` sources in its bindings.
+	pub rebinds: HashMap<String, Vec<(binding::Source, binding::Source)>>,
+	pub settings: Settings,
+}
+
+/// The parts of a [`Profile`] which could not be applied because they no longer exist in the [`Config`](crate::Config).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Migration {
+	/// The version of the config the profile was saved with.
+	pub from_version: u32,
+	/// The version of the config the profile was applied to.
+	pub to_version: u32,
+	/// The names of rebound actions which are not in the config.
+	pub dropped_actions: Vec<String>,
+	/// The layout of the profile, if it is not in the config.
+	pub dropped_layout: Option<String>,
+}
+
+impl Migration {
+	/// Returns true if the entire profile was applied.
+	pub fn is_lossless(&self) -> bool {
+		self.dropped_actions.is_empty() && self.dropped_layout.is_none()
+	}
+}
+
+/// A directory of [`profiles`](Profile), keyed by profile name and optionally by [`GamepadKind`].
+///
+/// The store can also remember which profile each gamepad was used with (by its [`Identity`]),
+/// so a player's profile can be picked when their gamepad connects in a later launch.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone)]
+pub struct ProfileStore {
+	directory: std::path::PathBuf,
+}
+
+#[cfg(feature = "serde")]
+impl ProfileStore {
+	pub fn new<P: Into<std::path::PathBuf>>(directory: P) -> Self {
+		Self {
+			directory: directory.into(),
+		}
+	}
+
+	/// Returns the file a profile is stored in.
+	/// Profiles for a specific gamepad are stored separately from the profile with the same name.
+	///
+	/// Names are escaped so that any name (like a player's chosen name) is a single file in the directory;
+	/// characters other than ASCII letters, digits, `-`, and `_` are written as `%XX` for each of their bytes.
+	pub fn path(&self, name: &str, gamepad: Option<GamepadKind>) -> std::path::PathBuf {
+		let name = escape_file_name(name);
+		match gamepad {
+			Some(kind) => self.directory.join(format!("{}.{:?}.profile", name, kind)),
+			None => self.directory.join(format!("{}.profile", name)),
+		}
+	}
+
+	pub fn save(
+		&self,
+		name: &str,
+		gamepad: Option<GamepadKind>,
+		profile: &Profile,
+	) -> Result<(), crate::Error> {
+		std::fs::create_dir_all(&self.directory)?;
+		let file = std::fs::File::create(self.path(name, gamepad))?;
+		Ok(bincode::serialize_into(
+			std::io::BufWriter::new(file),
+			profile,
+		)?)
+	}
+
+	/// Loads a profile, falling back to the profile without a gamepad kind
+	/// if there is no profile for the specific gamepad. Returns `None` if neither exist.
+	pub fn load(
+		&self,
+		name: &str,
+		gamepad: Option<GamepadKind>,
+	) -> Result<Option<Profile>, crate::Error> {
+		let mut path = self.path(name, gamepad);
+		if !path.exists() && gamepad.is_some() {
+			path = self.path(name, None);
+		}
+		if !path.exists() {
+			return Ok(None);
+		}
+		let file = std::fs::File::open(path)?;
+		Ok(Some(bincode::deserialize_from(std::io::BufReader::new(
+			file,
+		))?))
+	}
+
+	/// Remembers that a gamepad was used with the profile of the provided name,
+	/// replacing the profile it was previously used with.
+	pub fn remember_device(&self, identity: &Identity, name: &str) -> Result<(), crate::Error> {
+		let mut devices = self.load_devices()?;
+		devices.retain(|(other, _)| other != identity);
+		devices.push((identity.clone(), name.to_owned()));
+		std::fs::create_dir_all(&self.directory)?;
+		let file = std::fs::File::create(self.devices_path())?;
+		Ok(bincode::serialize_into(
+			std::io::BufWriter::new(file),
+			&devices,
+		)?)
+	}
+
+	/// Returns the name of the profile a gamepad was last used with.
+	/// If the gamepad has not been remembered, falls back to the most recently remembered gamepad
+	/// which [`matches`](Identity::matches) it (like another gamepad of the same model).
+	pub fn device_profile(&self, identity: &Identity) -> Result<Option<String>, crate::Error> {
+		let devices = self.load_devices()?;
+		let name = devices
+			.iter()
+			.find(|(other, _)| other == identity)
+			.or_else(|| {
+				devices
+					.iter()
+					.rev()
+					.find(|(other, _)| other.matches(identity))
+			})
+			.map(|(_, name)| name.clone());
+		Ok(name)
+	}
+
+	/// Returns the file which lists the profile each remembered gamepad was used with.
+	fn devices_path(&self) -> std::path::PathBuf {
+		self.directory.join("devices.index")
+	}
+
+	fn load_devices(&self) -> Result<Vec<(Identity, String)>, crate::Error> {
+		let path = self.devices_path();
+		if !path.exists() {
+			return Ok(Vec::new());
+		}
+		let file = std::fs::File::open(path)?;
+		Ok(bincode::deserialize_from(std::io::BufReader::new(file))?)
+	}
+}
+
+/// Escapes a profile name so it cannot contain path separators or `..`.
+#[cfg(feature = "serde")]
+fn escape_file_name(name: &str) -> String {
+	let mut escaped = String::with_capacity(name.len());
+	for byte in name.bytes() {
+		match byte {
+			b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => escaped.push(byte as char),
+			_ => escaped.push_str(&format!("%{:02X}", byte)),
+		}
+	}
+	escaped
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn names_cannot_leave_the_directory() {
+		let store = ProfileStore::new("profiles");
+		let path = store.path("../evil/name", None);
+		assert_eq!(path.parent(), Some(std::path::Path::new("profiles")));
+		assert_eq!(path.file_name().unwrap(), "%2E%2E%2Fevil%2Fname.profile");
+		assert_eq!(
+			store.path("player_1", Some(GamepadKind::DualAxisGamepad)),
+			std::path::Path::new("profiles").join("player_1.DualAxisGamepad.profile")
+		);
+	}
+
+	#[test]
+	fn profiles_round_trip() {
+		let directory =
+			std::env::temp_dir().join(format!("input-actions-profiles-{}", std::process::id()));
+		let store = ProfileStore::new(&directory);
+		let profile = Profile {
+			version: 3,
+			layout: Some("left_handed".to_owned()),
+			..Default::default()
+		};
+		store.save("../player", None, &profile).unwrap();
+		assert_eq!(
+			store.load("../player", None).unwrap(),
+			Some(profile.clone())
+		);
+		// Gamepad profiles fall back to the profile without a gamepad kind.
+		assert_eq!(
+			store
+				.load("../player", Some(GamepadKind::DualAxisGamepad))
+				.unwrap(),
+			Some(profile)
+		);
+		assert_eq!(store.load("nobody", None).unwrap(), None);
+		std::fs::remove_dir_all(directory).unwrap();
+	}
+}
@@ -0,0 +1,363 @@
+use crate::{
+	binding::{self, Source},
+	device::GamepadKind,
+	source::{Axis, Button, ConfirmButtons, Key, KeyboardLayout, MouseButton},
+};
+use std::collections::HashMap;
+
+/// How a bound [`source`](Source) should be shown to the player, for prompts like "Press \[A\] to jump".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Prompt {
+	/// The source as it is bound, which may be a virtual button like [`VirtualConfirm`](Button::VirtualConfirm).
+	pub source: Source,
+	/// The human-readable name of the physical input (like `Cross`, `A`, or `Left Shift`).
+	pub name: String,
+	/// An identifier for the image of the physical input (like `ps4/cross` or `keyboard/lshift`),
+	/// which applications can map to their own textures.
+	pub glyph: String,
+}
+
+/// The table used to turn [`sources`](Source) into [`prompts`](Prompt).
+/// Names and glyphs default to those of each [`GamepadKind`], and can be overridden per physical source.
+#[derive(Debug, Clone)]
+pub struct GlyphSet {
+	names: HashMap<Source, String>,
+	glyphs: HashMap<Source, String>,
+	/// The kind of gamepad whose names and glyphs are used for [`DualAxisGamepad`](GamepadKind::DualAxisGamepad).
+	generic_style: GamepadKind,
+}
+
+impl Default for GlyphSet {
+	fn default() -> Self {
+		Self {
+			names: HashMap::new(),
+			glyphs: HashMap::new(),
+			generic_style: GamepadKind::DualAxisGamepad,
+		}
+	}
+}
+
+impl GlyphSet {
+	/// Overrides the name of a physical source.
+	pub fn with_name(mut self, source: Source, name: &str) -> Self {
+		self.names.insert(source, name.to_owned());
+		self
+	}
+
+	/// Overrides the glyph identifier of a physical source.
+	pub fn with_glyph(mut self, source: Source, glyph: &str) -> Self {
+		self.glyphs.insert(source, glyph.to_owned());
+		self
+	}
+
+	/// Shows sources of [`DualAxisGamepad`](GamepadKind::DualAxisGamepad) using the names and glyphs of another kind of gamepad.
+	pub fn with_generic_style(mut self, kind: GamepadKind) -> Self {
+		self.generic_style = kind;
+		self
+	}
+
+	/// Returns the source which is shown for a bound source: virtual buttons are shown as the face button which sends them,
+	/// and [`physical keys`](Source::PhysicalKey) are shown as the key labeled at that position in the keyboard layout.
+	pub fn resolve_with(
+		&self,
+		source: Source,
+		layout: &KeyboardLayout,
+		confirm_buttons: &ConfirmButtons,
+	) -> Source {
+		match source {
+			Source::PhysicalKey(key) => Source::Keyboard(layout.logical(key)),
+			Source::Gamepad(kind, binding::Gamepad::Button(button)) => Source::Gamepad(
+				kind,
+				binding::Gamepad::Button(confirm_buttons.physical_button(kind, button)),
+			),
+			_ => source,
+		}
+	}
+
+	/// Returns the physical source which a source is shown as, using the default keyboard layout and confirm buttons.
+	pub fn resolve(&self, source: Source) -> Source {
+		self.resolve_with(
+			source,
+			&KeyboardLayout::qwerty(),
+			&ConfirmButtons::default(),
+		)
+	}
+
+	pub fn name(&self, source: Source) -> String {
+		self.name_with(
+			source,
+			&KeyboardLayout::qwerty(),
+			&ConfirmButtons::default(),
+		)
+	}
+
+	pub fn name_with(
+		&self,
+		source: Source,
+		layout: &KeyboardLayout,
+		confirm_buttons: &ConfirmButtons,
+	) -> String {
+		let physical = self.resolve_with(source, layout, confirm_buttons);
+		match self.names.get(&physical) {
+			Some(name) => name.clone(),
+			None => default_name(physical, self.style(physical)),
+		}
+	}
+
+	pub fn glyph(&self, source: Source) -> String {
+		self.glyph_with(
+			source,
+			&KeyboardLayout::qwerty(),
+			&ConfirmButtons::default(),
+		)
+	}
+
+	pub fn glyph_with(
+		&self,
+		source: Source,
+		layout: &KeyboardLayout,
+		confirm_buttons: &ConfirmButtons,
+	) -> String {
+		let physical = self.resolve_with(source, layout, confirm_buttons);
+		match self.glyphs.get(&physical) {
+			Some(glyph) => glyph.clone(),
+			None => default_glyph(physical, self.style(physical)),
+		}
+	}
+
+	pub fn prompt(&self, source: Source) -> Prompt {
+		self.prompt_with(
+			source,
+			&KeyboardLayout::qwerty(),
+			&ConfirmButtons::default(),
+		)
+	}
+
+	/// Returns the prompt for a source, naming [`physical keys`](Source::PhysicalKey) by their label in a keyboard layout,
+	/// and virtual buttons by the face button which sends them (see [`DeviceCache::confirm_buttons`](crate::DeviceCache::confirm_buttons)).
+	pub fn prompt_with(
+		&self,
+		source: Source,
+		layout: &KeyboardLayout,
+		confirm_buttons: &ConfirmButtons,
+	) -> Prompt {
+		Prompt {
+			source,
+			name: self.name_with(source, layout, confirm_buttons),
+			glyph: self.glyph_with(source, layout, confirm_buttons),
+		}
+	}
+
+	fn style(&self, source: Source) -> Option<GamepadKind> {
+		match source {
+			Source::Gamepad(GamepadKind::DualAxisGamepad, _) => Some(self.generic_style),
+			Source::Gamepad(kind, _) => Some(kind),
+			_ => None,
+		}
+	}
+}
+
+fn default_name(source: Source, style: Option<GamepadKind>) -> String {
+	match source {
+		Source::Keyboard(key) | Source::PhysicalKey(key) => key_name(key),
+		Source::Mouse(binding::Mouse::Button(button)) => match button {
+			MouseButton::Left => "Left Click",
+			MouseButton::Center => "Middle Click",
+			MouseButton::Right => "Right Click",
+		}
+		.to_owned(),
+		Source::Mouse(binding::Mouse::Move(axis)) => match axis {
+			binding::MouseAxis::MouseX => "Mouse Left/Right",
+			binding::MouseAxis::MouseY => "Mouse Up/Down",
+		}
+		.to_owned(),
+		Source::Mouse(binding::Mouse::Scroll(axis)) => match axis {
+			binding::MouseAxis::MouseX => "Scroll Left/Right",
+			binding::MouseAxis::MouseY => "Scroll Wheel",
+		}
+		.to_owned(),
+		Source::Gamepad(_, binding::Gamepad::Button(button)) => {
+			button_name(button, style.unwrap_or(GamepadKind::DualAxisGamepad)).to_owned()
+		}
+		Source::Gamepad(_, binding::Gamepad::Axis(axis)) => {
+			axis_name(axis, style.unwrap_or(GamepadKind::DualAxisGamepad)).to_owned()
+		}
+	}
+}
+
+fn default_glyph(source: Source, style: Option<GamepadKind>) -> String {
+	match source {
+		Source::Keyboard(Key::Unknown(_)) | Source::PhysicalKey(Key::Unknown(_)) => {
+			"keyboard/unknown".to_owned()
+		}
+		Source::Keyboard(key) | Source::PhysicalKey(key) => {
+			format!("keyboard/{}", format!("{:?}", key).to_lowercase())
+		}
+		Source::Mouse(binding::Mouse::Button(button)) => {
+			format!("mouse/{}", format!("{:?}", button).to_lowercase())
+		}
+		Source::Mouse(binding::Mouse::Move(axis)) => match axis {
+			binding::MouseAxis::MouseX => "mouse/move_x",
+			binding::MouseAxis::MouseY => "mouse/move_y",
+		}
+		.to_owned(),
+		Source::Mouse(binding::Mouse::Scroll(axis)) => match axis {
+			binding::MouseAxis::MouseX => "mouse/scroll_x",
+			binding::MouseAxis::MouseY => "mouse/scroll_y",
+		}
+		.to_owned(),
+		Source::Gamepad(..) => {
+			let style = style.unwrap_or(GamepadKind::DualAxisGamepad);
+			let prefix = match style {
+				GamepadKind::DualAxisGamepad => "gamepad",
+				GamepadKind::PS4Dualshock => "ps4",
+				GamepadKind::Xbox360 => "xbox360",
+				GamepadKind::Joycon => "joycon",
+			};
+			format!("{}/{}", prefix, slug(&default_name(source, Some(style))))
+		}
+	}
+}
+
+/// Lowercases a name, replacing anything other than letters and numbers with underscores.
+fn slug(name: &str) -> String {
+	let mut out = String::new();
+	for c in name.chars() {
+		if c.is_ascii_alphanumeric() {
+			out.push(c.to_ascii_lowercase());
+		} else if !out.ends_with('_') {
+			out.push('_');
+		}
+	}
+	out.trim_matches('_').to_owned()
+}
+
+fn key_name(key: Key) -> String {
+	let name = match key {
+		Key::Return => "Enter",
+		Key::NumpadEnter => "Numpad Enter",
+		Key::Escape => "Esc",
+		Key::Back => "Backspace",
+		Key::CapitalLock => "Caps Lock",
+		Key::Numlock => "Num Lock",
+		Key::ScrollLock => "Scroll Lock",
+		Key::Snapshot => "Print Screen",
+		Key::PageUp => "Page Up",
+		Key::PageDown => "Page Down",
+		Key::Left => "Left Arrow",
+		Key::Right => "Right Arrow",
+		Key::Up => "Up Arrow",
+		Key::Down => "Down Arrow",
+		Key::LAlt => "Left Alt",
+		Key::RAlt => "Right Alt",
+		Key::LShift => "Left Shift",
+		Key::RShift => "Right Shift",
+		Key::LControl => "Left Ctrl",
+		Key::RControl => "Right Ctrl",
+		Key::LWin => "Left Win",
+		Key::RWin => "Right Win",
+		Key::Grave => "`",
+		Key::Minus => "-",
+		Key::Equals => "=",
+		Key::LBracket => "[",
+		Key::RBracket => "]",
+		Key::Backslash => "\\",
+		Key::Semicolon => ";",
+		Key::Apostrophe => "'",
+		Key::Comma => ",",
+		Key::Period => ".",
+		Key::Slash => "/",
+		Key::OEM102 => "\\",
+		Key::PlayPause => "Play/Pause",
+		Key::NextTrack => "Next Track",
+		Key::PrevTrack => "Previous Track",
+		Key::VolumeUp => "Volume Up",
+		Key::VolumeDown => "Volume Down",
+		Key::Apps => "Menu",
+		Key::Sysrq => "SysRq",
+		Key::Unknown(scancode) => return format!("Key {:#X}", scancode),
+		_ => {
+			let debug = format!("{:?}", key);
+			return match key.to_string(&enumset::EnumSet::empty()) {
+				// Numpad keys have the same text as their main-keyboard counterparts.
+				Some(text) if debug.starts_with("Numpad") => format!("Numpad {}", text),
+				_ => match debug.strip_prefix("Key") {
+					Some(digit) => digit.to_owned(),
+					None => debug,
+				},
+			};
+		}
+	};
+	name.to_owned()
+}
+
+fn button_name(button: Button, style: GamepadKind) -> &'static str {
+	use GamepadKind::*;
+	match (button, style) {
+		(Button::FaceBottom, PS4Dualshock) => "Cross",
+		(Button::FaceLeft, PS4Dualshock) => "Square",
+		(Button::FaceRight, PS4Dualshock) => "Circle",
+		(Button::FaceTop, PS4Dualshock) => "Triangle",
+		(Button::FaceBottom, Joycon) => "B",
+		(Button::FaceLeft, Joycon) => "Y",
+		(Button::FaceRight, Joycon) => "A",
+		(Button::FaceTop, Joycon) => "X",
+		(Button::FaceBottom, _) => "A",
+		(Button::FaceLeft, _) => "X",
+		(Button::FaceRight, _) => "B",
+		(Button::FaceTop, _) => "Y",
+
+		// Virtual buttons are resolved to face buttons before being named.
+		(Button::VirtualConfirm, _) => "Confirm",
+		(Button::VirtualDeny, _) => "Back",
+
+		(Button::LThumbstick, PS4Dualshock) => "L3",
+		(Button::RThumbstick, PS4Dualshock) => "R3",
+		(Button::LThumbstick, _) => "Left Stick",
+		(Button::RThumbstick, _) => "Right Stick",
+
+		(Button::DPadUp, _) => "D-Pad Up",
+		(Button::DPadDown, _) => "D-Pad Down",
+		(Button::DPadLeft, _) => "D-Pad Left",
+		(Button::DPadRight, _) => "D-Pad Right",
+
+		(Button::LShoulder, PS4Dualshock) => "L1",
+		(Button::RShoulder, PS4Dualshock) => "R1",
+		(Button::LShoulder, Joycon) => "L",
+		(Button::RShoulder, Joycon) => "R",
+		(Button::LShoulder, _) => "LB",
+		(Button::RShoulder, _) => "RB",
+
+		(Button::LTrigger, PS4Dualshock) => "L2",
+		(Button::RTrigger, PS4Dualshock) => "R2",
+		(Button::LTrigger, Joycon) => "ZL",
+		(Button::RTrigger, Joycon) => "ZR",
+		(Button::LTrigger, _) => "LT",
+		(Button::RTrigger, _) => "RT",
+
+		(Button::Special, PS4Dualshock) => "PS",
+		(Button::LSpecial, PS4Dualshock) => "Share",
+		(Button::RSpecial, PS4Dualshock) => "Options",
+		(Button::Special, Joycon) => "Home",
+		(Button::LSpecial, Joycon) => "-",
+		(Button::RSpecial, Joycon) => "+",
+		(Button::Special, _) => "Guide",
+		(Button::LSpecial, _) => "Back",
+		(Button::RSpecial, _) => "Start",
+	}
+}
+
+fn axis_name(axis: Axis, style: GamepadKind) -> &'static str {
+	match (axis, style) {
+		(Axis::LThumbstickX, _) => "Left Stick Left/Right",
+		(Axis::LThumbstickY, _) => "Left Stick Up/Down",
+		(Axis::RThumbstickX, _) => "Right Stick Left/Right",
+		(Axis::RThumbstickY, _) => "Right Stick Up/Down",
+		(Axis::LTrigger, GamepadKind::PS4Dualshock) => "L2",
+		(Axis::RTrigger, GamepadKind::PS4Dualshock) => "R2",
+		(Axis::LTrigger, GamepadKind::Joycon) => "ZL",
+		(Axis::RTrigger, GamepadKind::Joycon) => "ZR",
+		(Axis::LTrigger, _) => "LT",
+		(Axis::RTrigger, _) => "RT",
+	}
+}
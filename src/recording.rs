@@ -0,0 +1,200 @@
+use crate::{binding, device, event, UserId};
+use std::time::{Duration, Instant};
+
+/// A single event captured by a [`DeviceCache`](crate::DeviceCache) while recording.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Entry {
+	/// An input which was sent to the user at the provided index of [`DeviceCache::users`](crate::DeviceCache::users).
+	Input(UserId, binding::Source, event::State),
+	DeviceConnected(device::Id),
+	DeviceDisconnected(device::Id),
+	Window(event::WindowEvent),
+}
+
+/// All of the [`entries`](Entry) captured between two calls to [`DeviceCache::update`](crate::DeviceCache::update).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Frame {
+	/// The amount of time between the start of the recording and the end of the frame.
+	pub time: Duration,
+	pub entries: Vec<Entry>,
+}
+
+/// A stream of input frames captured from a [`DeviceCache`](crate::DeviceCache)
+/// via [`start_recording`](crate::DeviceCache::start_recording), which can be played back
+/// via [`start_playback`](crate::DeviceCache::start_playback).
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Recording {
+	frames: Vec<Frame>,
+}
+
+impl Recording {
+	pub fn frames(&self) -> &Vec<Frame> {
+		&self.frames
+	}
+
+	pub fn len(&self) -> usize {
+		self.frames.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.frames.is_empty()
+	}
+
+	/// Writes the recording to a compact binary stream.
+	#[cfg(feature = "serde")]
+	pub fn write_to<W: std::io::Write>(&self, writer: W) -> Result<(), crate::Error> {
+		Ok(bincode::serialize_into(writer, self)?)
+	}
+
+	/// Reads a recording previously written by [`write_to`](Recording::write_to).
+	#[cfg(feature = "serde")]
+	pub fn read_from<R: std::io::Read>(reader: R) -> Result<Self, crate::Error> {
+		Ok(bincode::deserialize_from(reader)?)
+	}
+
+	#[cfg(feature = "serde")]
+	pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), crate::Error> {
+		let file = std::fs::File::create(path)?;
+		self.write_to(std::io::BufWriter::new(file))
+	}
+
+	#[cfg(feature = "serde")]
+	pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self, crate::Error> {
+		let file = std::fs::File::open(path)?;
+		Self::read_from(std::io::BufReader::new(file))
+	}
+}
+
+/// Accumulates entries into frames while a [`DeviceCache`](crate::DeviceCache) is recording.
+pub(crate) struct Recorder {
+	started_at: Instant,
+	frames: Vec<Frame>,
+	pending: Vec<Entry>,
+}
+
+impl Recorder {
+	pub(crate) fn new() -> Self {
+		Self {
+			started_at: Instant::now(),
+			frames: Vec::new(),
+			pending: Vec::new(),
+		}
+	}
+
+	pub(crate) fn push(&mut self, entry: Entry) {
+		self.pending.push(entry);
+	}
+
+	pub(crate) fn end_frame(&mut self) {
+		self.frames.push(Frame {
+			time: self.started_at.elapsed(),
+			entries: self.pending.drain(..).collect(),
+		});
+	}
+
+	pub(crate) fn finish(mut self) -> Recording {
+		if !self.pending.is_empty() {
+			self.end_frame();
+		}
+		Recording {
+			frames: self.frames,
+		}
+	}
+}
+
+/// The playback state of a [`Recording`] which is being fed into a [`DeviceCache`](crate::DeviceCache).
+/// While active, live mouse, keyboard, gamepad, and window events are ignored.
+pub struct Playback {
+	recording: Recording,
+	/// The index of the next frame to be played.
+	cursor: usize,
+	speed: f64,
+	/// The fractional amount of frames which have not yet been played.
+	accumulated: f64,
+	paused: bool,
+	/// The instant which corresponds to the start of the recording.
+	origin: Instant,
+}
+
+impl Playback {
+	pub(crate) fn new(recording: Recording) -> Self {
+		Self {
+			recording,
+			cursor: 0,
+			speed: 1.0,
+			accumulated: 0.0,
+			paused: false,
+			origin: Instant::now(),
+		}
+	}
+
+	pub fn recording(&self) -> &Recording {
+		&self.recording
+	}
+
+	/// Returns the index of the next frame that will be played.
+	pub fn frame(&self) -> usize {
+		self.cursor
+	}
+
+	pub fn is_finished(&self) -> bool {
+		self.cursor >= self.recording.len()
+	}
+
+	pub fn is_paused(&self) -> bool {
+		self.paused
+	}
+
+	pub fn pause(&mut self) {
+		self.paused = true;
+	}
+
+	pub fn resume(&mut self) {
+		self.paused = false;
+	}
+
+	pub fn speed(&self) -> f64 {
+		self.speed
+	}
+
+	/// Sets the amount of recorded frames which are played per [`update`](crate::DeviceCache::update).
+	/// A speed of `2.0` plays two frames every update, and `0.5` plays one frame every other update.
+	pub fn set_speed(&mut self, speed: f64) {
+		self.speed = speed.max(0.0);
+	}
+
+	/// Returns the instant at which a recorded frame occurred, relative to when playback started.
+	pub fn frame_instant(&self, frame: &Frame) -> Instant {
+		self.origin + frame.time
+	}
+
+	/// Returns the frames which should be played for the current update, advancing the cursor past them.
+	pub(crate) fn advance(&mut self) -> std::ops::Range<usize> {
+		let start = self.cursor;
+		if !self.paused {
+			self.accumulated += self.speed;
+			let count = self.accumulated.floor();
+			self.accumulated -= count;
+			self.cursor = (self.cursor + count as usize).min(self.recording.len());
+		}
+		start..self.cursor
+	}
+
+	/// Moves the cursor to `frame`, returning the frames which must be replayed
+	/// (from the start of the recording) to reach the state at that frame.
+	pub(crate) fn seek(&mut self, frame: usize) -> std::ops::Range<usize> {
+		self.cursor = frame.min(self.recording.len());
+		self.accumulated = 0.0;
+		let elapsed = self
+			.cursor
+			.checked_sub(1)
+			.map(|i| self.recording.frames[i].time)
+			.unwrap_or_default();
+		let now = Instant::now();
+		self.origin = now.checked_sub(elapsed).unwrap_or(now);
+		0..self.cursor
+	}
+}
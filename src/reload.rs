@@ -0,0 +1,169 @@
+use crate::{
+	binding::{ActionSet, ActionSetId},
+	Error, WeakLockConfig,
+};
+use std::{path::PathBuf, time::SystemTime};
+
+/// The result of parsing a bindings file: each action set it defines, or why it is invalid
+/// (usually [`Error::Parse`], which [`dsl::ParseError`](crate::dsl::ParseError)s convert into).
+pub type ParseResult = Result<Vec<(ActionSetId, ActionSet)>, Error>;
+
+/// Watches a bindings file while a [`DeviceCache`](crate::DeviceCache) is updated,
+/// replacing the action sets of the [`Config`](crate::Config) whenever the file is modified.
+/// Users with a replaced set enabled receive the new bindings immediately,
+/// keeping the state of any action which is still bound.
+///
+/// Files which fail to parse (or produce invalid action sets) are logged and otherwise ignored,
+/// so the previous bindings stay active until the file is fixed.
+pub struct ConfigWatcher {
+	path: PathBuf,
+	config: WeakLockConfig,
+	parse: Box<dyn Fn(&str) -> ParseResult + Send + Sync>,
+	modified_at: Option<SystemTime>,
+	last_error: Option<Error>,
+}
+
+impl ConfigWatcher {
+	pub fn new<P, F>(path: P, config: WeakLockConfig, parse: F) -> Self
+	where
+		P: Into<PathBuf>,
+		F: Fn(&str) -> ParseResult + Send + Sync + 'static,
+	{
+		Self {
+			path: path.into(),
+			config,
+			parse: Box::new(parse),
+			modified_at: None,
+			last_error: None,
+		}
+	}
+
+	pub fn path(&self) -> &PathBuf {
+		&self.path
+	}
+
+	/// Returns the reason the most recent change to the file could not be loaded, if it failed.
+	pub fn last_error(&self) -> Option<&Error> {
+		self.last_error.as_ref()
+	}
+
+	/// Reloads the file if it has been modified since the last poll,
+	/// returning the action sets which were replaced in the config.
+	pub(crate) fn poll(&mut self) -> Vec<(ActionSetId, ActionSet)> {
+		let modified_at = match std::fs::metadata(&self.path).and_then(|meta| meta.modified()) {
+			Ok(modified_at) => modified_at,
+			// The file may be mid-save, try again next update.
+			Err(_) => return Vec::new(),
+		};
+		if self.modified_at == Some(modified_at) {
+			return Vec::new();
+		}
+		self.modified_at = Some(modified_at);
+		match self.load() {
+			Ok(sets) => {
+				self.last_error = None;
+				sets
+			}
+			Err(error) => {
+				#[cfg(feature = "log")]
+				log::error!(target: crate::LOG, "Failed to reload {:?}: {}", self.path, error);
+				self.last_error = Some(error);
+				Vec::new()
+			}
+		}
+	}
+
+	fn load(&self) -> Result<Vec<(ActionSetId, ActionSet)>, Error> {
+		let text = std::fs::read_to_string(&self.path).map_err(Error::Io)?;
+		let sets = (self.parse)(&text)?;
+		let arc_config = self.config.upgrade().ok_or(Error::ConfigDropped)?;
+		let mut config = arc_config.write().unwrap();
+		// Validate every set before replacing any, so a partially-valid file does not leave the config half-updated.
+		for (_, set) in sets.iter() {
+			config.validate_action_set(set)?;
+		}
+		for (id, set) in sets.iter() {
+			config.set_action_set(*id, set.clone());
+		}
+		Ok(sets)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		binding::{ActionMap, LayoutId, Source},
+		source::{self, Key},
+		Config,
+	};
+	use std::{
+		fs::File,
+		io::Write,
+		sync::{Arc, RwLock},
+		time::Duration,
+	};
+
+	/// Parses files which contain the name of the key that jump is bound to.
+	fn parse(text: &str) -> ParseResult {
+		let key = match text {
+			"Space" => Key::Space,
+			"Return" => Key::Return,
+			_ => return Err(Error::Parse(format!("unknown key {}", text))),
+		};
+		let map = ActionMap::default().bind("jump", Source::Keyboard(key));
+		Ok(vec![(
+			ActionSetId::default(),
+			ActionSet::default().with(LayoutId::default(), map),
+		)])
+	}
+
+	fn write(path: &PathBuf, text: &str, age: u64) {
+		let mut file = File::create(path).unwrap();
+		file.write_all(text.as_bytes()).unwrap();
+		// Each write gets a distinct modification time, even on file systems with coarse timestamps.
+		file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(age))
+			.unwrap();
+	}
+
+	fn jump_sources(config: &Config) -> Vec<Source> {
+		let set = config.get_action_set(&ActionSetId::default()).unwrap();
+		let map = set.get(&LayoutId::default()).unwrap();
+		map.iter()
+			.flat_map(|(_, binding)| binding.sources())
+			.collect()
+	}
+
+	#[test]
+	fn invalid_files_keep_the_previous_sets() {
+		let config = Arc::new(RwLock::new(
+			Config::default()
+				.add_action("jump", source::Kind::Button)
+				.add_layout(LayoutId::default()),
+		));
+		let path =
+			std::env::temp_dir().join(format!("input-actions-reload-{}", std::process::id()));
+		let mut watcher = ConfigWatcher::new(&path, Arc::downgrade(&config), parse);
+
+		write(&path, "Space", 1);
+		assert_eq!(watcher.poll().len(), 1);
+		assert!(watcher.last_error().is_none());
+
+		write(&path, "Jump", 2);
+		assert!(watcher.poll().is_empty());
+		assert!(matches!(watcher.last_error(), Some(Error::Parse(_))));
+		assert_eq!(
+			jump_sources(&config.read().unwrap()),
+			vec![Source::Keyboard(Key::Space)]
+		);
+
+		write(&path, "Return", 3);
+		assert_eq!(watcher.poll().len(), 1);
+		assert!(watcher.last_error().is_none());
+		assert_eq!(
+			jump_sources(&config.read().unwrap()),
+			vec![Source::Keyboard(Key::Return)]
+		);
+		std::fs::remove_file(&path).unwrap();
+	}
+}
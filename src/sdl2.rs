@@ -0,0 +1,586 @@
+use crate::{
+	binding, device,
+	device::GamepadKind,
+	event,
+	source::{Axis, Button, Key, MouseButton},
+	text,
+};
+use sdl2::{
+	controller::GameController,
+	event::{Event, WindowEvent},
+	keyboard::{Keycode, Scancode},
+};
+use std::collections::HashMap;
+
+/// Parses the keyboard, mouse, text, and window events of an SDL event.
+/// A single SDL event can be sent as several events (like a mouse motion along both axes).
+/// Game controller events are parsed by [`Controllers`], if SDL is used as the gamepad backend.
+pub fn parse_sdl_event(event: &Event) -> Vec<event::Event> {
+	match event {
+		Event::Window { win_event, .. } => match win_event {
+			WindowEvent::SizeChanged(width, height) => vec![event::Event::Window(
+				event::WindowEvent::ResolutionChanged(*width as u32, *height as u32),
+			)],
+			WindowEvent::FocusGained => {
+				vec![event::Event::Window(event::WindowEvent::Focused(true))]
+			}
+			WindowEvent::FocusLost => {
+				vec![event::Event::Window(event::WindowEvent::Focused(false))]
+			}
+			_ => Vec::new(),
+		},
+		// Held keys are already pressed, and edit keys are repeated by the user's text input.
+		Event::KeyDown { repeat: true, .. } => Vec::new(),
+		Event::KeyDown {
+			keycode, scancode, ..
+		} => vec![key_event(*keycode, *scancode, event::ButtonState::Pressed)],
+		Event::KeyUp {
+			keycode, scancode, ..
+		} => vec![key_event(*keycode, *scancode, event::ButtonState::Released)],
+		Event::TextInput { text, .. } => text
+			.chars()
+			.filter(|c| !c.is_control())
+			.map(|c| event::Event::Text(text::TextInput::Char(c)))
+			.collect(),
+		Event::TextEditing {
+			text,
+			start,
+			length,
+			..
+		} => {
+			// SDL measures the cursor in characters, but the preedit cursor is in bytes.
+			let byte_index = |chars: i32| {
+				text.char_indices()
+					.nth(chars.max(0) as usize)
+					.map(|(index, _)| index)
+					.unwrap_or(text.len())
+			};
+			vec![event::Event::Text(text::TextInput::Preedit {
+				text: text.clone(),
+				cursor: Some((byte_index(*start), byte_index(*start + *length))),
+			})]
+		}
+		Event::MouseButtonDown { mouse_btn, .. } => {
+			mouse_button_event(*mouse_btn, event::ButtonState::Pressed)
+		}
+		Event::MouseButtonUp { mouse_btn, .. } => {
+			mouse_button_event(*mouse_btn, event::ButtonState::Released)
+		}
+		Event::MouseMotion { xrel, yrel, .. } => {
+			let mut events = Vec::with_capacity(2);
+			if *xrel != 0 {
+				events.push(mouse_event(
+					binding::Mouse::Move(binding::MouseAxis::MouseX),
+					event::State::MouseMove(*xrel as f64),
+				));
+			}
+			if *yrel != 0 {
+				events.push(mouse_event(
+					binding::Mouse::Move(binding::MouseAxis::MouseY),
+					event::State::MouseMove(*yrel as f64),
+				));
+			}
+			events
+		}
+		Event::MouseWheel {
+			precise_x,
+			precise_y,
+			..
+		} => {
+			let mut events = Vec::with_capacity(2);
+			if *precise_x != 0.0 {
+				events.push(mouse_event(
+					binding::Mouse::Scroll(binding::MouseAxis::MouseX),
+					event::State::MouseScroll(*precise_x),
+				));
+			}
+			if *precise_y != 0.0 {
+				events.push(mouse_event(
+					binding::Mouse::Scroll(binding::MouseAxis::MouseY),
+					event::State::MouseScroll(*precise_y),
+				));
+			}
+			events
+		}
+		_ => Vec::new(),
+	}
+}
+
+/// The SDL game controllers which have been opened, for using SDL as the gamepad backend
+/// instead of gilrs (see [`GamepadBackend::External`](device::GamepadBackend::External)).
+///
+/// SDL only sends events for controllers which are open, so the application opens each controller
+/// when SDL reports it was added, and passes it to [`connect`](Controllers::connect):
+/// ```rust,ignore
+/// Event::ControllerDeviceAdded { which, .. } => {
+///     let controller = controller_subsystem.open(which)?;
+///     input_sys.send_event(controllers.connect(&controller));
+///     open_controllers.push(controller);
+/// }
+/// event => {
+///     for input_event in controllers.parse_event(&event) {
+///         input_sys.send_event(input_event);
+///     }
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct Controllers {
+	/// The kind of each open controller by its instance id.
+	kinds: HashMap<u32, GamepadKind>,
+	/// Whether the left and right triggers of each open controller are pressed, by its instance id.
+	triggers: HashMap<u32, [bool; 2]>,
+}
+
+/// How far a trigger is pulled before its button is pressed.
+const TRIGGER_THRESHOLD: f32 = 0.5;
+
+impl Controllers {
+	/// Returns the event which connects an opened controller to the [`DeviceCache`](crate::DeviceCache).
+	pub fn connect(&mut self, controller: &GameController) -> event::Event {
+		let kind = gamepad_kind(&controller.name());
+		self.kinds.insert(controller.instance_id(), kind);
+		self.triggers.insert(controller.instance_id(), [false; 2]);
+		event::Event::DeviceConnected(device::Id::Gamepad(kind, controller.instance_id() as usize))
+	}
+
+	/// Parses the game controller events of an SDL event.
+	pub fn parse_event(&mut self, event: &Event) -> Vec<event::Event> {
+		match event {
+			Event::ControllerDeviceRemoved { which, .. } => match self.kinds.remove(which) {
+				Some(kind) => {
+					self.triggers.remove(which);
+					vec![event::Event::DeviceDisconnected(device::Id::Gamepad(
+						kind,
+						*which as usize,
+					))]
+				}
+				None => Vec::new(),
+			},
+			Event::ControllerButtonDown { which, button, .. } => {
+				self.button_event(*which, *button, event::ButtonState::Pressed)
+			}
+			Event::ControllerButtonUp { which, button, .. } => {
+				self.button_event(*which, *button, event::ButtonState::Released)
+			}
+			Event::ControllerAxisMotion {
+				which, axis, value, ..
+			} => {
+				let kind = match self.kinds.get(which) {
+					Some(kind) => *kind,
+					None => return Vec::new(),
+				};
+				let device = device::Id::Gamepad(kind, *which as usize);
+				let value = (*value as f32 / i16::MAX as f32).max(-1.0);
+				let gamepad_event = |source: binding::Gamepad, value: f32| {
+					event::Event::Input(
+						device,
+						binding::Source::Gamepad(kind, source),
+						event::State::ValueChanged(value),
+					)
+				};
+				match axis {
+					// SDL's y-axes point down, but gilrs's (and so the bindings') point up.
+					sdl2::controller::Axis::LeftX => {
+						vec![gamepad_event(
+							binding::Gamepad::Axis(Axis::LThumbstickX),
+							value,
+						)]
+					}
+					sdl2::controller::Axis::LeftY => {
+						vec![gamepad_event(
+							binding::Gamepad::Axis(Axis::LThumbstickY),
+							-value,
+						)]
+					}
+					sdl2::controller::Axis::RightX => {
+						vec![gamepad_event(
+							binding::Gamepad::Axis(Axis::RThumbstickX),
+							value,
+						)]
+					}
+					sdl2::controller::Axis::RightY => {
+						vec![gamepad_event(
+							binding::Gamepad::Axis(Axis::RThumbstickY),
+							-value,
+						)]
+					}
+					// Triggers are both an axis and an analog button.
+					sdl2::controller::Axis::TriggerLeft => {
+						let mut events =
+							vec![gamepad_event(binding::Gamepad::Axis(Axis::LTrigger), value)];
+						events.extend(self.trigger_events(*which, 0, Button::LTrigger, value));
+						events
+					}
+					sdl2::controller::Axis::TriggerRight => {
+						let mut events =
+							vec![gamepad_event(binding::Gamepad::Axis(Axis::RTrigger), value)];
+						events.extend(self.trigger_events(*which, 1, Button::RTrigger, value));
+						events
+					}
+				}
+			}
+			_ => Vec::new(),
+		}
+	}
+
+	/// Returns the events of a trigger's button, which is pressed and released as the trigger crosses
+	/// [`TRIGGER_THRESHOLD`], and changes value with the trigger in between.
+	fn trigger_events(
+		&mut self,
+		which: u32,
+		index: usize,
+		button: Button,
+		value: f32,
+	) -> Vec<event::Event> {
+		let (kind, triggers) = match (self.kinds.get(&which), self.triggers.get_mut(&which)) {
+			(Some(kind), Some(triggers)) => (*kind, triggers),
+			_ => return Vec::new(),
+		};
+		let is_pressed = value >= TRIGGER_THRESHOLD;
+		let was_pressed = std::mem::replace(&mut triggers[index], is_pressed);
+		let button_event = |state: event::State| {
+			event::Event::Input(
+				device::Id::Gamepad(kind, which as usize),
+				binding::Source::Gamepad(kind, binding::Gamepad::Button(button)),
+				state,
+			)
+		};
+		let mut events = Vec::with_capacity(2);
+		if is_pressed != was_pressed {
+			events.push(button_event(event::State::ButtonState(match is_pressed {
+				true => event::ButtonState::Pressed,
+				false => event::ButtonState::Released,
+			})));
+		}
+		events.push(button_event(event::State::ValueChanged(value)));
+		events
+	}
+
+	fn button_event(
+		&self,
+		which: u32,
+		button: sdl2::controller::Button,
+		state: event::ButtonState,
+	) -> Vec<event::Event> {
+		match (self.kinds.get(&which), controller_button(button)) {
+			(Some(kind), Some(button)) => vec![event::Event::Input(
+				device::Id::Gamepad(*kind, which as usize),
+				binding::Source::Gamepad(*kind, binding::Gamepad::Button(button)),
+				event::State::ButtonState(state),
+			)],
+			_ => Vec::new(),
+		}
+	}
+}
+
+/// Guesses the kind of a controller from the name SDL reports for it.
+fn gamepad_kind(name: &str) -> GamepadKind {
+	let name = name.to_lowercase();
+	if name.contains("ps4")
+		|| name.contains("ps5")
+		|| name.contains("dualshock")
+		|| name.contains("dualsense")
+	{
+		GamepadKind::PS4Dualshock
+	} else if name.contains("xbox") {
+		GamepadKind::Xbox360
+	} else if name.contains("joy-con") || name.contains("nintendo") {
+		GamepadKind::Joycon
+	} else {
+		GamepadKind::DualAxisGamepad
+	}
+}
+
+fn controller_button(button: sdl2::controller::Button) -> Option<Button> {
+	use sdl2::controller::Button as Sdl;
+	match button {
+		// SDL names the face buttons by their position on an Xbox controller.
+		Sdl::A => Some(Button::FaceBottom),
+		Sdl::B => Some(Button::FaceRight),
+		Sdl::X => Some(Button::FaceLeft),
+		Sdl::Y => Some(Button::FaceTop),
+		Sdl::Back => Some(Button::LSpecial),
+		Sdl::Guide => Some(Button::Special),
+		Sdl::Start => Some(Button::RSpecial),
+		Sdl::LeftStick => Some(Button::LThumbstick),
+		Sdl::RightStick => Some(Button::RThumbstick),
+		Sdl::LeftShoulder => Some(Button::LShoulder),
+		Sdl::RightShoulder => Some(Button::RShoulder),
+		Sdl::DPadUp => Some(Button::DPadUp),
+		Sdl::DPadDown => Some(Button::DPadDown),
+		Sdl::DPadLeft => Some(Button::DPadLeft),
+		Sdl::DPadRight => Some(Button::DPadRight),
+		Sdl::Misc1 | Sdl::Paddle1 | Sdl::Paddle2 | Sdl::Paddle3 | Sdl::Paddle4 | Sdl::Touchpad => {
+			None
+		}
+	}
+}
+
+fn mouse_event(mouse: binding::Mouse, state: event::State) -> event::Event {
+	event::Event::Input(device::Id::Mouse, binding::Source::Mouse(mouse), state)
+}
+
+fn mouse_button_event(
+	button: sdl2::mouse::MouseButton,
+	state: event::ButtonState,
+) -> Vec<event::Event> {
+	let button = match button {
+		sdl2::mouse::MouseButton::Left => MouseButton::Left,
+		sdl2::mouse::MouseButton::Middle => MouseButton::Center,
+		sdl2::mouse::MouseButton::Right => MouseButton::Right,
+		_ => return Vec::new(),
+	};
+	vec![mouse_event(
+		binding::Mouse::Button(button),
+		event::State::ButtonState(state),
+	)]
+}
+
+fn key_event(
+	keycode: Option<Keycode>,
+	scancode: Option<Scancode>,
+	state: event::ButtonState,
+) -> event::Event {
+	let physical = scancode.and_then(scancode_key);
+	event::Event::Key {
+		logical: keycode.and_then(keycode_key).or(physical),
+		// SDL's scancodes are USB usage ids, so they are converted to the platform's scancode via the key's position.
+		// Keys at unknown positions are sent as a scancode of 0, so they are only bound by their keycode.
+		scancode: physical.and_then(|key| key.scancode()).unwrap_or_default(),
+		state,
+	}
+}
+
+/// Returns the key which typed a keycode on a US-QWERTY keyboard.
+fn keycode_key(keycode: Keycode) -> Option<Key> {
+	// Keycodes which don't type a character are their scancode with this bit set.
+	const SCANCODE_MASK: i32 = 1 << 30;
+	let code = keycode.into_i32();
+	if code & SCANCODE_MASK != 0 {
+		return Scancode::from_i32(code & !SCANCODE_MASK).and_then(scancode_key);
+	}
+	match keycode {
+		Keycode::BACKSPACE => Some(Key::Back),
+		Keycode::TAB => Some(Key::Tab),
+		Keycode::RETURN => Some(Key::Return),
+		Keycode::ESCAPE => Some(Key::Escape),
+		Keycode::DELETE => Some(Key::Delete),
+		_ => std::char::from_u32(code as u32).and_then(Key::from_char),
+	}
+}
+
+/// Returns the key at the position of a scancode on a US-QWERTY keyboard.
+fn scancode_key(scancode: Scancode) -> Option<Key> {
+	Some(match scancode {
+		Scancode::A => Key::A,
+		Scancode::B => Key::B,
+		Scancode::C => Key::C,
+		Scancode::D => Key::D,
+		Scancode::E => Key::E,
+		Scancode::F => Key::F,
+		Scancode::G => Key::G,
+		Scancode::H => Key::H,
+		Scancode::I => Key::I,
+		Scancode::J => Key::J,
+		Scancode::K => Key::K,
+		Scancode::L => Key::L,
+		Scancode::M => Key::M,
+		Scancode::N => Key::N,
+		Scancode::O => Key::O,
+		Scancode::P => Key::P,
+		Scancode::Q => Key::Q,
+		Scancode::R => Key::R,
+		Scancode::S => Key::S,
+		Scancode::T => Key::T,
+		Scancode::U => Key::U,
+		Scancode::V => Key::V,
+		Scancode::W => Key::W,
+		Scancode::X => Key::X,
+		Scancode::Y => Key::Y,
+		Scancode::Z => Key::Z,
+		Scancode::Num1 => Key::Key1,
+		Scancode::Num2 => Key::Key2,
+		Scancode::Num3 => Key::Key3,
+		Scancode::Num4 => Key::Key4,
+		Scancode::Num5 => Key::Key5,
+		Scancode::Num6 => Key::Key6,
+		Scancode::Num7 => Key::Key7,
+		Scancode::Num8 => Key::Key8,
+		Scancode::Num9 => Key::Key9,
+		Scancode::Num0 => Key::Key0,
+		Scancode::Return => Key::Return,
+		Scancode::Escape => Key::Escape,
+		Scancode::Backspace => Key::Back,
+		Scancode::Tab => Key::Tab,
+		Scancode::Space => Key::Space,
+		Scancode::Minus => Key::Minus,
+		Scancode::Equals => Key::Equals,
+		Scancode::LeftBracket => Key::LBracket,
+		Scancode::RightBracket => Key::RBracket,
+		Scancode::Backslash | Scancode::NonUsHash => Key::Backslash,
+		Scancode::Semicolon => Key::Semicolon,
+		Scancode::Apostrophe => Key::Apostrophe,
+		Scancode::Grave => Key::Grave,
+		Scancode::Comma => Key::Comma,
+		Scancode::Period => Key::Period,
+		Scancode::Slash => Key::Slash,
+		Scancode::CapsLock => Key::CapitalLock,
+		Scancode::F1 => Key::F1,
+		Scancode::F2 => Key::F2,
+		Scancode::F3 => Key::F3,
+		Scancode::F4 => Key::F4,
+		Scancode::F5 => Key::F5,
+		Scancode::F6 => Key::F6,
+		Scancode::F7 => Key::F7,
+		Scancode::F8 => Key::F8,
+		Scancode::F9 => Key::F9,
+		Scancode::F10 => Key::F10,
+		Scancode::F11 => Key::F11,
+		Scancode::F12 => Key::F12,
+		Scancode::F13 => Key::F13,
+		Scancode::F14 => Key::F14,
+		Scancode::F15 => Key::F15,
+		Scancode::F16 => Key::F16,
+		Scancode::F17 => Key::F17,
+		Scancode::F18 => Key::F18,
+		Scancode::F19 => Key::F19,
+		Scancode::F20 => Key::F20,
+		Scancode::F21 => Key::F21,
+		Scancode::F22 => Key::F22,
+		Scancode::F23 => Key::F23,
+		Scancode::F24 => Key::F24,
+		Scancode::PrintScreen => Key::Snapshot,
+		Scancode::ScrollLock => Key::ScrollLock,
+		Scancode::Pause => Key::Pause,
+		Scancode::Insert => Key::Insert,
+		Scancode::Home => Key::Home,
+		Scancode::PageUp => Key::PageUp,
+		Scancode::Delete => Key::Delete,
+		Scancode::End => Key::End,
+		Scancode::PageDown => Key::PageDown,
+		Scancode::Right => Key::Right,
+		Scancode::Left => Key::Left,
+		Scancode::Down => Key::Down,
+		Scancode::Up => Key::Up,
+		Scancode::NumLockClear => Key::Numlock,
+		Scancode::KpDivide => Key::NumpadSlash,
+		Scancode::KpMultiply => Key::NumpadAsterisk,
+		Scancode::KpMinus => Key::NumpadMinus,
+		Scancode::KpPlus => Key::NumpadPlus,
+		Scancode::KpEnter => Key::NumpadEnter,
+		Scancode::Kp1 => Key::Numpad1,
+		Scancode::Kp2 => Key::Numpad2,
+		Scancode::Kp3 => Key::Numpad3,
+		Scancode::Kp4 => Key::Numpad4,
+		Scancode::Kp5 => Key::Numpad5,
+		Scancode::Kp6 => Key::Numpad6,
+		Scancode::Kp7 => Key::Numpad7,
+		Scancode::Kp8 => Key::Numpad8,
+		Scancode::Kp9 => Key::Numpad9,
+		Scancode::Kp0 => Key::Numpad0,
+		Scancode::KpPeriod => Key::NumpadDecimal,
+		Scancode::KpComma => Key::NumpadComma,
+		Scancode::KpEquals => Key::NumpadEquals,
+		Scancode::NonUsBackslash => Key::OEM102,
+		Scancode::Application => Key::Apps,
+		Scancode::Power => Key::Power,
+		Scancode::Stop => Key::Stop,
+		Scancode::Cut => Key::Cut,
+		Scancode::Copy => Key::Copy,
+		Scancode::Paste => Key::Paste,
+		Scancode::Mute | Scancode::AudioMute => Key::Mute,
+		Scancode::VolumeUp => Key::VolumeUp,
+		Scancode::VolumeDown => Key::VolumeDown,
+		Scancode::International1 => Key::AbntC1,
+		Scancode::International2 => Key::Kana,
+		Scancode::International3 => Key::Yen,
+		Scancode::International4 => Key::Convert,
+		Scancode::International5 => Key::NoConvert,
+		Scancode::SysReq => Key::Sysrq,
+		Scancode::LCtrl => Key::LControl,
+		Scancode::LShift => Key::LShift,
+		Scancode::LAlt => Key::LAlt,
+		Scancode::LGui => Key::LWin,
+		Scancode::RCtrl => Key::RControl,
+		Scancode::RShift => Key::RShift,
+		Scancode::RAlt => Key::RAlt,
+		Scancode::RGui => Key::RWin,
+		Scancode::AudioNext => Key::NextTrack,
+		Scancode::AudioPrev => Key::PrevTrack,
+		Scancode::AudioStop => Key::MediaStop,
+		Scancode::AudioPlay => Key::PlayPause,
+		Scancode::MediaSelect => Key::MediaSelect,
+		Scancode::Mail => Key::Mail,
+		Scancode::Calculator => Key::Calculator,
+		Scancode::Computer => Key::MyComputer,
+		Scancode::AcSearch => Key::WebSearch,
+		Scancode::AcHome => Key::WebHome,
+		Scancode::AcBack => Key::WebBack,
+		Scancode::AcForward => Key::WebForward,
+		Scancode::AcStop => Key::WebStop,
+		Scancode::AcRefresh => Key::WebRefresh,
+		Scancode::AcBookmarks => Key::WebFavorites,
+		Scancode::Sleep => Key::Sleep,
+		_ => return None,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn trigger_motion(value: i16) -> Event {
+		Event::ControllerAxisMotion {
+			timestamp: 0,
+			which: 3,
+			axis: sdl2::controller::Axis::TriggerLeft,
+			value,
+		}
+	}
+
+	fn trigger_states(events: &[event::Event]) -> Vec<event::State> {
+		let button = binding::Source::Gamepad(
+			GamepadKind::Xbox360,
+			binding::Gamepad::Button(Button::LTrigger),
+		);
+		events
+			.iter()
+			.filter_map(|event| match event {
+				event::Event::Input(_, source, state) if *source == button => Some(*state),
+				_ => None,
+			})
+			.collect()
+	}
+
+	#[test]
+	fn triggers_press_their_button_past_the_threshold() {
+		let mut controllers = Controllers::default();
+		controllers.kinds.insert(3, GamepadKind::Xbox360);
+		controllers.triggers.insert(3, [false; 2]);
+
+		let states = trigger_states(&controllers.parse_event(&trigger_motion(i16::MAX / 4)));
+		assert!(matches!(states[..], [event::State::ValueChanged(_)]));
+
+		let events = controllers.parse_event(&trigger_motion(i16::MAX));
+		assert_eq!(events.len(), 3);
+		assert!(matches!(
+			trigger_states(&events)[..],
+			[
+				event::State::ButtonState(event::ButtonState::Pressed),
+				event::State::ValueChanged(value),
+			] if value == 1.0
+		));
+
+		let states = trigger_states(&controllers.parse_event(&trigger_motion(i16::MAX / 4 * 3)));
+		assert!(matches!(states[..], [event::State::ValueChanged(_)]));
+
+		let states = trigger_states(&controllers.parse_event(&trigger_motion(0)));
+		assert!(matches!(
+			states[..],
+			[
+				event::State::ButtonState(event::ButtonState::Released),
+				event::State::ValueChanged(value),
+			] if value == 0.0
+		));
+	}
+}
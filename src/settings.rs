@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+/// The value of a single named [`setting`](Settings).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Setting {
+	Float(f64),
+	Bool(bool),
+}
+
+/// Named parameters (like `look_sensitivity` or `invert_y`) which belong to a single [`User`](crate::User).
+/// Behaviors like [`SettingMultiplier`](crate::action::behavior::SettingMultiplier) reference settings by name,
+/// so changing a setting only affects the bindings of the user it belongs to.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Settings(HashMap<String, Setting>);
+
+impl Settings {
+	pub fn with_float(mut self, name: &str, value: f64) -> Self {
+		self.set_float(name, value);
+		self
+	}
+
+	pub fn with_bool(mut self, name: &str, value: bool) -> Self {
+		self.set_bool(name, value);
+		self
+	}
+
+	pub fn set_float(&mut self, name: &str, value: f64) {
+		self.0.insert(name.to_owned(), Setting::Float(value));
+	}
+
+	pub fn set_bool(&mut self, name: &str, value: bool) {
+		self.0.insert(name.to_owned(), Setting::Bool(value));
+	}
+
+	pub fn get(&self, name: &str) -> Option<Setting> {
+		self.0.get(name).cloned()
+	}
+
+	/// Returns the value of a float setting, or `None` if the setting does not exist or is not a float.
+	pub fn float(&self, name: &str) -> Option<f64> {
+		match self.get(name) {
+			Some(Setting::Float(value)) => Some(value),
+			_ => None,
+		}
+	}
+
+	/// Returns the value of a bool setting, or `None` if the setting does not exist or is not a bool.
+	pub fn bool(&self, name: &str) -> Option<bool> {
+		match self.get(name) {
+			Some(Setting::Bool(value)) => Some(value),
+			_ => None,
+		}
+	}
+
+	pub fn remove(&mut self, name: &str) -> Option<Setting> {
+		self.0.remove(name)
+	}
+
+	pub fn iter(&self) -> std::collections::hash_map::Iter<'_, String, Setting> {
+		self.0.iter()
+	}
+}
@@ -0,0 +1,315 @@
+use crate::Error;
+use std::{collections::BTreeMap, convert::TryInto};
+
+/// The number of steps per 1.0 that axis values are quantized to in a [`Snapshot`] (16.16 fixed point),
+/// which covers values up to ±32768 (such as mouse movement in pixels) in steps of about 0.000015.
+pub const AXIS_STEPS: f64 = 65536.0;
+
+/// Rounds an axis value to the precision it has in a [`Snapshot`].
+/// Users updated by [`ticks`](crate::User::update_tick) report their axis values at this precision,
+/// so the local simulation uses the same values as the machines the snapshots are sent to.
+pub fn quantize_axis(value: f64) -> f64 {
+	to_fixed(value) as f64 / AXIS_STEPS
+}
+
+fn to_fixed(value: f64) -> i32 {
+	// Values out of range saturate (and NaN becomes 0).
+	(value * AXIS_STEPS).round() as i32
+}
+
+/// The state of a single [`button action`](crate::source::Kind::Button) in a [`Snapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ButtonSnapshot {
+	pub down: bool,
+	pub pressed: bool,
+	pub released: bool,
+}
+
+/// The state of all of a user's actions for one simulation tick,
+/// which can be encoded into a small byte buffer and applied to a user on a remote machine.
+///
+/// Actions are stored in a fixed order (sorted by [`action id`](crate::action::Id)),
+/// so both machines must be using the same [`Config`](crate::Config) and enabled action sets.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Snapshot {
+	tick: u64,
+	buttons: Vec<ButtonSnapshot>,
+	/// Axis values are not bounded (mouse movement, scrolling, and multiplied axes can exceed 1.0),
+	/// so they are stored as fixed point numbers with [`AXIS_STEPS`] steps per 1.0.
+	axes: Vec<i32>,
+}
+
+impl Snapshot {
+	pub(crate) fn new(tick: u64) -> Self {
+		Self {
+			tick,
+			buttons: Vec::new(),
+			axes: Vec::new(),
+		}
+	}
+
+	pub fn tick(&self) -> u64 {
+		self.tick
+	}
+
+	pub fn buttons(&self) -> &Vec<ButtonSnapshot> {
+		&self.buttons
+	}
+
+	pub(crate) fn push_button(&mut self, button: ButtonSnapshot) {
+		self.buttons.push(button);
+	}
+
+	pub fn axes(&self) -> impl std::iter::ExactSizeIterator<Item = f64> + '_ {
+		self.axes.iter().map(|&value| value as f64 / AXIS_STEPS)
+	}
+
+	pub(crate) fn push_axis(&mut self, value: f64) {
+		self.axes.push(to_fixed(value));
+	}
+
+	/// Returns true if the snapshot has no actions, such as a prediction made before any snapshot was received.
+	/// Users apply an empty snapshot as every button released and every axis at 0.
+	pub fn is_empty(&self) -> bool {
+		self.buttons.is_empty() && self.axes.is_empty()
+	}
+
+	/// Returns a copy of this snapshot for a future tick, as if the same buttons were still held down.
+	/// Presses and releases are not repeated.
+	pub fn predict(&self, tick: u64) -> Self {
+		Self {
+			tick,
+			buttons: self
+				.buttons
+				.iter()
+				.map(|button| ButtonSnapshot {
+					down: button.down,
+					pressed: false,
+					released: false,
+				})
+				.collect(),
+			axes: self.axes.clone(),
+		}
+	}
+
+	/// Returns true if the actions in both snapshots are in the same state, regardless of tick.
+	pub fn same_input(&self, other: &Snapshot) -> bool {
+		self.buttons == other.buttons && self.axes == other.axes
+	}
+
+	/// Encodes the snapshot as:
+	/// - tick (u64, little endian)
+	/// - button count (u16, little endian)
+	/// - axis count (u16, little endian)
+	/// - 3 bits per button (down, pressed, released), packed into bytes
+	/// - 4 bytes per axis (i32 fixed point with [`AXIS_STEPS`] steps per 1.0, little endian)
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let bit_count = self.buttons.len() * 3;
+		let mut bytes = Vec::with_capacity(12 + bit_count.div_ceil(8) + self.axes.len() * 4);
+		bytes.extend_from_slice(&self.tick.to_le_bytes());
+		bytes.extend_from_slice(&(self.buttons.len() as u16).to_le_bytes());
+		bytes.extend_from_slice(&(self.axes.len() as u16).to_le_bytes());
+		let mut bits = vec![0u8; bit_count.div_ceil(8)];
+		for (i, button) in self.buttons.iter().enumerate() {
+			for (j, flag) in [button.down, button.pressed, button.released]
+				.iter()
+				.enumerate()
+			{
+				if *flag {
+					let bit = i * 3 + j;
+					bits[bit / 8] |= 1 << (bit % 8);
+				}
+			}
+		}
+		bytes.append(&mut bits);
+		for axis in self.axes.iter() {
+			bytes.extend_from_slice(&axis.to_le_bytes());
+		}
+		bytes
+	}
+
+	/// Decodes a snapshot encoded by [`to_bytes`](Snapshot::to_bytes).
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+		Self::decode(bytes).ok_or(Error::MalformedSnapshot)
+	}
+
+	fn decode(bytes: &[u8]) -> Option<Self> {
+		let tick = u64::from_le_bytes(bytes.get(0..8)?.try_into().ok()?);
+		let button_count = u16::from_le_bytes(bytes.get(8..10)?.try_into().ok()?) as usize;
+		let axis_count = u16::from_le_bytes(bytes.get(10..12)?.try_into().ok()?) as usize;
+		let bits_end = 12 + (button_count * 3).div_ceil(8);
+		let bits = bytes.get(12..bits_end)?;
+		let is_set = |bit: usize| bits[bit / 8] & (1 << (bit % 8)) != 0;
+		let buttons = (0..button_count)
+			.map(|i| ButtonSnapshot {
+				down: is_set(i * 3),
+				pressed: is_set(i * 3 + 1),
+				released: is_set(i * 3 + 2),
+			})
+			.collect();
+		let axes = bytes
+			.get(bits_end..bits_end + axis_count * 4)?
+			.chunks_exact(4)
+			.map(|chunk| i32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+			.collect();
+		Some(Self {
+			tick,
+			buttons,
+			axes,
+		})
+	}
+}
+
+/// Tracks the snapshots received from a remote user, predicting snapshots for ticks which have not arrived yet.
+#[derive(Debug, Clone, Default)]
+pub struct RemoteInput {
+	received: BTreeMap<u64, Snapshot>,
+	predicted: BTreeMap<u64, Snapshot>,
+}
+
+impl RemoteInput {
+	/// Stores a snapshot received from the remote machine.
+	/// Returns the tick that must be re-simulated from if the snapshot was previously mispredicted,
+	/// in which case the simulation should be rolled back and the snapshots re-applied from that tick.
+	pub fn receive(&mut self, snapshot: Snapshot) -> Option<u64> {
+		let tick = snapshot.tick();
+		let mispredicted = match self.predicted.remove(&tick) {
+			Some(prediction) => !prediction.same_input(&snapshot),
+			None => false,
+		};
+		self.received.insert(tick, snapshot);
+		if mispredicted {
+			Some(tick)
+		} else {
+			None
+		}
+	}
+
+	/// Returns the snapshot for a given tick, and if that snapshot is a prediction.
+	/// If the snapshot for the tick has not been received, the most recent earlier snapshot is repeated.
+	/// Before any snapshot is received, the prediction is [`empty`](Snapshot::is_empty) (nothing held).
+	pub fn snapshot(&mut self, tick: u64) -> (Snapshot, bool) {
+		if let Some(snapshot) = self.received.get(&tick) {
+			return (snapshot.clone(), false);
+		}
+		let prediction = match self.received.range(..tick).next_back() {
+			Some((_, latest)) => latest.predict(tick),
+			None => Snapshot::new(tick),
+		};
+		self.predicted.insert(tick, prediction.clone());
+		(prediction, true)
+	}
+
+	/// Returns the most recent tick for which a real snapshot has been received.
+	pub fn latest_received_tick(&self) -> Option<u64> {
+		self.received.keys().next_back().cloned()
+	}
+
+	/// Forgets all snapshots before the provided tick (once they can no longer be rolled back to).
+	pub fn discard_before(&mut self, tick: u64) {
+		// Keep the latest snapshot before `tick` so that later ticks can still be predicted.
+		let keep_from = self
+			.received
+			.range(..tick)
+			.next_back()
+			.map(|(&latest, _)| latest)
+			.unwrap_or(tick);
+		self.received = self.received.split_off(&keep_from);
+		self.predicted = self.predicted.split_off(&tick);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn snapshot() -> Snapshot {
+		let mut snapshot = Snapshot::new(42);
+		for (down, pressed, released) in [
+			(true, true, false),
+			(false, false, true),
+			(true, false, false),
+		] {
+			snapshot.push_button(ButtonSnapshot {
+				down,
+				pressed,
+				released,
+			});
+		}
+		snapshot.push_axis(0.5);
+		snapshot.push_axis(-37.25);
+		snapshot.push_axis(1200.0);
+		snapshot
+	}
+
+	#[test]
+	fn bytes_round_trip() {
+		let snapshot = snapshot();
+		let bytes = snapshot.to_bytes();
+		// 12 byte header, 9 button bits in 2 bytes, 3 axes of 4 bytes
+		assert_eq!(bytes.len(), 12 + 2 + 3 * 4);
+		assert_eq!(Snapshot::from_bytes(&bytes).unwrap(), snapshot);
+	}
+
+	#[test]
+	fn axes_are_not_clamped() {
+		let axes = snapshot().axes().collect::<Vec<_>>();
+		assert_eq!(axes, vec![0.5, -37.25, 1200.0]);
+	}
+
+	#[test]
+	fn axes_are_quantized() {
+		let mut snapshot = Snapshot::new(0);
+		snapshot.push_axis(0.3);
+		let value = quantize_axis(0.3);
+		assert_ne!(value, 0.3);
+		assert!((value - 0.3).abs() <= 0.5 / AXIS_STEPS);
+		assert_eq!(snapshot.axes().next(), Some(value));
+		assert_eq!(quantize_axis(value), value);
+	}
+
+	#[test]
+	fn truncated_bytes_are_malformed() {
+		let bytes = snapshot().to_bytes();
+		for len in [0, 11, 13, bytes.len() - 1] {
+			assert!(matches!(
+				Snapshot::from_bytes(&bytes[..len]),
+				Err(Error::MalformedSnapshot)
+			));
+		}
+	}
+
+	#[test]
+	fn prediction_holds_buttons_without_edges() {
+		let prediction = snapshot().predict(43);
+		assert_eq!(prediction.tick(), 43);
+		assert!(prediction
+			.buttons()
+			.iter()
+			.all(|button| !button.pressed && !button.released));
+		assert_eq!(
+			prediction
+				.buttons()
+				.iter()
+				.map(|b| b.down)
+				.collect::<Vec<_>>(),
+			vec![true, false, true]
+		);
+		assert!(prediction.axes().eq(snapshot().axes()));
+	}
+
+	#[test]
+	fn mispredictions_request_rollback() {
+		let mut remote = RemoteInput::default();
+		remote.receive(snapshot());
+		let (predicted, is_prediction) = remote.snapshot(43);
+		assert!(is_prediction);
+		assert_eq!(remote.receive(predicted.clone()), None);
+
+		let (_, is_prediction) = remote.snapshot(44);
+		assert!(is_prediction);
+		let mut actual = Snapshot::new(44);
+		actual.push_axis(0.0);
+		assert_eq!(remote.receive(actual), Some(44));
+	}
+}
@@ -2,9 +2,15 @@ mod axis;
 pub use axis::*;
 mod button;
 pub use button::*;
+mod confirm_buttons;
+pub use confirm_buttons::*;
 mod key;
 pub use key::*;
+mod keyboard_layout;
+pub use keyboard_layout::*;
 mod kind;
 pub use kind::*;
 mod mouse;
 pub use mouse::*;
+mod scancode;
+pub use scancode::*;
@@ -1,4 +1,5 @@
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Axis {
 	/// The x-axis of the left thumbstick.
 	LThumbstickX,
@@ -32,3 +33,19 @@ impl std::convert::TryFrom<gilrs::Axis> for Axis {
 		}
 	}
 }
+
+impl std::str::FromStr for Axis {
+	type Err = ();
+	/// Parses the name of a variant (as written in the source code).
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		match name {
+			"LThumbstickX" => Ok(Self::LThumbstickX),
+			"LThumbstickY" => Ok(Self::LThumbstickY),
+			"RThumbstickX" => Ok(Self::RThumbstickX),
+			"RThumbstickY" => Ok(Self::RThumbstickY),
+			"LTrigger" => Ok(Self::LTrigger),
+			"RTrigger" => Ok(Self::RTrigger),
+			_ => Err(()),
+		}
+	}
+}
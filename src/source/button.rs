@@ -1,4 +1,5 @@
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Button {
 	/// The bottom button in the face-diamond.
 	/// - PS4: X
@@ -99,3 +100,32 @@ impl std::convert::TryFrom<gilrs::Button> for Button {
 		}
 	}
 }
+
+impl std::str::FromStr for Button {
+	type Err = ();
+	/// Parses the name of a variant (as written in the source code).
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		match name {
+			"FaceBottom" => Ok(Self::FaceBottom),
+			"FaceLeft" => Ok(Self::FaceLeft),
+			"FaceRight" => Ok(Self::FaceRight),
+			"FaceTop" => Ok(Self::FaceTop),
+			"VirtualConfirm" => Ok(Self::VirtualConfirm),
+			"VirtualDeny" => Ok(Self::VirtualDeny),
+			"LThumbstick" => Ok(Self::LThumbstick),
+			"RThumbstick" => Ok(Self::RThumbstick),
+			"DPadUp" => Ok(Self::DPadUp),
+			"DPadDown" => Ok(Self::DPadDown),
+			"DPadLeft" => Ok(Self::DPadLeft),
+			"DPadRight" => Ok(Self::DPadRight),
+			"LShoulder" => Ok(Self::LShoulder),
+			"RShoulder" => Ok(Self::RShoulder),
+			"LTrigger" => Ok(Self::LTrigger),
+			"RTrigger" => Ok(Self::RTrigger),
+			"Special" => Ok(Self::Special),
+			"LSpecial" => Ok(Self::LSpecial),
+			"RSpecial" => Ok(Self::RSpecial),
+			_ => Err(()),
+		}
+	}
+}
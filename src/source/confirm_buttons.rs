@@ -0,0 +1,104 @@
+use crate::{device::GamepadKind, source::Button};
+use std::collections::HashSet;
+
+/// Which face button confirms and which one denies on each kind of gamepad.
+///
+/// The [`DeviceCache`](crate::DeviceCache) uses this to also send [`VirtualConfirm`](Button::VirtualConfirm)
+/// and [`VirtualDeny`](Button::VirtualDeny) when those face buttons are pressed, and [`prompts`](crate::User::prompts)
+/// use the same table to show virtual buttons as the face button which sends them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfirmButtons {
+	/// The gamepad kinds whose confirm button is the right face button instead of the bottom one.
+	on_right: HashSet<GamepadKind>,
+}
+
+impl Default for ConfirmButtons {
+	fn default() -> Self {
+		Self {
+			on_right: vec![GamepadKind::Joycon].into_iter().collect(),
+		}
+	}
+}
+
+impl ConfirmButtons {
+	/// Sets whether the right face button confirms (and the bottom one denies) for a kind of gamepad.
+	/// By default this is only true for Switch controllers, but some regions also swap it for PlayStation controllers.
+	pub fn with_confirm_on_right(mut self, kind: GamepadKind, on_right: bool) -> Self {
+		if on_right {
+			self.on_right.insert(kind);
+		} else {
+			self.on_right.remove(&kind);
+		}
+		self
+	}
+
+	pub fn confirm_button(&self, kind: GamepadKind) -> Button {
+		if self.on_right.contains(&kind) {
+			Button::FaceRight
+		} else {
+			Button::FaceBottom
+		}
+	}
+
+	pub fn deny_button(&self, kind: GamepadKind) -> Button {
+		if self.on_right.contains(&kind) {
+			Button::FaceBottom
+		} else {
+			Button::FaceRight
+		}
+	}
+
+	/// Returns the virtual button which is also sent when a physical button is pressed, if any.
+	pub fn virtual_button(&self, kind: GamepadKind, button: Button) -> Option<Button> {
+		if button == self.confirm_button(kind) {
+			Some(Button::VirtualConfirm)
+		} else if button == self.deny_button(kind) {
+			Some(Button::VirtualDeny)
+		} else {
+			None
+		}
+	}
+
+	/// Returns the physical button which sends a button, which is the button itself unless it is virtual.
+	pub fn physical_button(&self, kind: GamepadKind, button: Button) -> Button {
+		match button {
+			Button::VirtualConfirm => self.confirm_button(kind),
+			Button::VirtualDeny => self.deny_button(kind),
+			_ => button,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn virtual_and_physical_buttons_agree() {
+		let table =
+			ConfirmButtons::default().with_confirm_on_right(GamepadKind::PS4Dualshock, true);
+		for kind in [
+			GamepadKind::DualAxisGamepad,
+			GamepadKind::Joycon,
+			GamepadKind::PS4Dualshock,
+		] {
+			for button in [Button::VirtualConfirm, Button::VirtualDeny] {
+				let physical = table.physical_button(kind, button);
+				assert_eq!(table.virtual_button(kind, physical), Some(button));
+			}
+		}
+		assert_eq!(
+			table.confirm_button(GamepadKind::DualAxisGamepad),
+			Button::FaceBottom
+		);
+		assert_eq!(table.confirm_button(GamepadKind::Joycon), Button::FaceRight);
+		assert_eq!(
+			table.deny_button(GamepadKind::PS4Dualshock),
+			Button::FaceBottom
+		);
+		assert_eq!(
+			table.virtual_button(GamepadKind::Joycon, Button::FaceTop),
+			None
+		);
+	}
+}
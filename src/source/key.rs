@@ -1,4 +1,7 @@
+use crate::source::ScanCode;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Key {
 	// Alphabet
 	A,
@@ -84,6 +87,8 @@ pub enum Key {
 	NumpadAsterisk,
 	NumpadSlash,
 	NumpadDecimal,
+	NumpadComma,
+	NumpadEquals,
 	NumpadEnter,
 
 	// Control Keys
@@ -133,6 +138,66 @@ pub enum Key {
 	Comma,
 	Period,
 	Slash,
+
+	// Symbols with their own key on some layouts
+	Asterisk,
+	At,
+	Caret,
+	Colon,
+	Plus,
+	Underline,
+
+	// International Keys
+	AbntC1,
+	AbntC2,
+	Ax,
+	Convert,
+	Kana,
+	Kanji,
+	NoConvert,
+	OEM102,
+	Yen,
+
+	// Media Keys
+	Mute,
+	VolumeDown,
+	VolumeUp,
+	PlayPause,
+	NextTrack,
+	PrevTrack,
+	Stop,
+	MediaStop,
+	MediaSelect,
+
+	// Application Keys
+	WebBack,
+	WebForward,
+	WebHome,
+	WebRefresh,
+	WebSearch,
+	WebStop,
+	WebFavorites,
+	NavigateBackward,
+	NavigateForward,
+	Mail,
+	MyComputer,
+	Calculator,
+	Copy,
+	Paste,
+	Cut,
+
+	// System Keys
+	Apps,
+	Compose,
+	Power,
+	Sleep,
+	Wake,
+	Sysrq,
+	Unlabeled,
+
+	/// A key which has no variant, identified by its [`scancode`](ScanCode).
+	/// It can still be bound, but only on the platform (and keyboard) which reported the scancode.
+	Unknown(ScanCode),
 }
 
 #[derive(Debug, Hash, enumset::EnumSetType)]
@@ -145,6 +210,8 @@ pub enum KeyModifier {
 }
 
 impl Key {
+	/// Returns the text typed by the key on a US-QWERTY keyboard.
+	/// For names which respect the player's keyboard layout, see [`GlyphSet::prompt_with`](crate::prompt::GlyphSet::prompt_with).
 	pub fn to_string(&self, modifiers: &enumset::EnumSet<KeyModifier>) -> Option<String> {
 		use Key::*;
 		let uppercase = modifiers.contains(KeyModifier::Shift);
@@ -205,6 +272,8 @@ impl Key {
 			NumpadAsterisk => Some("*"),
 			NumpadSlash => Some("/"),
 			NumpadDecimal => Some("."),
+			NumpadComma => Some(","),
+			NumpadEquals => Some("="),
 
 			// Keyboard Controls
 			Grave => Some(if uppercase { "~" } else { "`" }),
@@ -222,8 +291,213 @@ impl Key {
 			Period => Some(if uppercase { ">" } else { "." }),
 			Slash => Some(if uppercase { "?" } else { "/" }),
 
+			// Symbols
+			Asterisk => Some("*"),
+			At => Some("@"),
+			Caret => Some("^"),
+			Colon => Some(":"),
+			Plus => Some("+"),
+			Underline => Some("_"),
+
 			_ => None,
 		}
 		.map(|s| s.to_owned())
 	}
+
+	/// Returns the key on the main part of a US-QWERTY keyboard which types a character,
+	/// with or without shift (so both `'a'` and `'A'` are [`Key::A`], and `'!'` is [`Key::Key1`]).
+	/// This is the inverse of [`to_string`](Key::to_string), for backends which only report the text of a key.
+	pub fn from_char(c: char) -> Option<Key> {
+		use Key::*;
+		const TYPING_KEYS: [Key; 48] = [
+			A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, Key1,
+			Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, Key0, Grave, Space, Minus, Equals,
+			LBracket, RBracket, Backslash, Semicolon, Apostrophe, Comma, Period, Slash,
+		];
+		let text = c.to_string();
+		let unshifted = enumset::EnumSet::empty();
+		let shifted = enumset::EnumSet::only(KeyModifier::Shift);
+		TYPING_KEYS.iter().copied().find(|key| {
+			key.to_string(&unshifted).as_ref() == Some(&text)
+				|| key.to_string(&shifted).as_ref() == Some(&text)
+		})
+	}
+}
+
+impl std::str::FromStr for Key {
+	type Err = ();
+	/// Parses the name of a variant (as written in the source code), or `Unknown(scancode)`.
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		match name {
+			"A" => Ok(Self::A),
+			"B" => Ok(Self::B),
+			"C" => Ok(Self::C),
+			"D" => Ok(Self::D),
+			"E" => Ok(Self::E),
+			"F" => Ok(Self::F),
+			"G" => Ok(Self::G),
+			"H" => Ok(Self::H),
+			"I" => Ok(Self::I),
+			"J" => Ok(Self::J),
+			"K" => Ok(Self::K),
+			"L" => Ok(Self::L),
+			"M" => Ok(Self::M),
+			"N" => Ok(Self::N),
+			"O" => Ok(Self::O),
+			"P" => Ok(Self::P),
+			"Q" => Ok(Self::Q),
+			"R" => Ok(Self::R),
+			"S" => Ok(Self::S),
+			"T" => Ok(Self::T),
+			"U" => Ok(Self::U),
+			"V" => Ok(Self::V),
+			"W" => Ok(Self::W),
+			"X" => Ok(Self::X),
+			"Y" => Ok(Self::Y),
+			"Z" => Ok(Self::Z),
+			"Escape" => Ok(Self::Escape),
+			"F1" => Ok(Self::F1),
+			"F2" => Ok(Self::F2),
+			"F3" => Ok(Self::F3),
+			"F4" => Ok(Self::F4),
+			"F5" => Ok(Self::F5),
+			"F6" => Ok(Self::F6),
+			"F7" => Ok(Self::F7),
+			"F8" => Ok(Self::F8),
+			"F9" => Ok(Self::F9),
+			"F10" => Ok(Self::F10),
+			"F11" => Ok(Self::F11),
+			"F12" => Ok(Self::F12),
+			"F13" => Ok(Self::F13),
+			"F14" => Ok(Self::F14),
+			"F15" => Ok(Self::F15),
+			"F16" => Ok(Self::F16),
+			"F17" => Ok(Self::F17),
+			"F18" => Ok(Self::F18),
+			"F19" => Ok(Self::F19),
+			"F20" => Ok(Self::F20),
+			"F21" => Ok(Self::F21),
+			"F22" => Ok(Self::F22),
+			"F23" => Ok(Self::F23),
+			"F24" => Ok(Self::F24),
+			"Key1" => Ok(Self::Key1),
+			"Key2" => Ok(Self::Key2),
+			"Key3" => Ok(Self::Key3),
+			"Key4" => Ok(Self::Key4),
+			"Key5" => Ok(Self::Key5),
+			"Key6" => Ok(Self::Key6),
+			"Key7" => Ok(Self::Key7),
+			"Key8" => Ok(Self::Key8),
+			"Key9" => Ok(Self::Key9),
+			"Key0" => Ok(Self::Key0),
+			"Numlock" => Ok(Self::Numlock),
+			"Numpad0" => Ok(Self::Numpad0),
+			"Numpad1" => Ok(Self::Numpad1),
+			"Numpad2" => Ok(Self::Numpad2),
+			"Numpad3" => Ok(Self::Numpad3),
+			"Numpad4" => Ok(Self::Numpad4),
+			"Numpad5" => Ok(Self::Numpad5),
+			"Numpad6" => Ok(Self::Numpad6),
+			"Numpad7" => Ok(Self::Numpad7),
+			"Numpad8" => Ok(Self::Numpad8),
+			"Numpad9" => Ok(Self::Numpad9),
+			"NumpadPlus" => Ok(Self::NumpadPlus),
+			"NumpadMinus" => Ok(Self::NumpadMinus),
+			"NumpadAsterisk" => Ok(Self::NumpadAsterisk),
+			"NumpadSlash" => Ok(Self::NumpadSlash),
+			"NumpadDecimal" => Ok(Self::NumpadDecimal),
+			"NumpadComma" => Ok(Self::NumpadComma),
+			"NumpadEquals" => Ok(Self::NumpadEquals),
+			"NumpadEnter" => Ok(Self::NumpadEnter),
+			"Snapshot" => Ok(Self::Snapshot),
+			"ScrollLock" => Ok(Self::ScrollLock),
+			"Pause" => Ok(Self::Pause),
+			"Insert" => Ok(Self::Insert),
+			"Home" => Ok(Self::Home),
+			"Delete" => Ok(Self::Delete),
+			"End" => Ok(Self::End),
+			"PageUp" => Ok(Self::PageUp),
+			"PageDown" => Ok(Self::PageDown),
+			"Left" => Ok(Self::Left),
+			"Right" => Ok(Self::Right),
+			"Up" => Ok(Self::Up),
+			"Down" => Ok(Self::Down),
+			"Grave" => Ok(Self::Grave),
+			"Back" => Ok(Self::Back),
+			"Tab" => Ok(Self::Tab),
+			"CapitalLock" => Ok(Self::CapitalLock),
+			"Return" => Ok(Self::Return),
+			"Space" => Ok(Self::Space),
+			"LAlt" => Ok(Self::LAlt),
+			"RAlt" => Ok(Self::RAlt),
+			"LShift" => Ok(Self::LShift),
+			"RShift" => Ok(Self::RShift),
+			"LControl" => Ok(Self::LControl),
+			"RControl" => Ok(Self::RControl),
+			"LWin" => Ok(Self::LWin),
+			"RWin" => Ok(Self::RWin),
+			"Minus" => Ok(Self::Minus),
+			"Equals" => Ok(Self::Equals),
+			"LBracket" => Ok(Self::LBracket),
+			"RBracket" => Ok(Self::RBracket),
+			"Backslash" => Ok(Self::Backslash),
+			"Semicolon" => Ok(Self::Semicolon),
+			"Apostrophe" => Ok(Self::Apostrophe),
+			"Comma" => Ok(Self::Comma),
+			"Period" => Ok(Self::Period),
+			"Slash" => Ok(Self::Slash),
+			"Asterisk" => Ok(Self::Asterisk),
+			"At" => Ok(Self::At),
+			"Caret" => Ok(Self::Caret),
+			"Colon" => Ok(Self::Colon),
+			"Plus" => Ok(Self::Plus),
+			"Underline" => Ok(Self::Underline),
+			"AbntC1" => Ok(Self::AbntC1),
+			"AbntC2" => Ok(Self::AbntC2),
+			"Ax" => Ok(Self::Ax),
+			"Convert" => Ok(Self::Convert),
+			"Kana" => Ok(Self::Kana),
+			"Kanji" => Ok(Self::Kanji),
+			"NoConvert" => Ok(Self::NoConvert),
+			"OEM102" => Ok(Self::OEM102),
+			"Yen" => Ok(Self::Yen),
+			"Mute" => Ok(Self::Mute),
+			"VolumeDown" => Ok(Self::VolumeDown),
+			"VolumeUp" => Ok(Self::VolumeUp),
+			"PlayPause" => Ok(Self::PlayPause),
+			"NextTrack" => Ok(Self::NextTrack),
+			"PrevTrack" => Ok(Self::PrevTrack),
+			"Stop" => Ok(Self::Stop),
+			"MediaStop" => Ok(Self::MediaStop),
+			"MediaSelect" => Ok(Self::MediaSelect),
+			"WebBack" => Ok(Self::WebBack),
+			"WebForward" => Ok(Self::WebForward),
+			"WebHome" => Ok(Self::WebHome),
+			"WebRefresh" => Ok(Self::WebRefresh),
+			"WebSearch" => Ok(Self::WebSearch),
+			"WebStop" => Ok(Self::WebStop),
+			"WebFavorites" => Ok(Self::WebFavorites),
+			"NavigateBackward" => Ok(Self::NavigateBackward),
+			"NavigateForward" => Ok(Self::NavigateForward),
+			"Mail" => Ok(Self::Mail),
+			"MyComputer" => Ok(Self::MyComputer),
+			"Calculator" => Ok(Self::Calculator),
+			"Copy" => Ok(Self::Copy),
+			"Paste" => Ok(Self::Paste),
+			"Cut" => Ok(Self::Cut),
+			"Apps" => Ok(Self::Apps),
+			"Compose" => Ok(Self::Compose),
+			"Power" => Ok(Self::Power),
+			"Sleep" => Ok(Self::Sleep),
+			"Wake" => Ok(Self::Wake),
+			"Sysrq" => Ok(Self::Sysrq),
+			"Unlabeled" => Ok(Self::Unlabeled),
+			_ => name
+				.strip_prefix("Unknown(")
+				.and_then(|code| code.strip_suffix(')'))
+				.and_then(|code| code.parse::<ScanCode>().ok())
+				.map(Self::Unknown)
+				.ok_or(()),
+		}
+	}
 }
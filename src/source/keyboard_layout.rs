@@ -0,0 +1,69 @@
+use crate::source::Key;
+use std::collections::HashMap;
+
+/// Describes which logical key (the label printed on the key) is at each physical position of a keyboard,
+/// where positions are named by the key at that position on a US-QWERTY keyboard.
+///
+/// The [`DeviceCache`](crate::DeviceCache) learns the layout from [`key events`](crate::event::Event::Key)
+/// as keys are pressed, and it can also be set up front (for example, from the OS or a settings menu)
+/// so that prompts for [`physical keys`](crate::binding::Source::PhysicalKey) are correct before any key is pressed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeyboardLayout {
+	/// Positions whose logical key differs from US-QWERTY.
+	logical: HashMap<Key, Key>,
+}
+
+impl KeyboardLayout {
+	/// The US-QWERTY layout, where every key is labeled as its position.
+	pub fn qwerty() -> Self {
+		Self::default()
+	}
+
+	/// The French AZERTY layout.
+	pub fn azerty() -> Self {
+		Self::default()
+			.with(Key::Q, Key::A)
+			.with(Key::A, Key::Q)
+			.with(Key::W, Key::Z)
+			.with(Key::Z, Key::W)
+			.with(Key::Semicolon, Key::M)
+			.with(Key::M, Key::Comma)
+			.with(Key::Comma, Key::Semicolon)
+	}
+
+	/// The German QWERTZ layout.
+	pub fn qwertz() -> Self {
+		Self::default()
+			.with(Key::Y, Key::Z)
+			.with(Key::Z, Key::Y)
+			.with(Key::Minus, Key::Slash)
+	}
+
+	/// Records that the key at a physical position is labeled as a logical key.
+	pub fn with(mut self, physical: Key, logical: Key) -> Self {
+		self.set(physical, logical);
+		self
+	}
+
+	pub fn set(&mut self, physical: Key, logical: Key) {
+		if physical == logical {
+			self.logical.remove(&physical);
+		} else {
+			self.logical.insert(physical, logical);
+		}
+	}
+
+	/// Returns the label of the key at a physical position.
+	pub fn logical(&self, physical: Key) -> Key {
+		self.logical.get(&physical).cloned().unwrap_or(physical)
+	}
+
+	/// Returns the physical position of a labeled key.
+	pub fn physical(&self, logical: Key) -> Key {
+		self.logical
+			.iter()
+			.find(|(_, label)| **label == logical)
+			.map(|(physical, _)| *physical)
+			.unwrap_or(logical)
+	}
+}
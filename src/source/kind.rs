@@ -1,5 +1,6 @@
 /// Enum to differentiate between the two types of inputs: single state (button) and range (axis).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Kind {
 	Axis,
 	Button,
@@ -1,6 +1,20 @@
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MouseButton {
 	Left,
 	Center,
 	Right,
 }
+
+impl std::str::FromStr for MouseButton {
+	type Err = ();
+	/// Parses the name of a variant (as written in the source code).
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		match name {
+			"Left" => Ok(Self::Left),
+			"Center" => Ok(Self::Center),
+			"Right" => Ok(Self::Right),
+			_ => Err(()),
+		}
+	}
+}
@@ -0,0 +1,372 @@
+use crate::source::Key;
+
+/// A platform-specific code for the physical position of a key, independent of the keyboard layout.
+/// This is the `scancode` reported by winit: an evdev code on Linux, a set-1 scancode on Windows
+/// (with `0xE000` added for extended keys), and a virtual keycode on macOS.
+pub type ScanCode = u32;
+
+impl Key {
+	/// Returns the key at the position of a scancode on a US-QWERTY keyboard,
+	/// which is how [`physical keys`](crate::binding::Source::PhysicalKey) are named.
+	pub fn from_scancode(scancode: ScanCode) -> Option<Key> {
+		SCANCODES
+			.iter()
+			.find(|(code, _)| *code == scancode)
+			.map(|(_, key)| *key)
+	}
+
+	/// Returns the scancode of the position this key has on a US-QWERTY keyboard.
+	pub fn scancode(&self) -> Option<ScanCode> {
+		if let Key::Unknown(scancode) = self {
+			return Some(*scancode);
+		}
+		SCANCODES
+			.iter()
+			.find(|(_, key)| key == self)
+			.map(|(code, _)| *code)
+	}
+}
+
+/// Set-1 scancodes, which are shared by evdev (Linux) and Windows for the main keyboard.
+#[cfg(not(target_os = "macos"))]
+const SCANCODES: &[(ScanCode, Key)] = &[
+	(0x01, Key::Escape),
+	(0x02, Key::Key1),
+	(0x03, Key::Key2),
+	(0x04, Key::Key3),
+	(0x05, Key::Key4),
+	(0x06, Key::Key5),
+	(0x07, Key::Key6),
+	(0x08, Key::Key7),
+	(0x09, Key::Key8),
+	(0x0A, Key::Key9),
+	(0x0B, Key::Key0),
+	(0x0C, Key::Minus),
+	(0x0D, Key::Equals),
+	(0x0E, Key::Back),
+	(0x0F, Key::Tab),
+	(0x10, Key::Q),
+	(0x11, Key::W),
+	(0x12, Key::E),
+	(0x13, Key::R),
+	(0x14, Key::T),
+	(0x15, Key::Y),
+	(0x16, Key::U),
+	(0x17, Key::I),
+	(0x18, Key::O),
+	(0x19, Key::P),
+	(0x1A, Key::LBracket),
+	(0x1B, Key::RBracket),
+	(0x1C, Key::Return),
+	(0x1D, Key::LControl),
+	(0x1E, Key::A),
+	(0x1F, Key::S),
+	(0x20, Key::D),
+	(0x21, Key::F),
+	(0x22, Key::G),
+	(0x23, Key::H),
+	(0x24, Key::J),
+	(0x25, Key::K),
+	(0x26, Key::L),
+	(0x27, Key::Semicolon),
+	(0x28, Key::Apostrophe),
+	(0x29, Key::Grave),
+	(0x2A, Key::LShift),
+	(0x2B, Key::Backslash),
+	(0x2C, Key::Z),
+	(0x2D, Key::X),
+	(0x2E, Key::C),
+	(0x2F, Key::V),
+	(0x30, Key::B),
+	(0x31, Key::N),
+	(0x32, Key::M),
+	(0x33, Key::Comma),
+	(0x34, Key::Period),
+	(0x35, Key::Slash),
+	(0x36, Key::RShift),
+	(0x37, Key::NumpadAsterisk),
+	(0x38, Key::LAlt),
+	(0x39, Key::Space),
+	(0x3A, Key::CapitalLock),
+	(0x3B, Key::F1),
+	(0x3C, Key::F2),
+	(0x3D, Key::F3),
+	(0x3E, Key::F4),
+	(0x3F, Key::F5),
+	(0x40, Key::F6),
+	(0x41, Key::F7),
+	(0x42, Key::F8),
+	(0x43, Key::F9),
+	(0x44, Key::F10),
+	(0x45, Key::Numlock),
+	(0x46, Key::ScrollLock),
+	(0x47, Key::Numpad7),
+	(0x48, Key::Numpad8),
+	(0x49, Key::Numpad9),
+	(0x4A, Key::NumpadMinus),
+	(0x4B, Key::Numpad4),
+	(0x4C, Key::Numpad5),
+	(0x4D, Key::Numpad6),
+	(0x4E, Key::NumpadPlus),
+	(0x4F, Key::Numpad1),
+	(0x50, Key::Numpad2),
+	(0x51, Key::Numpad3),
+	(0x52, Key::Numpad0),
+	(0x53, Key::NumpadDecimal),
+	// The extra key next to left shift on ISO keyboards.
+	(0x56, Key::OEM102),
+	(0x57, Key::F11),
+	(0x58, Key::F12),
+	#[cfg(target_os = "windows")]
+	(0x64, Key::F13),
+	#[cfg(target_os = "windows")]
+	(0x65, Key::F14),
+	#[cfg(target_os = "windows")]
+	(0x66, Key::F15),
+	#[cfg(target_os = "windows")]
+	(0x67, Key::F16),
+	#[cfg(target_os = "windows")]
+	(0x68, Key::F17),
+	#[cfg(target_os = "windows")]
+	(0x69, Key::F18),
+	#[cfg(target_os = "windows")]
+	(0x6A, Key::F19),
+	#[cfg(target_os = "windows")]
+	(0x6B, Key::F20),
+	#[cfg(target_os = "windows")]
+	(0x6C, Key::F21),
+	#[cfg(target_os = "windows")]
+	(0x6D, Key::F22),
+	#[cfg(target_os = "windows")]
+	(0x6E, Key::F23),
+	#[cfg(target_os = "windows")]
+	(0x76, Key::F24),
+	#[cfg(target_os = "windows")]
+	(0xE01C, Key::NumpadEnter),
+	#[cfg(target_os = "windows")]
+	(0xE01D, Key::RControl),
+	#[cfg(target_os = "windows")]
+	(0xE035, Key::NumpadSlash),
+	#[cfg(target_os = "windows")]
+	(0xE037, Key::Snapshot),
+	#[cfg(target_os = "windows")]
+	(0xE038, Key::RAlt),
+	#[cfg(target_os = "windows")]
+	(0xE045, Key::Pause),
+	#[cfg(target_os = "windows")]
+	(0xE047, Key::Home),
+	#[cfg(target_os = "windows")]
+	(0xE048, Key::Up),
+	#[cfg(target_os = "windows")]
+	(0xE049, Key::PageUp),
+	#[cfg(target_os = "windows")]
+	(0xE04B, Key::Left),
+	#[cfg(target_os = "windows")]
+	(0xE04D, Key::Right),
+	#[cfg(target_os = "windows")]
+	(0xE04F, Key::End),
+	#[cfg(target_os = "windows")]
+	(0xE050, Key::Down),
+	#[cfg(target_os = "windows")]
+	(0xE051, Key::PageDown),
+	#[cfg(target_os = "windows")]
+	(0xE052, Key::Insert),
+	#[cfg(target_os = "windows")]
+	(0xE053, Key::Delete),
+	#[cfg(target_os = "windows")]
+	(0xE05B, Key::LWin),
+	#[cfg(target_os = "windows")]
+	(0xE05C, Key::RWin),
+	#[cfg(not(target_os = "windows"))]
+	(96, Key::NumpadEnter),
+	#[cfg(not(target_os = "windows"))]
+	(97, Key::RControl),
+	#[cfg(not(target_os = "windows"))]
+	(98, Key::NumpadSlash),
+	#[cfg(not(target_os = "windows"))]
+	(99, Key::Snapshot),
+	#[cfg(not(target_os = "windows"))]
+	(100, Key::RAlt),
+	#[cfg(not(target_os = "windows"))]
+	(102, Key::Home),
+	#[cfg(not(target_os = "windows"))]
+	(103, Key::Up),
+	#[cfg(not(target_os = "windows"))]
+	(104, Key::PageUp),
+	#[cfg(not(target_os = "windows"))]
+	(105, Key::Left),
+	#[cfg(not(target_os = "windows"))]
+	(106, Key::Right),
+	#[cfg(not(target_os = "windows"))]
+	(107, Key::End),
+	#[cfg(not(target_os = "windows"))]
+	(108, Key::Down),
+	#[cfg(not(target_os = "windows"))]
+	(109, Key::PageDown),
+	#[cfg(not(target_os = "windows"))]
+	(110, Key::Insert),
+	#[cfg(not(target_os = "windows"))]
+	(111, Key::Delete),
+	#[cfg(not(target_os = "windows"))]
+	(119, Key::Pause),
+	#[cfg(not(target_os = "windows"))]
+	(125, Key::LWin),
+	#[cfg(not(target_os = "windows"))]
+	(126, Key::RWin),
+	#[cfg(not(target_os = "windows"))]
+	(183, Key::F13),
+	#[cfg(not(target_os = "windows"))]
+	(184, Key::F14),
+	#[cfg(not(target_os = "windows"))]
+	(185, Key::F15),
+	#[cfg(not(target_os = "windows"))]
+	(186, Key::F16),
+	#[cfg(not(target_os = "windows"))]
+	(187, Key::F17),
+	#[cfg(not(target_os = "windows"))]
+	(188, Key::F18),
+	#[cfg(not(target_os = "windows"))]
+	(189, Key::F19),
+	#[cfg(not(target_os = "windows"))]
+	(190, Key::F20),
+	#[cfg(not(target_os = "windows"))]
+	(191, Key::F21),
+	#[cfg(not(target_os = "windows"))]
+	(192, Key::F22),
+	#[cfg(not(target_os = "windows"))]
+	(193, Key::F23),
+	#[cfg(not(target_os = "windows"))]
+	(194, Key::F24),
+];
+
+/// Virtual keycodes (`kVK_*`), which describe key positions on macOS.
+#[cfg(target_os = "macos")]
+const SCANCODES: &[(ScanCode, Key)] = &[
+	(0x00, Key::A),
+	(0x01, Key::S),
+	(0x02, Key::D),
+	(0x03, Key::F),
+	(0x04, Key::H),
+	(0x05, Key::G),
+	(0x06, Key::Z),
+	(0x07, Key::X),
+	(0x08, Key::C),
+	(0x09, Key::V),
+	(0x0B, Key::B),
+	(0x0C, Key::Q),
+	(0x0D, Key::W),
+	(0x0E, Key::E),
+	(0x0F, Key::R),
+	(0x10, Key::Y),
+	(0x11, Key::T),
+	(0x12, Key::Key1),
+	(0x13, Key::Key2),
+	(0x14, Key::Key3),
+	(0x15, Key::Key4),
+	(0x16, Key::Key6),
+	(0x17, Key::Key5),
+	(0x18, Key::Equals),
+	(0x19, Key::Key9),
+	(0x1A, Key::Key7),
+	(0x1B, Key::Minus),
+	(0x1C, Key::Key8),
+	(0x1D, Key::Key0),
+	(0x1E, Key::RBracket),
+	(0x1F, Key::O),
+	(0x20, Key::U),
+	(0x21, Key::LBracket),
+	(0x22, Key::I),
+	(0x23, Key::P),
+	(0x24, Key::Return),
+	(0x25, Key::L),
+	(0x26, Key::J),
+	(0x27, Key::Apostrophe),
+	(0x28, Key::K),
+	(0x29, Key::Semicolon),
+	(0x2A, Key::Backslash),
+	(0x2B, Key::Comma),
+	(0x2C, Key::Slash),
+	(0x2D, Key::N),
+	(0x2E, Key::M),
+	(0x2F, Key::Period),
+	(0x30, Key::Tab),
+	(0x31, Key::Space),
+	(0x32, Key::Grave),
+	(0x33, Key::Back),
+	(0x35, Key::Escape),
+	(0x36, Key::RWin),
+	(0x37, Key::LWin),
+	(0x38, Key::LShift),
+	(0x39, Key::CapitalLock),
+	(0x3A, Key::LAlt),
+	(0x3B, Key::LControl),
+	(0x3C, Key::RShift),
+	(0x3D, Key::RAlt),
+	(0x3E, Key::RControl),
+	(0x40, Key::F17),
+	(0x41, Key::NumpadDecimal),
+	(0x43, Key::NumpadAsterisk),
+	(0x45, Key::NumpadPlus),
+	(0x47, Key::Numlock),
+	(0x4B, Key::NumpadSlash),
+	(0x4C, Key::NumpadEnter),
+	(0x4E, Key::NumpadMinus),
+	(0x4F, Key::F18),
+	(0x50, Key::F19),
+	(0x52, Key::Numpad0),
+	(0x53, Key::Numpad1),
+	(0x54, Key::Numpad2),
+	(0x55, Key::Numpad3),
+	(0x56, Key::Numpad4),
+	(0x57, Key::Numpad5),
+	(0x58, Key::Numpad6),
+	(0x59, Key::Numpad7),
+	(0x5A, Key::F20),
+	(0x5B, Key::Numpad8),
+	(0x5C, Key::Numpad9),
+	(0x60, Key::F5),
+	(0x61, Key::F6),
+	(0x62, Key::F7),
+	(0x63, Key::F3),
+	(0x64, Key::F8),
+	(0x65, Key::F9),
+	(0x67, Key::F11),
+	(0x69, Key::F13),
+	(0x6A, Key::F16),
+	(0x6B, Key::F14),
+	(0x6D, Key::F10),
+	(0x6F, Key::F12),
+	(0x71, Key::F15),
+	(0x73, Key::Home),
+	(0x74, Key::PageUp),
+	(0x75, Key::Delete),
+	(0x76, Key::F4),
+	(0x77, Key::End),
+	(0x78, Key::F2),
+	(0x79, Key::PageDown),
+	(0x7A, Key::F1),
+	(0x7B, Key::Left),
+	(0x7C, Key::Right),
+	(0x7D, Key::Down),
+	(0x7E, Key::Up),
+];
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn scancodes_round_trip() {
+		for (code, key) in SCANCODES.iter() {
+			assert_eq!(Key::from_scancode(*code), Some(*key));
+			assert_eq!(key.scancode(), Some(*code));
+		}
+		assert_eq!(Key::Unknown(0x1FF).scancode(), Some(0x1FF));
+	}
+
+	#[cfg(not(target_os = "macos"))]
+	#[test]
+	fn iso_key_has_a_scancode() {
+		assert_eq!(Key::from_scancode(0x56), Some(Key::OEM102));
+	}
+}
@@ -2,27 +2,43 @@ use crate::{
 	action,
 	binding::{self, ActionSet, ActionSetId, LayoutId},
 	device::{self, GamepadKind},
-	event,
-	source::{self, Axis, Button},
-	ArcLockUser, WeakLockUser,
+	event, join, recording,
+	source::{self, Axis, Button, Key},
+	text, ArcLockUser, Error, WeakLockUser,
 };
 use std::{
 	collections::HashMap,
 	sync::{Arc, RwLock, Weak},
 };
 
+/// Identifies a user by the order it was added to the [`DeviceCache`] (its index in [`users`](DeviceCache::users)).
+/// Ids are never reused: when a user is dropped, its id stays vacant, so ids already handed out keep referring to the same user.
 pub type UserId = usize;
 
 pub type ArcLockConfig = Arc<RwLock<Config>>;
 pub type WeakLockConfig = Weak<RwLock<Config>>;
 #[derive(Default, Clone, Debug)]
 pub struct Config {
+	version: u32,
 	actions: HashMap<action::Id, source::Kind>,
 	layouts: Vec<LayoutId>,
+	/// The layout each layout inherits bindings from, if it has one.
+	layout_parents: HashMap<LayoutId, LayoutId>,
 	action_sets: HashMap<ActionSetId, ActionSet>,
 }
 
 impl Config {
+	/// Sets the version of the config, which is saved in [`profiles`](crate::profile::Profile)
+	/// so they can be migrated when actions are added or removed.
+	pub fn with_version(mut self, version: u32) -> Self {
+		self.version = version;
+		self
+	}
+
+	pub fn version(&self) -> u32 {
+		self.version
+	}
+
 	/// Adds an action to the list of actions the system supports.
 	pub fn add_action(mut self, name: action::Id, action: source::Kind) -> Self {
 		self.actions.insert(name, action);
@@ -35,55 +51,322 @@ impl Config {
 		self
 	}
 
+	/// Adds a layout which inherits the bindings of a parent layout (for example, `Some("left_handed")` inheriting from `None`).
+	/// Action maps for the layout only need to list the actions which differ from the parent;
+	/// any action it does not bind is resolved from the parent (and its parent, and so on).
+	pub fn add_layout_with_parent(mut self, layout: LayoutId, parent: LayoutId) -> Self {
+		self.layouts.push(layout);
+		self.layout_parents.insert(layout, parent);
+		self
+	}
+
+	/// Returns the layout and each of its ancestors, in the order bindings are resolved.
+	pub fn layout_chain(&self, layout: LayoutId) -> Vec<LayoutId> {
+		let mut chain = vec![layout];
+		let mut current = layout;
+		while let Some(parent) = self.layout_parents.get(&current) {
+			// A cycle would otherwise resolve forever, it is reported by `validate`.
+			if chain.contains(parent) {
+				break;
+			}
+			chain.push(*parent);
+			current = *parent;
+		}
+		chain
+	}
+
 	/// Associates an [`action set`](ActionSet) with an [`id`](ActionSetId).
 	pub fn add_action_set(mut self, id: ActionSetId, set: ActionSet) -> Self {
 		self.action_sets.insert(id, set);
 		self
 	}
 
+	/// Associates an [`action set`](ActionSet) with an [`id`](ActionSetId),
+	/// failing if the set is not valid for the actions and layouts already added to the config
+	/// (see [`validate`](Config::validate)).
+	pub fn try_add_action_set(self, id: ActionSetId, set: ActionSet) -> Result<Self, Error> {
+		self.validate_action_set(&set)?;
+		Ok(self.add_action_set(id, set))
+	}
+
+	/// Checks that all action sets only bind actions and layouts which have been added to the config,
+	/// that layouts only inherit from layouts which have been added (without any cycles),
+	/// and that all behaviors support the sources they are bound to.
+	pub fn validate(&self) -> Result<(), Error> {
+		for (layout, parent) in self.layout_parents.iter() {
+			if !self.layouts.contains(parent) {
+				return Err(Error::UnknownLayout(*parent));
+			}
+			let chain = self.layout_chain(*layout);
+			if self.layout_parents.contains_key(chain.last().unwrap()) {
+				return Err(Error::LayoutCycle(*layout));
+			}
+		}
+		for set in self.action_sets.values() {
+			self.validate_action_set(set)?;
+		}
+		Ok(())
+	}
+
+	pub(crate) fn validate_action_set(&self, set: &ActionSet) -> Result<(), Error> {
+		for (layout, action_map) in set.iter() {
+			if !self.layouts.contains(layout) {
+				return Err(Error::UnknownLayout(*layout));
+			}
+			for (action_id, behavior_binding) in action_map.iter() {
+				if !self.actions.contains_key(action_id) {
+					return Err(Error::UnknownAction(action_id));
+				}
+				behavior_binding.validate()?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Returns the id of the action with the provided name, if it has been added.
+	pub(crate) fn find_action(&self, name: &str) -> Option<action::Id> {
+		self.actions.keys().find(|id| **id == name).cloned()
+	}
+
+	/// Returns the id of the layout with the provided name (or the default layout for `None`), if it has been added.
+	pub(crate) fn find_layout(&self, name: Option<&str>) -> Option<LayoutId> {
+		self.layouts.iter().find(|layout| **layout == name).cloned()
+	}
+
+	#[cfg(feature = "hot-reload")]
+	pub(crate) fn set_action_set(&mut self, id: ActionSetId, set: ActionSet) {
+		self.action_sets.insert(id, set);
+	}
+
+	/// Returns the id of the action set with the provided name (or the default set for `None`), if it has been added.
+	pub(crate) fn find_action_set(&self, name: Option<&str>) -> Option<ActionSetId> {
+		self.action_sets.keys().find(|id| **id == name).cloned()
+	}
+
+	pub(crate) fn get_action_kind(&self, id: &action::Id) -> Option<source::Kind> {
+		self.actions.get(id).cloned()
+	}
+
 	pub(crate) fn get_action_set(&self, id: &binding::ActionSetId) -> Option<&binding::ActionSet> {
 		self.action_sets.get(&id)
 	}
 }
 
 pub struct DeviceCache {
-	gamepad_input: gilrs::Gilrs,
+	gamepad_input: Option<gilrs::Gilrs>,
+	focused: bool,
 	consts: Arc<RwLock<Consts>>,
 	unassigned_devices: Vec<device::Id>,
 	assigned_devices: HashMap<device::Id, (WeakLockUser, event::InputSender)>,
 	disconnected_devices: HashMap<device::Id, (WeakLockUser, event::InputSender)>,
+	/// The stable identities of connected (and disconnected but assigned) gamepads.
+	identities: HashMap<device::Id, device::Identity>,
+	/// The identities of gamepads when they were disconnected from their users, most recently disconnected last.
+	/// Kept apart from `identities`, since another gamepad may connect with the id of a disconnected one.
+	disconnected_identities: Vec<(device::Id, Option<device::Identity>)>,
 	users: Vec<(WeakLockUser, Vec<device::Id>)>,
+	assignment_policy: device::AssignmentPolicy,
+	/// The index of the user who will be given the next device, for [`RoundRobin`](device::AssignmentPolicy::RoundRobin) assignment.
+	next_user: usize,
+	join: Option<join::Join>,
+	/// False while join mode is paused, so devices neither join nor leave.
+	joining: bool,
+	/// The users created when players joined, which are owned by the cache.
+	joined_users: Vec<ArcLockUser>,
+	join_observers: Vec<crossbeam_channel::Sender<join::Event>>,
+	device_observers: Vec<crossbeam_channel::Sender<device::Event>>,
+	recorder: Option<recording::Recorder>,
+	playback: Option<recording::Playback>,
+	#[cfg(feature = "hot-reload")]
+	watchers: Vec<crate::reload::ConfigWatcher>,
 }
 
 impl Default for DeviceCache {
+	/// # Panics
+	/// If gamepads could not be initialized. See [`DeviceCache::new`].
 	fn default() -> Self {
-		Self {
-			gamepad_input: gilrs::Gilrs::new().unwrap(),
+		Self::new().unwrap_or_else(|error| panic!("{}", error))
+	}
+}
+
+impl DeviceCache {
+	/// Creates the device cache, failing if the gamepad backend could not be initialized.
+	pub fn new() -> Result<Self, Error> {
+		Self::with_gamepad_backend(device::GamepadBackend::default())
+	}
+
+	/// Creates the device cache with gamepad input coming from a specific backend,
+	/// failing if the backend could not be initialized.
+	pub fn with_gamepad_backend(backend: device::GamepadBackend) -> Result<Self, Error> {
+		let gamepad_input = match backend {
+			device::GamepadBackend::Gilrs => {
+				Some(gilrs::Gilrs::new().map_err(|error| Error::Gamepad(error.to_string()))?)
+			}
+			device::GamepadBackend::External => None,
+		};
+		Ok(Self {
+			gamepad_input,
+			focused: true,
 			consts: Default::default(),
 			unassigned_devices: vec![device::Id::Mouse, device::Id::Keyboard],
 			assigned_devices: HashMap::new(),
 			disconnected_devices: HashMap::new(),
+			identities: HashMap::new(),
+			disconnected_identities: Vec::new(),
 			users: Vec::new(),
+			assignment_policy: device::AssignmentPolicy::default(),
+			next_user: 0,
+			join: None,
+			joining: false,
+			joined_users: Vec::new(),
+			join_observers: Vec::new(),
+			device_observers: Vec::new(),
+			recorder: None,
+			playback: None,
+			#[cfg(feature = "hot-reload")]
+			watchers: Vec::new(),
 		}
-		.initialize_gamepads()
+		.initialize_gamepads())
+	}
+
+	/// Sets how unassigned devices are given to users (the first free user by default).
+	pub fn with_assignment_policy(mut self, policy: device::AssignmentPolicy) -> Self {
+		self.assignment_policy = policy;
+		self
+	}
+
+	/// Changes how unassigned devices are given to users. Devices which are already assigned keep their users.
+	pub fn set_assignment_policy(&mut self, policy: device::AssignmentPolicy) {
+		self.assignment_policy = policy;
+	}
+
+	/// Returns how unassigned devices are given to users.
+	pub fn assignment_policy(&self) -> device::AssignmentPolicy {
+		self.assignment_policy
+	}
+
+	/// Enables join mode, where devices are held until they press a join source instead of being given to users automatically.
+	/// See [`Join`](join::Join).
+	pub fn with_join(mut self, join: join::Join) -> Self {
+		self.join = Some(join);
+		self.joining = true;
+		self
+	}
+
+	/// Pauses or resumes join mode (for example, closing the lobby when the game starts).
+	/// While paused, unassigned devices are still held, but players cannot join or leave.
+	pub fn set_joining(&mut self, joining: bool) {
+		self.joining = joining && self.join.is_some();
+	}
+
+	/// Returns true if the cache is in join mode, and players can currently join or leave.
+	pub fn is_joining(&self) -> bool {
+		self.joining
+	}
+
+	/// Returns a channel which receives players joining and leaving while in join mode.
+	/// Dropping the receiver unsubscribes it.
+	pub fn subscribe_to_joins(&mut self) -> crossbeam_channel::Receiver<join::Event> {
+		let (sender, receiver) = crossbeam_channel::unbounded();
+		self.join_observers.push(sender);
+		receiver
+	}
+
+	/// Returns a channel which receives devices connecting, disconnecting, and changing users.
+	/// Dropping the receiver unsubscribes it.
+	pub fn subscribe_to_devices(&mut self) -> crossbeam_channel::Receiver<device::Event> {
+		let (sender, receiver) = crossbeam_channel::unbounded();
+		self.device_observers.push(sender);
+		receiver
+	}
+
+	/// Returns every known device: those assigned to users, unassigned devices,
+	/// and devices which were disconnected from their users.
+	pub fn devices(&self) -> Vec<device::Info> {
+		self.users
+			.iter()
+			.flat_map(|(_, device_ids)| device_ids.iter())
+			.chain(self.unassigned_devices.iter())
+			.chain(self.disconnected_devices.keys())
+			.filter_map(|&device_id| self.device_info(device_id))
+			.collect()
+	}
+
+	/// Returns information about a device, if it is connected (or was disconnected from a user).
+	pub fn device_info(&self, device_id: device::Id) -> Option<device::Info> {
+		let (user, connected) = if self.unassigned_devices.contains(&device_id) {
+			(None, true)
+		} else if let Some((weak_user, _)) = self.assigned_devices.get(&device_id) {
+			(self.user_index(weak_user), true)
+		} else if let Some((weak_user, _)) = self.disconnected_devices.get(&device_id) {
+			(self.user_index(weak_user), false)
+		} else {
+			return None;
+		};
+		let gamepad = match (device_id, self.gamepad_input.as_ref()) {
+			(device::Id::Gamepad(_, index), Some(gamepad_input)) => gamepad_input
+				.gamepads()
+				.find(|(id, _)| Into::<usize>::into(*id) == index)
+				.map(|(_, gamepad)| gamepad),
+			_ => None,
+		};
+		Some(device::Info::new(
+			device_id,
+			self.identities.get(&device_id).cloned(),
+			user,
+			connected,
+			gamepad,
+		))
+	}
+
+	fn user_index(&self, weak_user: &WeakLockUser) -> Option<UserId> {
+		self.users
+			.iter()
+			.position(|(weak, _)| weak.ptr_eq(weak_user))
+	}
+
+	fn notify_device(&mut self, event: device::Event) {
+		self.device_observers
+			.retain(|sender| sender.send(event).is_ok());
 	}
-}
 
-impl DeviceCache {
 	/// Grabs all gamepads from gilrs and attempts to connect them (or cache them if there are no users).
 	/// User internally when constructing the singleton.
 	fn initialize_gamepads(mut self) -> Self {
-		let existing_gamepad_ids = self
-			.gamepad_input
-			.gamepads()
-			.map(|(id, _)| id)
-			.collect::<Vec<_>>();
+		let existing_gamepad_ids = match self.gamepad_input.as_ref() {
+			Some(gamepad_input) => gamepad_input.gamepads().map(|(id, _)| id).collect(),
+			None => Vec::new(),
+		};
 		for id in existing_gamepad_ids {
-			self.connect_gamepad(id);
+			self.identify_gilrs_gamepad(id);
+			self.connect_gamepad(Self::gamepad_device_id(id));
 		}
 		self
 	}
 
+	/// Sets the stable identity of a gamepad from an [`External`](device::GamepadBackend::External) backend,
+	/// so it can be given back to its user if it reconnects with a different id.
+	/// Should be called before the gamepad is connected via [`send_event`](DeviceCache::send_event).
+	pub fn identify_device(&mut self, device_id: device::Id, identity: device::Identity) {
+		self.identities.insert(device_id, identity);
+	}
+
+	fn identify_gilrs_gamepad(&mut self, id: gilrs::GamepadId) {
+		let identity = self
+			.gamepad_input
+			.as_ref()
+			.and_then(|gamepad_input| gamepad_input.connected_gamepad(id))
+			.map(|gamepad| device::Identity::new(gamepad.uuid()));
+		if let Some(identity) = identity {
+			self.identities
+				.insert(Self::gamepad_device_id(id), identity);
+		}
+	}
+
+	fn gamepad_device_id(id: gilrs::GamepadId) -> device::Id {
+		device::Id::Gamepad(Self::get_gamepad_kind(&id), id.into())
+	}
+
 	fn get_gamepad_kind(_id: &gilrs::GamepadId) -> GamepadKind {
 		// GILRS seems to always provide "Xbox Controller" as the gamepad name (`name()` AND `os_name()`)
 		// regardless of what kind of controller is actually is.
@@ -95,116 +378,435 @@ impl DeviceCache {
 	/// Connects a gamepad to user data.
 	///
 	/// If the gamepad previously disconnected from a user, it is automatically assigned to the same user
-	/// (assuming the provided `id` is the same).
+	/// (if it has the same [`identity`](device::Identity), or the same `id` when its identity is unknown).
+	/// Gamepads of the same model can only be told apart by a serial number or path, so without one,
+	/// the gamepad is given to the user of the most recently disconnected gamepad of its model.
 	///
 	/// If user already has another gamepad or the gamepad was never previously connected,
 	/// then it is assigned to the first user without a gamepad.
-	fn connect_gamepad(&mut self, id: gilrs::GamepadId) {
-		let device_id = device::Id::Gamepad(Self::get_gamepad_kind(&id), id.into());
+	fn connect_gamepad(&mut self, device_id: device::Id) {
+		self.record(recording::Entry::DeviceConnected(device_id));
 
-		if let Some((weak_user, _)) = self.disconnected_devices.remove(&device_id) {
+		let previous_id = self.find_disconnected(device_id);
+		if let Some((weak_user, _)) =
+			previous_id.and_then(|id| self.disconnected_devices.remove(&id))
+		{
+			self.disconnected_identities
+				.retain(|(id, _)| Some(*id) != previous_id);
+			// The identity of the previous id may belong to a gamepad which has since connected with that id.
+			if let Some(previous_id) = previous_id.filter(|id| *id != device_id) {
+				if !self.unassigned_devices.contains(&previous_id)
+					&& !self.assigned_devices.contains_key(&previous_id)
+				{
+					self.identities.remove(&previous_id);
+				}
+			}
 			if let Some(arc_user) = weak_user.upgrade() {
-				self.assign_device(&arc_user, device_id);
+				if let Some(index) = self.insert_device(&arc_user, device_id) {
+					self.notify_device(device::Event::Reconnected(device_id, index));
+				}
 				return;
 			}
 		}
 
 		self.unassigned_devices.push(device_id);
+		self.notify_device(device::Event::Connected(device_id));
 	}
 
-	/// Unassigns a gilrs gamepad from an user it may be assigned to.
-	fn disconnect_gamepad(&mut self, id: gilrs::GamepadId) {
-		let device_id = device::Id::Gamepad(Self::get_gamepad_kind(&id), id.into());
+	/// Returns the disconnected gamepad which a newly connected gamepad is (most likely) the same as.
+	/// Identities are compared when both gamepads have one, since gilrs can give a different gamepad the id of a disconnected one.
+	/// Otherwise the gamepad must have the same id. The most recently disconnected match is preferred.
+	fn find_disconnected(&self, device_id: device::Id) -> Option<device::Id> {
+		let identity = self.identities.get(&device_id);
+		self.disconnected_identities
+			.iter()
+			.rev()
+			.filter(|(id, _)| self.disconnected_devices.contains_key(id))
+			.find(|(id, other)| match (identity, other) {
+				(Some(identity), Some(other)) => other.matches(identity),
+				_ => *id == device_id,
+			})
+			.map(|(id, _)| *id)
+	}
+
+	/// Unassigns a gamepad from an user it may be assigned to.
+	fn disconnect_gamepad(&mut self, device_id: device::Id) {
+		self.record(recording::Entry::DeviceDisconnected(device_id));
+		let mut user = None;
 		if let Some(owner) = self.assigned_devices.remove(&device_id) {
-			for (weak, device_ids) in self.users.iter_mut() {
+			for (index, (weak, device_ids)) in self.users.iter_mut().enumerate() {
 				if weak.ptr_eq(&owner.0) {
 					device_ids.retain(|&id| id != device_id);
+					user = Some(index);
 					break;
 				}
 			}
+			Self::release_device_actions(&owner.0, device_id);
+			self.disconnected_identities
+				.retain(|(id, _)| *id != device_id);
+			self.disconnected_identities
+				.push((device_id, self.identities.get(&device_id).cloned()));
 			self.disconnected_devices.insert(device_id, owner);
 		} else {
 			self.unassigned_devices.retain(|&id| id != device_id);
+			self.identities.remove(&device_id);
 		}
+		self.notify_device(device::Event::Disconnected(device_id, user));
 	}
 
 	fn assign_device(&mut self, arc_user: &ArcLockUser, device_id: device::Id) {
+		if let Some(index) = self.insert_device(arc_user, device_id) {
+			self.notify_device(device::Event::Assigned(device_id, index));
+		}
+	}
+
+	/// Gives a device to a user, returning the index of the user.
+	fn insert_device(&mut self, arc_user: &ArcLockUser, device_id: device::Id) -> Option<UserId> {
 		let input_sender = arc_user.read().unwrap().input_sender().clone();
 		let weak_user = Arc::downgrade(&arc_user);
-		for (weak, device_ids) in self.users.iter_mut() {
+		let mut user = None;
+		for (index, (weak, device_ids)) in self.users.iter_mut().enumerate() {
 			if weak.ptr_eq(&weak_user) {
 				device_ids.push(device_id);
+				user = Some(index);
 				break;
 			}
 		}
 		self.assigned_devices
 			.insert(device_id, (weak_user, input_sender));
+		user
 	}
 
-	/// Iterates over all unassigned devices and attempts to assign them to users.
+	/// Iterates over all unassigned devices and attempts to assign them to users via the assignment policy.
 	/// Used predominately to assign devices to users on initialization
 	/// (where users are added after the system queries all the gamepads).
 	fn assign_unused_devices(&mut self) {
+		// In join mode, devices are only assigned when they press a join source.
+		if self.join.is_some() || self.assignment_policy == device::AssignmentPolicy::Manual {
+			return;
+		}
 		let unused_devices = self.unassigned_devices.drain(..).collect::<Vec<_>>();
-		'iterDevices: for device in unused_devices {
-			match device {
-				// Mouse and Keyboard devices should always go to the first user
-				device::Id::Mouse | device::Id::Keyboard => {
-					for (weak_user, _device_ids) in self.users.iter_mut() {
-						if let Some(arc_user) = weak_user.upgrade() {
-							self.assign_device(&arc_user, device);
-							continue 'iterDevices;
-						}
-					}
+		for device in unused_devices {
+			match self.user_for_unused_device(device) {
+				Some(arc_user) => self.assign_device(&arc_user, device),
+				None => self.unassigned_devices.push(device),
+			}
+		}
+	}
+
+	/// Returns the user an unassigned device should be given to, according to the assignment policy.
+	fn user_for_unused_device(&mut self, device: device::Id) -> Option<ArcLockUser> {
+		let live_users =
+			self.users
+				.iter()
+				.enumerate()
+				.filter_map(|(index, (weak_user, device_ids))| {
+					Some((index, weak_user.upgrade()?, device_ids))
+				});
+		match (self.assignment_policy, device) {
+			(device::AssignmentPolicy::Manual, _) => None,
+			(device::AssignmentPolicy::SingleUser, _)
+			// Mouse and Keyboard devices should always go to the first user
+			| (
+				device::AssignmentPolicy::FirstFree,
+				device::Id::Mouse | device::Id::Keyboard,
+			) => live_users.map(|(_, arc_user, _)| arc_user).next(),
+			// Assign gamepads to users without gamepads
+			(device::AssignmentPolicy::FirstFree, device::Id::Gamepad(_, _)) => live_users
+				.filter(|(_, _, device_ids)| {
+					!device_ids
+						.iter()
+						.any(|id| matches!(id, device::Id::Gamepad(_, _)))
+				})
+				.map(|(_, arc_user, _)| arc_user)
+				.next(),
+			(device::AssignmentPolicy::RoundRobin, _) => {
+				let partner = match device {
+					device::Id::Mouse => Some(device::Id::Keyboard),
+					device::Id::Keyboard => Some(device::Id::Mouse),
+					device::Id::Gamepad(_, _) => None,
+				};
+				let partner_user = partner
+					.and_then(|id| self.assigned_devices.get(&id))
+					.and_then(|(weak_user, _)| weak_user.upgrade());
+				if partner_user.is_some() {
+					return partner_user;
 				}
-				// Assign gamepads to users without gamepads
-				device::Id::Gamepad(_, _) => {
-					for (weak_user, device_ids) in self.users.iter_mut() {
-						if let Some(arc_user) = weak_user.upgrade() {
-							let has_gamepad = device_ids.iter().any(|id| match id {
-								device::Id::Gamepad(_, _) => true,
-								_ => false,
-							});
-							if !has_gamepad {
-								self.assign_device(&arc_user, device);
-								continue 'iterDevices;
-							}
-						}
-					}
+				let live_users = live_users.collect::<Vec<_>>();
+				let (index, arc_user, _) = live_users
+					.iter()
+					.find(|(index, _, _)| *index >= self.next_user)
+					.or_else(|| live_users.first())?;
+				self.next_user = index + 1;
+				Some(arc_user.clone())
+			}
+		}
+	}
+
+	/// Gives a device to the user at the provided index of [`users`](DeviceCache::users), taking it from its current user.
+	/// A disconnected device will be reconnected to the new user.
+	pub fn assign(&mut self, device_id: device::Id, user: UserId) -> Result<(), Error> {
+		let arc_user = self
+			.users
+			.get(user)
+			.and_then(|(weak_user, _)| weak_user.upgrade())
+			.ok_or(Error::UnknownUser(user))?;
+		if let Some(entry) = self.disconnected_devices.get_mut(&device_id) {
+			let previous_owner = entry.0.clone();
+			*entry = (
+				Arc::downgrade(&arc_user),
+				arc_user.read().unwrap().input_sender().clone(),
+			);
+			Self::release_device_actions(&previous_owner, device_id);
+			if let Some(index) = self.user_index(&previous_owner) {
+				self.notify_device(device::Event::Unassigned(device_id, index));
+			}
+			self.notify_device(device::Event::Assigned(device_id, user));
+			return Ok(());
+		}
+		if self.unassigned_devices.contains(&device_id) {
+			self.unassigned_devices.retain(|id| *id != device_id);
+		} else if self.owner_index(device_id).is_some() {
+			self.take_device(device_id);
+		} else {
+			return Err(Error::UnknownDevice(device_id));
+		}
+		self.assign_device(&arc_user, device_id);
+		Ok(())
+	}
+
+	/// Takes a device from its user, returning the index of the user it was assigned to (if any).
+	/// Unless the assignment policy is [`Manual`](device::AssignmentPolicy::Manual) (or the cache is in join mode),
+	/// the device will be given to a user again on the next [`update`](DeviceCache::update).
+	pub fn unassign(&mut self, device_id: device::Id) -> Option<UserId> {
+		if let Some((previous_owner, _)) = self.disconnected_devices.remove(&device_id) {
+			self.identities.remove(&device_id);
+			Self::release_device_actions(&previous_owner, device_id);
+			let index = self.user_index(&previous_owner)?;
+			self.notify_device(device::Event::Unassigned(device_id, index));
+			return Some(index);
+		}
+		let index = self.owner_index(device_id)?;
+		self.take_device(device_id);
+		self.unassigned_devices.push(device_id);
+		Some(index)
+	}
+
+	/// Returns the devices assigned to the user at the provided index of [`users`](DeviceCache::users)
+	/// (not including any which are disconnected).
+	pub fn devices_of(&self, user: UserId) -> &[device::Id] {
+		self.users
+			.get(user)
+			.map_or(&[], |(_, device_ids)| device_ids.as_slice())
+	}
+
+	fn owner_index(&self, device_id: device::Id) -> Option<UserId> {
+		self.users
+			.iter()
+			.position(|(_, device_ids)| device_ids.contains(&device_id))
+	}
+
+	/// Removes a device from the user it is assigned to, releasing that user's actions.
+	fn take_device(&mut self, device_id: device::Id) {
+		if let Some((weak_user, _)) = self.assigned_devices.remove(&device_id) {
+			for (index, (weak, device_ids)) in self.users.iter_mut().enumerate() {
+				if weak.ptr_eq(&weak_user) {
+					device_ids.retain(|&id| id != device_id);
+					self.notify_device(device::Event::Unassigned(device_id, index));
+					break;
 				}
 			}
-			self.unassigned_devices.push(device);
+			Self::release_device_actions(&weak_user, device_id);
 		}
 	}
 
+	/// Releases the actions a user had bound to a device they lost, so inputs held on the device do not leave actions stuck down.
+	/// Gamepads of the same kind share sources, so any other gamepad of that kind the user has is released as well.
+	fn release_device_actions(weak_user: &WeakLockUser, device_id: device::Id) {
+		if let Some(arc_user) = weak_user.upgrade() {
+			arc_user.write().unwrap().release_device(device_id.into());
+		}
+	}
+
+	/// Adds a user, whose [`UserId`] is the number of users added before it.
 	pub fn add_user(&mut self, user: WeakLockUser) {
 		self.users.push((user, Vec::new()));
 	}
 
+	/// Joins or leaves the game if any of the events from a device press a join or leave source.
+	/// Returns true if the events were consumed by joining or leaving.
+	fn join_or_leave(&mut self, events: &[(device::Id, binding::Source, event::State)]) -> bool {
+		let join = match self.join.as_ref() {
+			Some(join) if self.joining => join,
+			_ => return false,
+		};
+		let leave_device = events
+			.iter()
+			.find(|(_, source, state)| join.is_leave(source, state))
+			.map(|(device, _, _)| *device);
+		let join_device = events
+			.iter()
+			.find(|(_, source, state)| join.is_join(source, state))
+			.map(|(device, _, _)| *device);
+		if let Some(device_id) = leave_device {
+			if let Some(index) = self
+				.users
+				.iter()
+				.position(|(_, ids)| ids.contains(&device_id))
+			{
+				self.leave(index);
+				return true;
+			}
+		}
+		if let Some(device_id) = join_device {
+			if self.unassigned_devices.contains(&device_id) {
+				self.join(device_id);
+				return true;
+			}
+		}
+		false
+	}
+
+	/// Assigns an unassigned device (and its partner, for the keyboard and mouse) to the first user without devices,
+	/// or to a new user if every user has devices.
+	fn join(&mut self, device_id: device::Id) {
+		let device_ids = match device_id {
+			device::Id::Keyboard | device::Id::Mouse => {
+				vec![device::Id::Keyboard, device::Id::Mouse]
+			}
+			device::Id::Gamepad(_, _) => vec![device_id],
+		}
+		.into_iter()
+		.filter(|id| self.unassigned_devices.contains(id))
+		.collect::<Vec<_>>();
+
+		let joined_users = (0..self.users.len())
+			.filter(|index| self.has_joined(*index))
+			.count();
+		let is_full = self
+			.join
+			.as_ref()
+			.is_none_or(|join| join.is_full(joined_users));
+		let slot = match is_full {
+			true => None,
+			false => self.vacant_user().or_else(|| self.create_user()),
+		};
+		let event = match slot {
+			Some((index, arc_user)) => {
+				self.unassigned_devices
+					.retain(|id| !device_ids.contains(id));
+				for id in device_ids {
+					self.assign_device(&arc_user, id);
+				}
+				join::Event::Joined(index, device_id)
+			}
+			None => join::Event::Rejected(device_id),
+		};
+		self.notify_join(event);
+	}
+
+	/// Unassigns all of a user's devices (including those which are disconnected), keeping the user for the next player to join.
+	fn leave(&mut self, index: UserId) {
+		let (weak_user, device_ids) = &mut self.users[index];
+		let weak_user = weak_user.clone();
+		let device_ids = std::mem::take(device_ids);
+		for id in device_ids {
+			self.assigned_devices.remove(&id);
+			self.unassigned_devices.push(id);
+			// Actions held when the player left would otherwise stay held until the next player joins.
+			Self::release_device_actions(&weak_user, id);
+			self.notify_device(device::Event::Unassigned(id, index));
+		}
+		// Disconnected devices are unassigned when they reconnect.
+		self.disconnected_devices
+			.retain(|_, (owner, _)| !owner.ptr_eq(&weak_user));
+		self.notify_join(join::Event::Left(index));
+	}
+
+	/// Returns true if the user at the index has devices (even if they are disconnected).
+	fn has_joined(&self, index: UserId) -> bool {
+		let (weak_user, device_ids) = &self.users[index];
+		!device_ids.is_empty()
+			|| self
+				.disconnected_devices
+				.values()
+				.any(|(owner, _)| owner.ptr_eq(weak_user))
+	}
+
+	/// Returns the first user who has not joined.
+	fn vacant_user(&self) -> Option<(UserId, ArcLockUser)> {
+		(0..self.users.len())
+			.filter(|index| !self.has_joined(*index))
+			.find_map(|index| Some((index, self.users[index].0.upgrade()?)))
+	}
+
+	/// Creates a user for a new player via the join user factory, if there is one.
+	fn create_user(&mut self) -> Option<(UserId, ArcLockUser)> {
+		let index = self.users.len();
+		let factory = self.join.as_mut()?.user_factory()?;
+		let arc_user = factory(index).with_consts(self.consts()).arclocked();
+		self.joined_users.push(arc_user.clone());
+		self.add_user(Arc::downgrade(&arc_user));
+		Some((index, arc_user))
+	}
+
+	fn notify_join(&mut self, event: join::Event) {
+		self.join_observers
+			.retain(|sender| sender.send(event.clone()).is_ok());
+	}
+
 	/// Queries the gilrs system to get all gamepad input events.
 	/// Sends relevant events to `process_event` (or connects/disconnects the gamepad if required).
 	pub fn update(&mut self) {
 		self.prune_users();
 		self.assign_unused_devices();
 		self.read_events();
+		self.play_frames();
+		#[cfg(feature = "hot-reload")]
+		self.reload_configs();
+		if let Some(recorder) = self.recorder.as_mut() {
+			recorder.end_frame();
+		}
+	}
+
+	/// Reloads the action sets in a bindings file whenever it changes (checked each [`update`](DeviceCache::update)).
+	#[cfg(feature = "hot-reload")]
+	pub fn watch_config(&mut self, watcher: crate::reload::ConfigWatcher) {
+		self.watchers.push(watcher);
+	}
+
+	#[cfg(feature = "hot-reload")]
+	fn reload_configs(&mut self) {
+		for watcher in self.watchers.iter_mut() {
+			for (id, set) in watcher.poll() {
+				for (weak_user, _) in self.users.iter() {
+					if let Some(arc_user) = weak_user.upgrade() {
+						arc_user.write().unwrap().reload_action_set(id, set.clone());
+					}
+				}
+			}
+		}
 	}
 
+	/// Returns every user added to the cache, indexed by [`UserId`] (including users which have since been dropped).
 	pub fn users(&self) -> Vec<WeakLockUser> {
 		self.users.iter().map(|(user, _)| user.clone()).collect()
 	}
 
+	/// Unassigns the devices of users who've been dropped.
+	/// The users keep their place in `users`, so that the ids of later users do not change.
 	fn prune_users(&mut self) {
-		// Remove all users who've been dropped.
-		// Can use `Vec::drain_filter` when that api stabilizes.
-		// O(n) performance where `n` is the number of loaded chunks
-		let mut i = 0;
-		while i < self.users.len() {
-			if self.users[i].0.strong_count() == 0 {
-				let (_, mut device_ids) = self.users.remove(i);
-				self.unassigned_devices.append(&mut device_ids);
-			} else {
-				i += 1;
+		for index in 0..self.users.len() {
+			let (weak_user, device_ids) = &mut self.users[index];
+			if weak_user.strong_count() > 0 || device_ids.is_empty() {
+				continue;
 			}
+			let mut device_ids = std::mem::take(device_ids);
+			for &id in device_ids.iter() {
+				self.assigned_devices.remove(&id);
+				self.notify_device(device::Event::Unassigned(id, index));
+			}
+			self.unassigned_devices.append(&mut device_ids);
 		}
 	}
 
@@ -215,16 +817,22 @@ impl DeviceCache {
 			id,
 			event, /*system time*/
 			..
-		}) = self.gamepad_input.next_event()
+		}) = self
+			.gamepad_input
+			.as_mut()
+			.and_then(|gamepad_input| gamepad_input.next_event())
 		{
 			let gamepad_kind = Self::get_gamepad_kind(&id);
 			let device = device::Id::Gamepad(gamepad_kind, id.into());
 			match event {
 				// Gamepad has been connected. If gamepad's UUID doesn't match one of disconnected gamepads,
 				// newly connected gamepad will get new ID.
-				EventType::Connected => self.connect_gamepad(id),
+				EventType::Connected => {
+					self.identify_gilrs_gamepad(id);
+					self.connect_gamepad(device);
+				}
 				// Gamepad has been disconnected. Disconnected gamepad will not generate any new events.
-				EventType::Disconnected => self.disconnect_gamepad(id),
+				EventType::Disconnected => self.disconnect_gamepad(device),
 				// There was an `Event`, but it was dropped by one of filters. You should ignore it.
 				EventType::Dropped => {}
 				// Some button on gamepad has been pressed.
@@ -286,48 +894,307 @@ impl DeviceCache {
 		Arc::downgrade(&self.consts)
 	}
 
+	/// Begins capturing all input sent to users, device connections, and window events.
+	/// Each call to [`update`](DeviceCache::update) marks the end of a recorded frame.
+	/// Any in-progress recording is discarded.
+	pub fn start_recording(&mut self) {
+		self.recorder = Some(recording::Recorder::new());
+	}
+
+	/// Stops the active recording, returning the captured frames (if a recording was in progress).
+	pub fn stop_recording(&mut self) -> Option<recording::Recording> {
+		self.recorder.take().map(|recorder| recorder.finish())
+	}
+
+	pub fn is_recording(&self) -> bool {
+		self.recorder.is_some()
+	}
+
+	fn record(&mut self, entry: recording::Entry) {
+		if let Some(recorder) = self.recorder.as_mut() {
+			recorder.push(entry);
+		}
+	}
+
+	/// Begins feeding the frames of a [`recording`](recording::Recording) to users,
+	/// one frame per [`update`](DeviceCache::update) (scaled by [`speed`](recording::Playback::set_speed)).
+	/// While playback is active, all live device and window events are ignored.
+	///
+	/// Users are updated by the cache after each recorded frame, at the time the frame was recorded,
+	/// so that every press and release reaches the user exactly as it did when recording
+	/// (even when several frames are played in one update).
+	/// Until playback is stopped, [`User::update`](crate::User::update) and [`User::update_tick`](crate::User::update_tick) do nothing.
+	///
+	/// Recorded inputs are sent to users by their index in [`users`](DeviceCache::users),
+	/// so the same amount of users should exist as when the recording was made.
+	pub fn start_playback(&mut self, recording: recording::Recording) {
+		self.playback = Some(recording::Playback::new(recording));
+		for arc_user in self.live_users() {
+			arc_user.write().unwrap().set_playback_active(true);
+		}
+	}
+
+	/// Stops playback (if active), returning the recording that was being played.
+	pub fn stop_playback(&mut self) -> Option<recording::Recording> {
+		for arc_user in self.live_users() {
+			arc_user.write().unwrap().set_playback_active(false);
+		}
+		self.playback
+			.take()
+			.map(|playback| playback.recording().clone())
+	}
+
+	pub fn playback(&self) -> Option<&recording::Playback> {
+		self.playback.as_ref()
+	}
+
+	/// Provides access to the active playback in order to pause, resume, or change the speed.
+	pub fn playback_mut(&mut self) -> Option<&mut recording::Playback> {
+		self.playback.as_mut()
+	}
+
+	/// Moves the active playback to the provided frame.
+	/// All user action states are reset (handles to them remain valid), and each frame up to `frame` is replayed immediately
+	/// (updating each user once per frame) so that action states match the state when the frame was recorded.
+	pub fn seek_playback(&mut self, frame: usize) {
+		let mut playback = match self.playback.take() {
+			Some(playback) => playback,
+			None => return,
+		};
+		let users = self.live_users();
+		for arc_user in users.iter() {
+			arc_user.write().unwrap().reset_action_states();
+		}
+		for index in playback.seek(frame) {
+			self.play_frame(&playback, index, &users);
+		}
+		self.playback = Some(playback);
+	}
+
+	fn play_frames(&mut self) {
+		if let Some(mut playback) = self.playback.take() {
+			let users = self.live_users();
+			for index in playback.advance() {
+				self.play_frame(&playback, index, &users);
+			}
+			self.playback = Some(playback);
+		}
+	}
+
+	/// Sends the entries of a recorded frame, then updates each user at the time the frame was recorded.
+	fn play_frame(&mut self, playback: &recording::Playback, index: usize, users: &[ArcLockUser]) {
+		let frame = &playback.recording().frames()[index];
+		self.play_entries(frame);
+		let time = playback.frame_instant(frame);
+		for arc_user in users.iter() {
+			let mut user = arc_user.write().unwrap();
+			// Users added since playback started are also driven by the recording.
+			user.set_playback_active(true);
+			user.update_playback_frame(&time);
+		}
+	}
+
+	fn live_users(&self) -> Vec<ArcLockUser> {
+		self.users
+			.iter()
+			.filter_map(|(weak_user, _)| weak_user.upgrade())
+			.collect()
+	}
+
+	fn play_entries(&mut self, frame: &recording::Frame) {
+		for entry in frame.entries.iter() {
+			match entry {
+				recording::Entry::Input(user_index, source, state) => {
+					let arc_user = self
+						.users
+						.get(*user_index)
+						.and_then(|(weak_user, _)| weak_user.upgrade());
+					if let Some(arc_user) = arc_user {
+						let user = arc_user.read().unwrap();
+						let _ = user.input_sender().try_send((*source, *state));
+					}
+				}
+				// Focus belongs to the live window, so replaying it could leave the live window ignoring input.
+				recording::Entry::Window(event::WindowEvent::Focused(_)) => {}
+				recording::Entry::Window(window_event) => {
+					self.apply_window_event(window_event.clone());
+				}
+				// Devices are physically connected or disconnected regardless of playback,
+				// so recorded device events are only informational.
+				recording::Entry::DeviceConnected(_) | recording::Entry::DeviceDisconnected(_) => {}
+			}
+		}
+	}
+
 	/// Sends an input event to the system.
 	/// Use with caution! Gamepad events are already handled/built-in,
 	/// but Mouse and Keyboard events should come from the relevant feature/extension (like winit).
+	///
+	/// While a recording is [`playing back`](DeviceCache::start_playback), input, keys, text, and window events
+	/// (other than focus changes) are ignored, since they are replayed from the recording instead.
+	/// Devices connecting or disconnecting and focus changes still apply, since they describe the live system.
 	pub fn send_event(&mut self, event: event::Event) {
+		let is_live = matches!(
+			event,
+			event::Event::DeviceConnected(_)
+				| event::Event::DeviceDisconnected(_)
+				| event::Event::Window(event::WindowEvent::Focused(_))
+		);
+		if self.playback.is_some() && !is_live {
+			return;
+		}
 		match event {
-			event::Event::Window(event::WindowEvent::ResolutionChanged(width, height)) => {
-				let mut consts = self.consts.write().unwrap();
+			event::Event::Window(window_event) => {
+				self.record(recording::Entry::Window(window_event.clone()));
+				self.apply_window_event(window_event);
+			}
+			event::Event::Input(device_source, binding_source, state) => {
+				self.send_device_event((device_source, binding_source, state));
+			}
+			event::Event::DeviceConnected(device_id) => self.connect_gamepad(device_id),
+			event::Event::DeviceDisconnected(device_id) => self.disconnect_gamepad(device_id),
+			event::Event::Text(input) => {
+				self.send_text_event(text::TextEvent::Input(input));
+			}
+			event::Event::Key {
+				logical,
+				scancode,
+				state,
+			} => {
+				// Keys without a variant are still bound by their position, unless the scancode is unknown too.
+				let physical = Key::from_scancode(scancode)
+					.or_else(|| Some(Key::Unknown(scancode)).filter(|_| scancode != 0));
+				if let (Some(physical), Some(logical)) = (physical, logical) {
+					let mut consts = self.consts.write().unwrap();
+					if consts.keyboard_layout.logical(physical) != logical {
+						consts.keyboard_layout.set(physical, logical);
+					}
+				}
+				let state = event::State::ButtonState(state);
+				if let Some(logical) = logical {
+					self.send_device_event((
+						device::Id::Keyboard,
+						binding::Source::Keyboard(logical),
+						state,
+					));
+				}
+				if let Some(physical) = physical {
+					self.send_device_event((
+						device::Id::Keyboard,
+						binding::Source::PhysicalKey(physical),
+						state,
+					));
+				}
+			}
+		}
+	}
+
+	/// Returns the keyboard layout, as set via [`set_keyboard_layout`](DeviceCache::set_keyboard_layout)
+	/// and learned from [`key events`](event::Event::Key).
+	pub fn keyboard_layout(&self) -> source::KeyboardLayout {
+		self.consts.read().unwrap().keyboard_layout.clone()
+	}
+
+	/// Sets the keyboard layout used to name [`physical keys`](binding::Source::PhysicalKey) in prompts.
+	/// Keys pressed afterwards still update the layout, if it turns out to be different.
+	pub fn set_keyboard_layout(&mut self, layout: source::KeyboardLayout) {
+		self.consts.write().unwrap().keyboard_layout = layout;
+	}
+
+	/// Returns which face buttons send [`VirtualConfirm`](Button::VirtualConfirm) and [`VirtualDeny`](Button::VirtualDeny).
+	pub fn confirm_buttons(&self) -> source::ConfirmButtons {
+		self.consts.read().unwrap().confirm_buttons.clone()
+	}
+
+	/// Sets which face buttons send [`VirtualConfirm`](Button::VirtualConfirm) and [`VirtualDeny`](Button::VirtualDeny),
+	/// which also changes the buttons shown in [`prompts`](crate::User::prompts) for them.
+	pub fn set_confirm_buttons(&mut self, confirm_buttons: source::ConfirmButtons) {
+		self.consts.write().unwrap().confirm_buttons = confirm_buttons;
+	}
+
+	fn apply_window_event(&mut self, event: event::WindowEvent) {
+		if let event::WindowEvent::Focused(focused) = event {
+			self.focused = focused;
+			return;
+		}
+		let mut consts = self.consts.write().unwrap();
+		match event {
+			event::WindowEvent::ResolutionChanged(width, height) => {
 				consts.screen_size = (
 					(width as f64) / consts.scale_factor,
 					(height as f64) / consts.scale_factor,
 				);
-				return;
 			}
-			event::Event::Window(event::WindowEvent::ScaleFactorChanged(
-				width,
-				height,
-				scale_factor,
-			)) => {
-				let mut consts = self.consts.write().unwrap();
+			event::WindowEvent::ScaleFactorChanged(width, height, scale_factor) => {
 				consts.scale_factor = scale_factor;
 				consts.screen_size = (
 					(width as f64) / consts.scale_factor,
 					(height as f64) / consts.scale_factor,
 				);
-				return;
 			}
-			event::Event::Input(device_source, binding_source, state) => {
-				self.send_device_event((device_source, binding_source, state));
+			event::WindowEvent::ScaleFactor(scale_factor) => {
+				let (width, height) = consts.screen_size;
+				let ratio = consts.scale_factor / scale_factor;
+				consts.scale_factor = scale_factor;
+				consts.screen_size = (width * ratio, height * ratio);
 			}
+			event::WindowEvent::Focused(_) => {}
 		}
 	}
 
-	fn send_device_event(&self, event: (device::Id, binding::Source, event::State)) {
-		for (device, binding, event) in self.parse_input_event(event) {
-			if let Some((_user, sender)) = self.assigned_devices.get(&device) {
+	fn send_device_event(&mut self, event: (device::Id, binding::Source, event::State)) {
+		if self.playback.is_some() {
+			return;
+		}
+		// Releases are still sent while unfocused, so keys and buttons held when focus was lost are not stuck down.
+		if !self.focused
+			&& matches!(event.0, device::Id::Keyboard | device::Id::Mouse)
+			&& !matches!(
+				event.2,
+				event::State::ButtonState(event::ButtonState::Released)
+			) {
+			return;
+		}
+		if let (
+			device::Id::Keyboard,
+			binding::Source::Keyboard(key),
+			event::State::ButtonState(state),
+		) = event
+		{
+			if let Some(edit_key) = text::EditKey::from_key(key) {
+				self.send_text_event(text::TextEvent::EditKey(edit_key, state));
+			}
+		}
+		let events = self.parse_input_event(event);
+		if self.join_or_leave(&events) {
+			return;
+		}
+		for (device, binding, event) in events {
+			if let Some((user, sender)) = self.assigned_devices.get(&device) {
 				let _ = sender.try_send((binding, event));
+				if let Some(recorder) = self.recorder.as_mut() {
+					if let Some(index) = self.users.iter().position(|(weak, _)| weak.ptr_eq(user)) {
+						recorder.push(recording::Entry::Input(index, binding, event));
+					}
+				}
+			}
+		}
+	}
+
+	/// Sends text to the user who is assigned the keyboard.
+	fn send_text_event(&self, event: text::TextEvent) {
+		if !self.focused {
+			return;
+		}
+		if let Some((user, _)) = self.assigned_devices.get(&device::Id::Keyboard) {
+			if let Some(arc_user) = user.upgrade() {
+				let _ = arc_user.read().unwrap().text_sender().try_send(event);
 			}
 		}
 	}
 
 	// Based on the platform and the event, we may need to split the event into multiple events.
-	// For example: the bottom and right face buttons on a gamepad may need to also trigger
+	// For example: the confirm and deny face buttons on a gamepad (see `ConfirmButtons`) also trigger
 	// `Button::VirtualConfirm` or `Button::VirtualDeny` in addition to the original button.
 	fn parse_input_event(
 		&self,
@@ -336,27 +1203,18 @@ impl DeviceCache {
 		let mut events = vec![event.clone()];
 		if let (
 			device_id,
-			binding::Source::Gamepad(kind, binding::Gamepad::Button(Button::FaceBottom)),
+			binding::Source::Gamepad(kind, binding::Gamepad::Button(button)),
 			state,
 		) = event
 		{
-			events.push((
-				device_id,
-				binding::Source::Gamepad(kind, binding::Gamepad::Button(Button::VirtualConfirm)),
-				state,
-			));
-		}
-		if let (
-			device_id,
-			binding::Source::Gamepad(kind, binding::Gamepad::Button(Button::FaceRight)),
-			state,
-		) = event
-		{
-			events.push((
-				device_id,
-				binding::Source::Gamepad(kind, binding::Gamepad::Button(Button::VirtualDeny)),
-				state,
-			));
+			let consts = self.consts.read().unwrap();
+			if let Some(virtual_button) = consts.confirm_buttons.virtual_button(kind, button) {
+				events.push((
+					device_id,
+					binding::Source::Gamepad(kind, binding::Gamepad::Button(virtual_button)),
+					state,
+				));
+			}
 		}
 		events
 	}
@@ -365,6 +1223,8 @@ impl DeviceCache {
 pub struct Consts {
 	pub(crate) screen_size: (f64, f64),
 	scale_factor: f64,
+	pub(crate) keyboard_layout: source::KeyboardLayout,
+	pub(crate) confirm_buttons: source::ConfirmButtons,
 }
 
 impl Default for Consts {
@@ -372,6 +1232,497 @@ impl Default for Consts {
 		Self {
 			screen_size: (0.0, 0.0),
 			scale_factor: 1.0,
+			keyboard_layout: source::KeyboardLayout::default(),
+			confirm_buttons: source::ConfirmButtons::default(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{binding::ActionMap, User};
+
+	fn config() -> ArcLockConfig {
+		Arc::new(RwLock::new(
+			Config::default()
+				.add_action("jump", source::Kind::Button)
+				.add_layout(LayoutId::default())
+				.add_action_set(
+					ActionSetId::default(),
+					ActionSet::default().with(
+						LayoutId::default(),
+						ActionMap::default().bind("jump", binding::Source::Keyboard(Key::Space)),
+					),
+				),
+		))
+	}
+
+	fn key(state: event::ButtonState) -> event::Event {
+		event::Event::Input(
+			device::Id::Keyboard,
+			binding::Source::Keyboard(Key::Space),
+			event::State::ButtonState(state),
+		)
+	}
+
+	#[test]
+	fn layouts_resolve_through_their_parents() {
+		let config = Config::default()
+			.add_layout(None)
+			.add_layout_with_parent(Some("left_handed"), None)
+			.add_layout_with_parent(Some("left_handed_arcade"), Some("left_handed"));
+		assert_eq!(
+			config.layout_chain(Some("left_handed_arcade")),
+			vec![Some("left_handed_arcade"), Some("left_handed"), None]
+		);
+		assert_eq!(config.layout_chain(None), vec![None]);
+		assert!(config.validate().is_ok());
+	}
+
+	#[test]
+	fn validate_rejects_bad_layout_parents() {
+		let config = Config::default()
+			.add_layout_with_parent(Some("a"), Some("b"))
+			.add_layout_with_parent(Some("b"), Some("a"));
+		assert_eq!(config.layout_chain(Some("a")), vec![Some("a"), Some("b")]);
+		assert!(matches!(config.validate(), Err(Error::LayoutCycle(_))));
+
+		let config = Config::default().add_layout_with_parent(Some("a"), Some("missing"));
+		assert!(matches!(
+			config.validate(),
+			Err(Error::UnknownLayout(Some("missing")))
+		));
+	}
+
+	#[test]
+	fn confirm_buttons_route_input_and_prompts() {
+		let confirm = binding::Source::Gamepad(
+			GamepadKind::Joycon,
+			binding::Gamepad::Button(Button::VirtualConfirm),
+		);
+		let config = Arc::new(RwLock::new(
+			Config::default()
+				.add_action("confirm", source::Kind::Button)
+				.add_layout(LayoutId::default())
+				.add_action_set(
+					ActionSetId::default(),
+					ActionSet::default().with(
+						LayoutId::default(),
+						ActionMap::default().bind("confirm", confirm),
+					),
+				),
+		));
+		let mut cache =
+			DeviceCache::with_gamepad_backend(device::GamepadBackend::External).unwrap();
+		let mut user = User::new("player".to_owned())
+			.with_config(Arc::downgrade(&config))
+			.with_consts(cache.consts());
+		user.enable_action_set(ActionSetId::default());
+		let user = user.arclocked();
+		cache.add_user(Arc::downgrade(&user));
+		let joycon = device::Id::Gamepad(GamepadKind::Joycon, 0);
+		cache.send_event(event::Event::DeviceConnected(joycon));
+		cache.update();
+
+		let press = |cache: &mut DeviceCache, button| {
+			cache.send_event(event::Event::Input(
+				joycon,
+				binding::Source::Gamepad(GamepadKind::Joycon, binding::Gamepad::Button(button)),
+				event::State::ButtonState(event::ButtonState::Pressed),
+			));
+			let mut user = user.write().unwrap();
+			user.update(&std::time::Instant::now());
+			let state = user.get_action("confirm").unwrap().upgrade().unwrap();
+			let down = state.read().unwrap().is_button_down();
+			down
+		};
+		assert!(!press(&mut cache, Button::FaceBottom));
+		assert!(press(&mut cache, Button::FaceRight));
+		let prompts = user
+			.read()
+			.unwrap()
+			.prompts("confirm", &crate::prompt::GlyphSet::default());
+		assert_eq!(prompts[0].name, "A");
+
+		cache.set_confirm_buttons(
+			source::ConfirmButtons::default().with_confirm_on_right(GamepadKind::Joycon, false),
+		);
+		let prompts = user
+			.read()
+			.unwrap()
+			.prompts("confirm", &crate::prompt::GlyphSet::default());
+		assert_eq!(prompts[0].name, "B");
+	}
+
+	fn gamepad(index: usize) -> device::Id {
+		device::Id::Gamepad(GamepadKind::DualAxisGamepad, index)
+	}
+
+	fn gamepad_button(button: Button) -> binding::Source {
+		binding::Source::Gamepad(
+			GamepadKind::DualAxisGamepad,
+			binding::Gamepad::Button(button),
+		)
+	}
+
+	fn press(cache: &mut DeviceCache, device: device::Id, source: binding::Source) {
+		cache.send_event(event::Event::Input(
+			device,
+			source,
+			event::State::ButtonState(event::ButtonState::Pressed),
+		));
+	}
+
+	/// A cache in join mode, where Start joins, Select leaves, and new users are made from the config.
+	fn joining_cache(config: &ArcLockConfig, max_users: Option<usize>) -> DeviceCache {
+		let weak_config = Arc::downgrade(config);
+		let mut join = join::Join::default()
+			.with_join_source(gamepad_button(Button::RSpecial))
+			.with_join_source(binding::Source::Keyboard(Key::Return))
+			.with_leave_source(gamepad_button(Button::LSpecial))
+			.with_new_users(move |_| {
+				let mut user = User::new("player".to_owned()).with_config(weak_config.clone());
+				user.enable_action_set(ActionSetId::default());
+				user
+			});
+		if let Some(max_users) = max_users {
+			join = join.with_max_users(max_users);
+		}
+		DeviceCache::with_gamepad_backend(device::GamepadBackend::External)
+			.unwrap()
+			.with_join(join)
+	}
+
+	#[test]
+	fn devices_join_by_pressing_a_join_source() {
+		let config = config();
+		let mut cache = joining_cache(&config, None);
+		let joins = cache.subscribe_to_joins();
+		for index in 0..2 {
+			cache.send_event(event::Event::DeviceConnected(gamepad(index)));
 		}
+		cache.update();
+		assert!(cache.users().is_empty());
+
+		press(&mut cache, gamepad(1), gamepad_button(Button::FaceBottom));
+		press(&mut cache, gamepad(1), gamepad_button(Button::RSpecial));
+		press(&mut cache, gamepad(0), gamepad_button(Button::RSpecial));
+		press(
+			&mut cache,
+			device::Id::Keyboard,
+			binding::Source::Keyboard(Key::Return),
+		);
+		assert_eq!(
+			joins.try_iter().collect::<Vec<_>>(),
+			vec![
+				join::Event::Joined(0, gamepad(1)),
+				join::Event::Joined(1, gamepad(0)),
+				join::Event::Joined(2, device::Id::Keyboard),
+			]
+		);
+		assert_eq!(cache.devices_of(0), &[gamepad(1)]);
+		assert_eq!(
+			cache.devices_of(2),
+			&[device::Id::Keyboard, device::Id::Mouse]
+		);
+	}
+
+	#[test]
+	fn leaving_frees_a_slot_for_the_next_player() {
+		let config = config();
+		let mut cache = joining_cache(&config, Some(1));
+		let joins = cache.subscribe_to_joins();
+		for index in 0..2 {
+			cache.send_event(event::Event::DeviceConnected(gamepad(index)));
+		}
+		press(&mut cache, gamepad(0), gamepad_button(Button::RSpecial));
+		press(&mut cache, gamepad(1), gamepad_button(Button::RSpecial));
+		press(&mut cache, gamepad(0), gamepad_button(Button::LSpecial));
+		press(&mut cache, gamepad(1), gamepad_button(Button::RSpecial));
+		assert_eq!(
+			joins.try_iter().collect::<Vec<_>>(),
+			vec![
+				join::Event::Joined(0, gamepad(0)),
+				join::Event::Rejected(gamepad(1)),
+				join::Event::Left(0),
+				join::Event::Joined(0, gamepad(1)),
+			]
+		);
+		assert_eq!(cache.users().len(), 1);
+
+		cache.set_joining(false);
+		press(&mut cache, gamepad(0), gamepad_button(Button::RSpecial));
+		assert!(joins.try_iter().next().is_none());
+	}
+
+	#[test]
+	fn playback_updates_users_once_per_frame() {
+		let config = config();
+		let mut cache =
+			DeviceCache::with_gamepad_backend(device::GamepadBackend::External).unwrap();
+		let mut user = User::new("player".to_owned()).with_config(Arc::downgrade(&config));
+		user.enable_action_set(ActionSetId::default());
+		let user = user.arclocked();
+		cache.add_user(Arc::downgrade(&user));
+		cache.update();
+
+		cache.start_recording();
+		for state in [
+			event::ButtonState::Pressed,
+			event::ButtonState::Released,
+			event::ButtonState::Pressed,
+			event::ButtonState::Released,
+		] {
+			cache.send_event(key(state));
+			cache.update();
+			user.write().unwrap().update(&std::time::Instant::now());
+		}
+		let recording = cache.stop_recording().unwrap();
+
+		let events = user.write().unwrap().subscribe_to("jump");
+		cache.start_playback(recording);
+		cache.playback_mut().unwrap().set_speed(4.0);
+		cache.update();
+		// The application's update is ignored, so it can't swallow the edges of the last frame.
+		user.write().unwrap().update(&std::time::Instant::now());
+
+		let events = events
+			.try_iter()
+			.map(|(_, event)| event)
+			.collect::<Vec<_>>();
+		let started = events
+			.iter()
+			.filter(|event| matches!(event, action::Event::Started))
+			.count();
+		let performed = events
+			.iter()
+			.filter(|event| matches!(event, action::Event::Performed))
+			.count();
+		assert_eq!((started, performed), (2, 2));
+		assert!(cache.playback().unwrap().is_finished());
+	}
+
+	/// A cache with the keyboard, mouse, and `gamepads` connected, and `count` users.
+	fn cache_with_users(
+		config: &ArcLockConfig,
+		policy: device::AssignmentPolicy,
+		gamepads: usize,
+		count: usize,
+	) -> (DeviceCache, Vec<ArcLockUser>) {
+		let mut cache = DeviceCache::with_gamepad_backend(device::GamepadBackend::External)
+			.unwrap()
+			.with_assignment_policy(policy);
+		let users = (0..count)
+			.map(|_| {
+				let mut user = User::new("player".to_owned())
+					.with_config(Arc::downgrade(config))
+					.with_consts(cache.consts());
+				user.enable_action_set(ActionSetId::default());
+				let user = user.arclocked();
+				cache.add_user(Arc::downgrade(&user));
+				user
+			})
+			.collect::<Vec<_>>();
+		for index in 0..gamepads {
+			cache.send_event(event::Event::DeviceConnected(gamepad(index)));
+		}
+		cache.update();
+		(cache, users)
+	}
+
+	#[test]
+	fn first_free_gives_each_user_a_gamepad() {
+		let config = config();
+		let (cache, _users) = cache_with_users(&config, device::AssignmentPolicy::FirstFree, 3, 2);
+		assert_eq!(
+			cache.devices_of(0),
+			&[device::Id::Mouse, device::Id::Keyboard, gamepad(0)]
+		);
+		assert_eq!(cache.devices_of(1), &[gamepad(1)]);
+		assert!(cache.device_info(gamepad(2)).unwrap().user.is_none());
+	}
+
+	#[test]
+	fn round_robin_takes_turns_and_keeps_the_keyboard_with_the_mouse() {
+		let config = config();
+		let (cache, _users) = cache_with_users(&config, device::AssignmentPolicy::RoundRobin, 3, 2);
+		assert_eq!(
+			cache.devices_of(0),
+			&[device::Id::Mouse, device::Id::Keyboard, gamepad(1)]
+		);
+		assert_eq!(cache.devices_of(1), &[gamepad(0), gamepad(2)]);
+	}
+
+	#[test]
+	fn single_user_gets_every_device() {
+		let config = config();
+		let (cache, _users) = cache_with_users(&config, device::AssignmentPolicy::SingleUser, 2, 2);
+		assert_eq!(cache.devices_of(0).len(), 4);
+		assert!(cache.devices_of(1).is_empty());
+	}
+
+	#[test]
+	fn manual_assignment_moves_devices_between_users() {
+		let config = config();
+		let (mut cache, users) = cache_with_users(&config, device::AssignmentPolicy::Manual, 1, 2);
+		assert!(cache.devices_of(0).is_empty());
+		assert!(matches!(
+			cache.assign(device::Id::Keyboard, 2),
+			Err(Error::UnknownUser(2))
+		));
+
+		cache.assign(device::Id::Keyboard, 1).unwrap();
+		cache.assign(gamepad(0), 1).unwrap();
+		let handle = users[1].read().unwrap().get_action("jump").unwrap();
+		cache.send_event(key(event::ButtonState::Pressed));
+		users[1].write().unwrap().update(&std::time::Instant::now());
+		assert!(handle.upgrade().unwrap().read().unwrap().is_button_down());
+
+		// Moving the keyboard releases the actions it held, without replacing the user's action states.
+		cache.assign(device::Id::Keyboard, 0).unwrap();
+		users[1].write().unwrap().update(&std::time::Instant::now());
+		assert!(!handle.upgrade().unwrap().read().unwrap().is_button_down());
+		assert_eq!(cache.devices_of(0), &[device::Id::Keyboard]);
+		assert_eq!(cache.devices_of(1), &[gamepad(0)]);
+
+		assert_eq!(cache.unassign(gamepad(0)), Some(1));
+		assert!(cache.devices_of(1).is_empty());
+		cache.update();
+		assert!(cache.devices_of(1).is_empty());
+	}
+
+	#[test]
+	fn user_ids_stay_the_same_when_an_earlier_user_is_dropped() {
+		let config = config();
+		let (mut cache, mut users) =
+			cache_with_users(&config, device::AssignmentPolicy::FirstFree, 2, 2);
+		let devices = cache.subscribe_to_devices();
+		users.remove(0);
+		cache.update();
+
+		assert_eq!(cache.users().len(), 2);
+		assert_eq!(cache.device_info(gamepad(1)).unwrap().user, Some(1));
+		let unassigned = devices
+			.try_iter()
+			.filter(|event| matches!(event, device::Event::Unassigned(_, _)))
+			.collect::<Vec<_>>();
+		assert_eq!(unassigned.len(), 3);
+		assert!(unassigned
+			.iter()
+			.all(|event| matches!(event, device::Event::Unassigned(_, 0))));
+		// The keyboard and mouse move to the remaining user, who already has a gamepad.
+		assert_eq!(
+			cache.devices_of(1),
+			&[gamepad(1), device::Id::Mouse, device::Id::Keyboard]
+		);
+		cache.assign(gamepad(0), 1).unwrap();
+		assert_eq!(cache.devices_of(1)[3], gamepad(0));
+		assert!(matches!(
+			cache.assign(gamepad(0), 0),
+			Err(Error::UnknownUser(0))
+		));
+	}
+
+	/// Two users who were each given a gamepad of the same model, with the serials `a` and `b` when `serials` is set.
+	fn cache_with_gamepads(
+		config: &ArcLockConfig,
+		serials: bool,
+	) -> (DeviceCache, Vec<ArcLockUser>) {
+		let (mut cache, users) =
+			cache_with_users(config, device::AssignmentPolicy::FirstFree, 0, 2);
+		for (index, serial) in ["a", "b"].iter().enumerate() {
+			connect(&mut cache, gamepad(index), serials.then_some(*serial));
+		}
+		cache.update();
+		(cache, users)
+	}
+
+	fn connect(cache: &mut DeviceCache, device_id: device::Id, serial: Option<&str>) {
+		let identity = device::Identity::new([1; 16]);
+		let identity = match serial {
+			Some(serial) => identity.with_serial(serial.to_owned()),
+			None => identity,
+		};
+		cache.identify_device(device_id, identity);
+		cache.send_event(event::Event::DeviceConnected(device_id));
+	}
+
+	#[test]
+	fn reconnected_gamepads_return_to_the_user_with_the_same_serial() {
+		let config = config();
+		let (mut cache, _users) = cache_with_gamepads(&config, true);
+		let devices = cache.subscribe_to_devices();
+		cache.send_event(event::Event::DeviceDisconnected(gamepad(0)));
+		cache.send_event(event::Event::DeviceDisconnected(gamepad(1)));
+		connect(&mut cache, gamepad(2), Some("a"));
+		// A different gamepad may be given the id of a disconnected one.
+		connect(&mut cache, gamepad(1), Some("c"));
+		assert_eq!(
+			devices.try_iter().skip(2).collect::<Vec<_>>(),
+			vec![
+				device::Event::Reconnected(gamepad(2), 0),
+				device::Event::Connected(gamepad(1)),
+			]
+		);
+		connect(&mut cache, gamepad(3), Some("b"));
+		assert_eq!(
+			devices.try_iter().collect::<Vec<_>>(),
+			vec![device::Event::Reconnected(gamepad(3), 1)]
+		);
+		assert_eq!(
+			cache
+				.device_info(gamepad(1))
+				.unwrap()
+				.identity
+				.unwrap()
+				.serial(),
+			Some("c")
+		);
+	}
+
+	#[test]
+	fn gamepads_without_serials_return_to_the_most_recent_user() {
+		let config = config();
+		let (mut cache, _users) = cache_with_gamepads(&config, false);
+		let devices = cache.subscribe_to_devices();
+		cache.send_event(event::Event::DeviceDisconnected(gamepad(0)));
+		cache.send_event(event::Event::DeviceDisconnected(gamepad(1)));
+		connect(&mut cache, gamepad(2), None);
+		connect(&mut cache, gamepad(3), None);
+		assert_eq!(
+			devices.try_iter().skip(2).collect::<Vec<_>>(),
+			vec![
+				device::Event::Reconnected(gamepad(2), 1),
+				device::Event::Reconnected(gamepad(3), 0),
+			]
+		);
+	}
+
+	#[test]
+	fn devices_and_focus_change_during_playback() {
+		let config = config();
+		let (mut cache, users) =
+			cache_with_users(&config, device::AssignmentPolicy::FirstFree, 1, 1);
+		let devices = cache.subscribe_to_devices();
+		cache.start_recording();
+		cache.update();
+		let recording = cache.stop_recording().unwrap();
+		cache.start_playback(recording);
+
+		cache.send_event(event::Event::DeviceDisconnected(gamepad(0)));
+		cache.send_event(event::Event::Window(event::WindowEvent::Focused(false)));
+		cache.send_event(key(event::ButtonState::Pressed));
+		assert_eq!(
+			devices.try_iter().collect::<Vec<_>>(),
+			vec![device::Event::Disconnected(gamepad(0), Some(0))]
+		);
+
+		cache.stop_playback();
+		cache.send_event(key(event::ButtonState::Pressed));
+		let mut user = users[0].write().unwrap();
+		user.update(&std::time::Instant::now());
+		let state = user.get_action("jump").unwrap().upgrade().unwrap();
+		assert!(!state.read().unwrap().is_button_down());
 	}
 }
@@ -0,0 +1,102 @@
+use crate::{binding, event::ButtonState, source::Key};
+use crossbeam_channel::{Receiver, Sender};
+use std::time::Duration;
+
+/// Text entered by a user while [`text input`](crate::User::start_text_input) is active.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextInput {
+	/// A character was typed (already resolved against the keyboard layout and modifiers by the OS).
+	Char(char),
+	/// An input method is composing text which has not been committed yet.
+	/// The cursor is the byte range of the selection in the text, if the IME provides one.
+	Preedit {
+		text: String,
+		cursor: Option<(usize, usize)>,
+	},
+	/// An input method committed text, which should be inserted (replacing any preedit text).
+	Commit(String),
+	/// An editing key was pressed (or repeated while held).
+	Edit(EditKey),
+}
+
+/// The keys used to edit text, as opposed to typing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EditKey {
+	Backspace,
+	Delete,
+	Left,
+	Right,
+	Up,
+	Down,
+	Home,
+	End,
+	Enter,
+	Tab,
+	Escape,
+}
+
+impl EditKey {
+	pub fn from_key(key: Key) -> Option<Self> {
+		match key {
+			Key::Back => Some(Self::Backspace),
+			Key::Delete => Some(Self::Delete),
+			Key::Left => Some(Self::Left),
+			Key::Right => Some(Self::Right),
+			Key::Up => Some(Self::Up),
+			Key::Down => Some(Self::Down),
+			Key::Home => Some(Self::Home),
+			Key::End => Some(Self::End),
+			Key::Return | Key::NumpadEnter => Some(Self::Enter),
+			Key::Tab => Some(Self::Tab),
+			Key::Escape => Some(Self::Escape),
+			_ => None,
+		}
+	}
+
+	/// Returns true if the edit key should repeat while held.
+	pub fn repeats(&self) -> bool {
+		!matches!(self, Self::Enter | Self::Tab | Self::Escape)
+	}
+}
+
+pub type TextSender = Sender<TextInput>;
+pub type TextReceiver = Receiver<TextInput>;
+
+/// What is sent from the [`DeviceCache`](crate::DeviceCache) to the user who owns the keyboard.
+/// Edit keys are sent with their state (instead of as [`TextInput::Edit`]) so the user can repeat them while held.
+#[derive(Debug, Clone)]
+pub(crate) enum TextEvent {
+	Input(TextInput),
+	EditKey(EditKey, ButtonState),
+}
+
+pub(crate) type TextEventSender = Sender<TextEvent>;
+pub(crate) type TextEventReceiver = Receiver<TextEvent>;
+
+/// How long an edit key must be held before it repeats, and how often it repeats afterwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyRepeat {
+	pub delay: Duration,
+	pub interval: Duration,
+}
+
+impl Default for KeyRepeat {
+	fn default() -> Self {
+		Self {
+			delay: Duration::from_millis(500),
+			interval: Duration::from_millis(33),
+		}
+	}
+}
+
+/// Returns true if a keyboard source types or edits text, so it should not trigger actions while text input is active.
+pub(crate) fn is_text_key(source: &binding::Source) -> bool {
+	match source {
+		binding::Source::Keyboard(key) | binding::Source::PhysicalKey(key) => {
+			EditKey::from_key(*key).is_some() || key.to_string(&enumset::EnumSet::empty()).is_some()
+		}
+		_ => false,
+	}
+}
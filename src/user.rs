@@ -1,12 +1,15 @@
 use crate::{
-	action, binding,
+	action, binding, device,
 	event::{self, InputReceiver, InputSender},
-	Consts, WeakLockConfig,
+	profile::{Migration, Profile},
+	prompt::{GlyphSet, Prompt},
+	snapshot::Snapshot,
+	source, text, Consts, Error, Settings, WeakLockConfig,
 };
 use std::{
 	collections::{HashMap, HashSet},
 	sync::{Arc, RwLock, Weak},
-	time::Instant,
+	time::{Duration, Instant},
 };
 
 pub type ArcLockUser = Arc<RwLock<User>>;
@@ -16,44 +19,88 @@ pub struct User {
 	consts: Weak<RwLock<Consts>>,
 
 	name: String,
+	settings: Settings,
+	/// Sources the user has swapped in their bindings, as `(original, replacement)` pairs per action,
+	/// where each original is a source in the config (so each appears at most once per action).
+	rebinds: HashMap<action::Id, Vec<(binding::Source, binding::Source)>>,
 
 	active_layout: binding::LayoutId,
+	/// The layout to switch to when the user starts using each kind of device.
+	device_layouts: HashMap<device::Kind, binding::LayoutId>,
+	device_activity_threshold: f64,
+	mouse_activity_threshold: f64,
+	last_device: Option<device::Kind>,
+	device_observers: Vec<crossbeam_channel::Sender<device::Kind>>,
 	enabled_action_sets: HashMap<binding::ActionSetId, binding::ActionSet>,
-	bound_actions: HashMap<BindingStateKey, action::Id>,
+	bound_actions: HashMap<action::Id, ActionBinding>,
+	/// The actions bound to each source, derived from `bound_actions`, so events don't need to scan every binding.
+	source_actions: HashMap<binding::Source, Vec<action::Id>>,
 	action_states: HashMap<action::Id, action::ArcLockState>,
 	ticking_states: HashSet<action::Id>,
 
 	input_receiver: InputReceiver,
 	input_sender: InputSender,
+
+	text_receiver: text::TextEventReceiver,
+	text_sender: text::TextEventSender,
+	/// Where text is sent while text input is active.
+	text_output: Option<text::TextSender>,
+	/// Set when text input starts, so held keyboard actions are released on the next update.
+	release_text_keys: bool,
+	/// Sources to release for an action on the next update, because the source was unbound or its device was taken.
+	pending_releases: Vec<(action::Id, binding::Source)>,
+	key_repeat: text::KeyRepeat,
+	/// The edit key being held, when it was pressed, and how many times it has repeated.
+	held_edit_key: Option<(text::EditKey, action::Time, u32)>,
+
+	observers: Vec<action::Observer>,
+	published: action::SnapshotHandle,
+	/// Set while the [`DeviceCache`](crate::DeviceCache) is playing back a recording,
+	/// during which it updates the user once per recorded frame (instead of the application updating the user).
+	playback_active: bool,
 }
 
+/// Where the binding of an active action came from, and the sources it is bound to.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct BindingStateKey {
+struct ActionBinding {
 	set_id: binding::ActionSetId,
 	layout: binding::LayoutId,
 	sources: Vec<binding::Source>,
 }
 
-impl BindingStateKey {
-	fn contains(&self, source: binding::Source) -> bool {
-		self.sources.contains(&source)
-	}
-}
-
 impl User {
 	pub fn new(name: String) -> Self {
 		let (input_sender, input_receiver) = crossbeam_channel::unbounded();
+		let (text_sender, text_receiver) = crossbeam_channel::unbounded();
 		Self {
 			config: Weak::new(),
 			consts: Weak::new(),
 			name,
+			settings: Settings::default(),
+			rebinds: HashMap::new(),
 			active_layout: binding::LayoutId::default(),
+			device_layouts: HashMap::new(),
+			device_activity_threshold: 0.25,
+			mouse_activity_threshold: 4.0,
+			last_device: None,
+			device_observers: Vec::new(),
 			enabled_action_sets: HashMap::new(),
 			bound_actions: HashMap::new(),
+			source_actions: HashMap::new(),
 			action_states: HashMap::new(),
 			ticking_states: HashSet::new(),
 			input_receiver,
 			input_sender,
+			text_receiver,
+			text_sender,
+			text_output: None,
+			release_text_keys: false,
+			pending_releases: Vec::new(),
+			key_repeat: text::KeyRepeat::default(),
+			held_edit_key: None,
+			observers: Vec::new(),
+			published: action::SnapshotHandle::default(),
+			playback_active: false,
 		}
 	}
 
@@ -75,35 +122,367 @@ impl User {
 		&self.name
 	}
 
+	pub fn with_settings(mut self, settings: Settings) -> Self {
+		self.settings = settings;
+		self
+	}
+
+	/// The settings referenced by name in this user's behaviors (such as look sensitivity).
+	pub fn settings(&self) -> &Settings {
+		&self.settings
+	}
+
+	/// Changes to settings take effect for the next input event, without needing to re-enable action sets.
+	pub fn settings_mut(&mut self) -> &mut Settings {
+		&mut self.settings
+	}
+
+	/// Replaces a source in the bindings of an action for this user only, keeping any behaviors of the binding.
+	/// `from` is always the source in the [`Config`](crate::Config)'s action sets (even if it was rebound before),
+	/// so rebinding `W` to `S` and `S` to `W` swaps the two keys. Rebinding a source to itself restores it.
+	pub fn rebind(&mut self, action: action::Id, from: binding::Source, to: binding::Source) {
+		let rebinds = self.rebinds.entry(action).or_default();
+		rebinds.retain(|(original, _)| *original != from);
+		if from != to {
+			rebinds.push((from, to));
+		}
+		if rebinds.is_empty() {
+			self.rebinds.remove(action);
+		}
+		self.rebuild_action_states();
+	}
+
+	/// Restores the bindings of an action to those of the [`Config`](crate::Config).
+	pub fn clear_rebinds(&mut self, action: action::Id) {
+		if self.rebinds.remove(&action).is_some() {
+			self.rebuild_action_states();
+		}
+	}
+
+	pub fn rebinds(&self) -> &HashMap<action::Id, Vec<(binding::Source, binding::Source)>> {
+		&self.rebinds
+	}
+
+	/// Captures the user's rebinds, layout, and settings so they can be saved.
+	pub fn profile(&self) -> Profile {
+		Profile {
+			version: self
+				.config
+				.upgrade()
+				.map(|config| config.read().unwrap().version())
+				.unwrap_or_default(),
+			layout: self.active_layout.map(|layout| layout.to_owned()),
+			rebinds: self
+				.rebinds
+				.iter()
+				.map(|(id, rebinds)| (id.to_string(), rebinds.clone()))
+				.collect(),
+			settings: self.settings.clone(),
+		}
+	}
+
+	/// Replaces the user's rebinds, layout, and settings with those of a profile.
+	/// Actions and layouts which are no longer in the [`Config`](crate::Config) are skipped
+	/// instead of failing, and are reported in the returned [`Migration`].
+	pub fn apply_profile(&mut self, profile: &Profile) -> Result<Migration, Error> {
+		let arc_config = self.config.upgrade().ok_or(Error::ConfigDropped)?;
+		let config = arc_config.read().unwrap();
+		let mut migration = Migration {
+			from_version: profile.version,
+			to_version: config.version(),
+			..Default::default()
+		};
+
+		self.rebinds.clear();
+		for (name, rebinds) in profile.rebinds.iter() {
+			match config.find_action(name) {
+				Some(id) => {
+					self.rebinds.insert(id, rebinds.clone());
+				}
+				None => migration.dropped_actions.push(name.clone()),
+			}
+		}
+		migration.dropped_actions.sort();
+
+		match config.find_layout(profile.layout.as_deref()) {
+			Some(layout) => self.active_layout = layout,
+			None => migration.dropped_layout = profile.layout.clone(),
+		}
+		drop(config);
+
+		self.settings = profile.settings.clone();
+		self.rebuild_action_states();
+		Ok(migration)
+	}
+
 	pub(crate) fn input_sender(&self) -> &event::InputSender {
 		&self.input_sender
 	}
 
+	pub(crate) fn text_sender(&self) -> &text::TextEventSender {
+		&self.text_sender
+	}
+
+	/// Sets how long edit keys (like backspace) must be held before they repeat, and how often they repeat.
+	pub fn with_key_repeat(mut self, key_repeat: text::KeyRepeat) -> Self {
+		self.key_repeat = key_repeat;
+		self
+	}
+
+	/// Starts sending the text typed by the user (via the keyboard assigned to them) to the returned receiver,
+	/// such as when a chat box gains focus. While active, keyboard actions bound to keys which type or edit text
+	/// are suspended, and any which are held are released on the next update.
+	/// Calling this again replaces the previous receiver.
+	pub fn start_text_input(&mut self) -> text::TextReceiver {
+		// Text typed before the input was focused is not meant for it.
+		while self.text_receiver.try_recv().is_ok() {}
+		let (sender, receiver) = crossbeam_channel::unbounded();
+		self.text_output = Some(sender);
+		self.release_text_keys = true;
+		self.held_edit_key = None;
+		receiver
+	}
+
+	/// Stops sending text, and resumes keyboard actions.
+	/// Text input also stops when the receiver returned by [`start_text_input`](User::start_text_input) is dropped.
+	pub fn stop_text_input(&mut self) {
+		self.text_output = None;
+		self.release_text_keys = false;
+		self.held_edit_key = None;
+	}
+
+	pub fn is_text_input_active(&self) -> bool {
+		self.text_output.is_some()
+	}
+
 	/// Sets the layout of a user.
 	/// If not called, all user's start with a `None` layout (default layout).
+	/// Actions bound in both the previous and new layout keep their state (so held buttons stay held).
 	pub fn set_layout(&mut self, layout: binding::LayoutId) {
+		if self.active_layout == layout {
+			return;
+		}
 		self.active_layout = layout;
+		self.rebuild_action_states();
+	}
+
+	pub fn layout(&self) -> binding::LayoutId {
+		self.active_layout
+	}
+
+	/// Automatically [`sets the layout`](User::set_layout) whenever the user switches to a device of the provided kind.
+	pub fn with_device_layout(mut self, kind: device::Kind, layout: binding::LayoutId) -> Self {
+		self.device_layouts.insert(kind, layout);
+		self
+	}
+
+	/// Sets how far an axis (like a thumbstick) must move before its device is considered the [`last used`](User::last_device).
+	/// Defaults to 0.25, so that stick drift on an idle gamepad does not switch devices.
+	pub fn with_device_activity_threshold(mut self, threshold: f64) -> Self {
+		self.device_activity_threshold = threshold;
+		self
+	}
+
+	/// Sets how many pixels the mouse must move in one update before it is considered the [`last used`](User::last_device) device.
+	/// Defaults to 4, so that bumping the desk does not switch away from a gamepad.
+	pub fn with_mouse_activity_threshold(mut self, threshold: f64) -> Self {
+		self.mouse_activity_threshold = threshold;
+		self
+	}
+
+	/// Returns the kind of device which most recently sent meaningful input to this user.
+	/// A keyboard and mouse count as one device, so this stays as whichever of the two was used first
+	/// until the user switches to a gamepad.
+	pub fn last_device(&self) -> Option<device::Kind> {
+		self.last_device
+	}
+
+	/// Returns the sources currently bound to an action (for the active layout and enabled action sets),
+	/// including any the user has [`rebound`](User::rebind).
+	pub fn bound_sources(&self, action: action::Id) -> Vec<binding::Source> {
+		let mut sources = Vec::new();
+		for set_id in self.enabled_action_sets.keys() {
+			if let Some(binding) = self.resolve_bindings(set_id).get(action) {
+				for source in binding.sources().into_iter() {
+					if !sources.contains(&source) {
+						sources.push(source);
+					}
+				}
+			}
+		}
+		sources
+	}
+
+	/// Returns the sources bound to an action which belong to the [`last used device`](User::last_device)
+	/// (where a keyboard and mouse count as one device), or all bound sources if the user has not sent any input yet.
+	pub fn prompt_sources(&self, action: action::Id) -> Vec<binding::Source> {
+		let mut sources = self.bound_sources(action);
+		if let Some(kind) = self.last_device {
+			sources.retain(|source| source.device_kind().is_used_with(&kind));
+		}
+		sources
+	}
+
+	/// Returns the [`prompts`](Prompt) to show for an action, based on the [`prompt sources`](User::prompt_sources).
+	/// [`Physical keys`](binding::Source::PhysicalKey) are named using the [`keyboard layout`](crate::DeviceCache::keyboard_layout),
+	/// and virtual buttons by the face button which sends them (see [`confirm buttons`](crate::DeviceCache::confirm_buttons)).
+	pub fn prompts(&self, action: action::Id, glyphs: &GlyphSet) -> Vec<Prompt> {
+		let (layout, confirm_buttons) = self
+			.consts
+			.upgrade()
+			.map(|consts| {
+				let consts = consts.read().unwrap();
+				(
+					consts.keyboard_layout.clone(),
+					consts.confirm_buttons.clone(),
+				)
+			})
+			.unwrap_or_default();
+		self.prompt_sources(action)
+			.into_iter()
+			.map(|source| glyphs.prompt_with(source, &layout, &confirm_buttons))
+			.collect()
+	}
+
+	/// Returns a channel which receives the kind of device the user switched to, whenever [`last_device`](User::last_device) changes.
+	/// Dropping the receiver unsubscribes it.
+	pub fn subscribe_to_device_changes(&mut self) -> crossbeam_channel::Receiver<device::Kind> {
+		let (sender, receiver) = crossbeam_channel::unbounded();
+		self.device_observers.push(sender);
+		receiver
+	}
+
+	/// Records the device which sent an input if the input is meaningful (a press, or mouse motion
+	/// or an axis beyond their activity threshold), switching layouts if the user switched devices.
+	fn track_device(&mut self, source: binding::Source, state: &event::State) {
+		let is_active = match state {
+			event::State::ButtonState(button) => *button == event::ButtonState::Pressed,
+			event::State::MouseMove(delta) => delta.abs() >= self.mouse_activity_threshold,
+			event::State::MouseScroll(delta) => *delta != 0.0,
+			event::State::ValueChanged(value) => {
+				(*value as f64).abs() >= self.device_activity_threshold
+			}
+		};
+		let kind = source.device_kind();
+		let is_same_device = self
+			.last_device
+			.is_some_and(|last| last.is_used_with(&kind));
+		if !is_active || is_same_device {
+			return;
+		}
+		self.last_device = Some(kind);
+		self.device_observers
+			.retain(|sender| sender.send(kind).is_ok());
+		let layout = self.device_layouts.get(&kind).or_else(|| {
+			self.device_layouts
+				.iter()
+				.find(|(layout_kind, _)| layout_kind.is_used_with(&kind))
+				.map(|(_, layout)| layout)
+		});
+		if let Some(layout) = layout.cloned() {
+			self.set_layout(layout);
+		}
+	}
+
+	/// Discards the state of all actions as if they had never received input,
+	/// keeping the actions themselves (so any handles to them remain valid).
+	pub(crate) fn reset_action_states(&mut self) {
+		let action_ids = self.action_states.keys().cloned().collect::<Vec<_>>();
+		self.cancel_actions(action_ids);
+		self.pending_releases.clear();
+		for arc_state in self.action_states.values() {
+			arc_state.write().unwrap().reset();
+		}
+	}
+
+	/// Releases the actions bound to sources of a kind of device on the next update,
+	/// such as when the device is taken from the user, so inputs held on it do not leave actions stuck down.
+	/// Actions bound to the user's other devices are unaffected.
+	pub(crate) fn release_device(&mut self, kind: device::Kind) {
+		for (source, action_ids) in self.source_actions.iter() {
+			if source.device_kind() == kind {
+				for action_id in action_ids.iter() {
+					self.pending_releases.push((*action_id, *source));
+				}
+			}
+		}
+	}
+
+	/// Enables a provided [`action set`](binding::ActionSet) for a given user.
+	/// When enabled, a user will receive input events for the actions in the [`action set`](binding::ActionSet),
+	/// until the set is disabled (or until [`crate::DeviceCache::update`] stops being called).
+	pub fn enable_action_set(&mut self, id: binding::ActionSetId) {
+		let _ = self.try_enable_action_set(id);
+	}
+
+	/// Enables a provided [`action set`](binding::ActionSet) for a given user,
+	/// failing if the config has been dropped or does not contain the action set.
+	pub fn try_enable_action_set(&mut self, id: binding::ActionSetId) -> Result<(), Error> {
+		let arc_config = self.config.upgrade().ok_or(Error::ConfigDropped)?;
+		let action_set = {
+			let config = arc_config.read().unwrap();
+			config
+				.get_action_set(&id)
+				.cloned()
+				.ok_or(Error::UnknownActionSet(id))?
+		};
+		self.enabled_action_sets.insert(id, action_set);
+		self.add_action_states(id);
+		Ok(())
+	}
+
+	/// Replaces the bindings of an enabled action set (if it is enabled), keeping the state of actions which are still bound.
+	#[cfg(feature = "hot-reload")]
+	pub(crate) fn reload_action_set(&mut self, id: binding::ActionSetId, set: binding::ActionSet) {
+		if !self.enabled_action_sets.contains_key(&id) {
+			return;
+		}
+		self.enabled_action_sets.insert(id, set);
+		self.rebuild_action_states();
+	}
+
+	/// Recreates the bindings of all actions from the enabled action sets, active layout, and rebinds.
+	/// Actions which are still bound keep their current state (and any handles to them remain valid),
+	/// while actions which are no longer bound are canceled.
+	/// Sources which are no longer bound to an action are released on the next update.
+	fn rebuild_action_states(&mut self) {
+		let previous_states = self.action_states.clone();
+		let previous_bindings = std::mem::take(&mut self.bound_actions);
 		self.bound_actions.clear();
+		self.source_actions.clear();
 		self.action_states.clear();
 		self.ticking_states.clear();
 		let set_ids = self.enabled_action_sets.keys().cloned().collect::<Vec<_>>();
 		for set_id in set_ids {
 			self.add_action_states(set_id);
 		}
-	}
 
-	/// Enables a provided [`action set`](binding::ActionSet) for a given user.
-	/// When enabled, a user will receive input events for the actions in the [`action set`](binding::ActionSet),
-	/// until the set is disabled (or until [`crate::DeviceCache::update`] stops being called).
-	pub fn enable_action_set(&mut self, id: binding::ActionSetId) {
-		if let Some(arc_config) = self.config.upgrade() {
-			if let Ok(config) = arc_config.read() {
-				if let Some(action_set) = config.get_action_set(&id) {
-					self.enabled_action_sets.insert(id, action_set.clone());
-					self.add_action_states(id);
+		let mut removed_actions = Vec::new();
+		for (action_id, previous) in previous_states.iter() {
+			match self.action_states.get_mut(action_id) {
+				Some(arc_state) => {
+					let behaviors = arc_state.read().unwrap().behaviors().clone();
+					previous.write().unwrap().set_behaviors(behaviors);
+					*arc_state = previous.clone();
+					let sources = &self.bound_actions[action_id].sources;
+					for source in previous_bindings[action_id].sources.iter() {
+						if !sources.contains(source) {
+							self.pending_releases.push((*action_id, *source));
+						}
+					}
 				}
+				None => removed_actions.push(*action_id),
 			}
 		}
+		// Canceling needs the removed states, so they are temporarily restored.
+		for action_id in removed_actions.iter() {
+			self.action_states
+				.insert(action_id, previous_states[action_id].clone());
+		}
+		self.cancel_actions(removed_actions.clone());
+		for action_id in removed_actions.iter() {
+			self.action_states.remove(action_id);
+		}
 	}
 
 	pub fn disable_action_set(&mut self, id: binding::ActionSetId) {
@@ -111,6 +490,79 @@ impl User {
 		self.remove_action_states(&id);
 	}
 
+	/// Returns a channel which receives an [`event`](action::Event) whenever any of the user's actions change.
+	/// Events are emitted during [`update`](User::update). Dropping the receiver unsubscribes it.
+	pub fn subscribe(&mut self) -> action::EventReceiver {
+		let (sender, receiver) = crossbeam_channel::unbounded();
+		self.observers.push(action::Observer::Channel(None, sender));
+		receiver
+	}
+
+	/// Returns a channel which receives an [`event`](action::Event) whenever a specific action changes.
+	/// Events are emitted during [`update`](User::update). Dropping the receiver unsubscribes it.
+	pub fn subscribe_to(&mut self, id: action::Id) -> action::EventReceiver {
+		let (sender, receiver) = crossbeam_channel::unbounded();
+		self.observers
+			.push(action::Observer::Channel(Some(id), sender));
+		receiver
+	}
+
+	/// Registers a callback which is executed during [`update`](User::update) whenever a specific action changes.
+	pub fn observe<F>(&mut self, id: action::Id, callback: F)
+	where
+		F: FnMut(action::Id, action::Event) + Send + Sync + 'static,
+	{
+		self.observers
+			.push(action::Observer::Callback(Some(id), Box::new(callback)));
+	}
+
+	/// Removes all callbacks registered via [`observe`](User::observe) for an action.
+	pub fn clear_observers(&mut self, id: action::Id) {
+		self.observers.retain(|observer| match observer {
+			action::Observer::Callback(filter, _) => *filter != Some(id),
+			action::Observer::Channel(_, _) => true,
+		});
+	}
+
+	fn notify(&mut self, events: Vec<(action::Id, action::Event)>) {
+		if self.observers.is_empty() {
+			return;
+		}
+		for (id, event) in events {
+			self.observers
+				.retain_mut(|observer| observer.notify(id, event));
+		}
+	}
+
+	/// Emits [`Canceled`](action::Event::Canceled) for any of the actions which are actuated,
+	/// in preparation for those actions being removed.
+	fn cancel_actions(&mut self, action_ids: Vec<action::Id>) {
+		let canceled = action_ids
+			.into_iter()
+			.filter(|id| match self.action_states.get(id) {
+				Some(arc_state) => arc_state.read().unwrap().is_actuated(),
+				None => false,
+			})
+			.map(|id| (id, action::Event::Canceled))
+			.collect();
+		self.notify(canceled);
+	}
+
+	/// Returns a handle to the state of all actions as of the most recent update,
+	/// which can be read from other threads without locking the user.
+	pub fn snapshot_handle(&self) -> action::SnapshotHandle {
+		self.published.clone()
+	}
+
+	fn publish(&self) {
+		let states = self
+			.action_states
+			.iter()
+			.map(|(id, arc_state)| (*id, arc_state.read().unwrap().value_snapshot()))
+			.collect();
+		self.published.publish(action::StateSnapshot::new(states));
+	}
+
 	pub fn get_action_in(user: &ArcLockUser, id: action::Id) -> Option<action::WeakLockState> {
 		match user.read() {
 			Ok(user) => user.get_action(id),
@@ -122,88 +574,897 @@ impl User {
 		self.action_states.get(id).map(|arc| Arc::downgrade(&arc))
 	}
 
-	fn add_action_states(&mut self, set_id: binding::ActionSetId) {
-		if let Some(action_binding_map) = self
-			.enabled_action_sets
-			.get(&set_id)
-			.unwrap()
-			.get(&self.active_layout)
-		{
-			for (action_id, behavior_binding) in action_binding_map.iter() {
-				self.bound_actions.insert(
-					BindingStateKey {
-						set_id: set_id,
-						layout: self.active_layout,
-						sources: behavior_binding.sources(),
-					},
-					action_id,
-				);
-				let action_state = action::State::new(behavior_binding.clone());
-				let must_tick = action_state.requires_updates();
-				let arc = action_state.arclocked();
-				if must_tick {
-					self.ticking_states.insert(action_id);
+	/// Returns the state of an action, failing if the action is not in any of the user's enabled action sets.
+	pub fn try_get_action(&self, id: action::Id) -> Result<action::WeakLockState, Error> {
+		self.get_action(id).ok_or(Error::ActionNotEnabled(id))
+	}
+
+	/// Returns the ids of the user's active button and axis actions, in the order they are stored in a [`Snapshot`].
+	/// Actions which are not in the [`Config`](crate::Config) are not included.
+	fn snapshot_layout(&self) -> (Vec<action::Id>, Vec<action::Id>) {
+		let mut ids = self.action_states.keys().cloned().collect::<Vec<_>>();
+		ids.sort_unstable();
+		let mut buttons = Vec::new();
+		let mut axes = Vec::new();
+		if let Some(arc_config) = self.config.upgrade() {
+			let config = arc_config.read().unwrap();
+			for id in ids {
+				match config.get_action_kind(&id) {
+					Some(source::Kind::Button) => buttons.push(id),
+					Some(source::Kind::Axis) => axes.push(id),
+					None => {}
+				}
+			}
+		}
+		(buttons, axes)
+	}
+
+	/// Captures the state of all actions as of the most recent update,
+	/// so it can be sent to another machine and [`applied`](User::apply_snapshot) to the same user there.
+	pub fn snapshot(&self, tick: u64) -> Snapshot {
+		let (buttons, axes) = self.snapshot_layout();
+		let mut snapshot = Snapshot::new(tick);
+		for id in buttons {
+			let state = self.action_states.get(id).unwrap().read().unwrap();
+			snapshot.push_button(state.button_snapshot());
+		}
+		for id in axes {
+			let state = self.action_states.get(id).unwrap().read().unwrap();
+			snapshot.push_axis(state.axis_value());
+		}
+		snapshot
+	}
+
+	/// Updates all actions to the state captured in a [`snapshot`](User::snapshot) from another machine,
+	/// in place of [`update_tick`](User::update_tick).
+	/// Snapshots do not depend on previous ticks, so re-applying older snapshots is safe when rolling back.
+	///
+	/// An [`empty`](Snapshot::is_empty) snapshot (predicted before any were received) releases every button and zeroes every axis.
+	/// Otherwise, fails with [`MalformedSnapshot`](Error::MalformedSnapshot) (without changing any actions)
+	/// if the snapshot does not have the same amount of button and axis actions as this user.
+	pub fn apply_snapshot(&mut self, snapshot: &Snapshot, delta: Duration) -> Result<(), Error> {
+		let time = action::Time::Tick {
+			index: snapshot.tick(),
+			delta,
+		};
+		let (buttons, axes) = self.snapshot_layout();
+		let counts_match =
+			buttons.len() == snapshot.buttons().len() && axes.len() == snapshot.axes().len();
+		if !counts_match && !snapshot.is_empty() {
+			return Err(Error::MalformedSnapshot);
+		}
+		// An empty snapshot has no values, so every action is given the neutral value instead.
+		let button_snapshots = snapshot
+			.buttons()
+			.iter()
+			.copied()
+			.chain(std::iter::repeat(Default::default()));
+		for (id, button) in buttons.into_iter().zip(button_snapshots) {
+			let mut state = self.action_states.get(id).unwrap().write().unwrap();
+			state.apply_button_snapshot(&button, &time);
+		}
+		let axis_values = snapshot.axes().chain(std::iter::repeat(0.0));
+		for (id, value) in axes.into_iter().zip(axis_values) {
+			let mut state = self.action_states.get(id).unwrap().write().unwrap();
+			state.apply_axis_snapshot(value, &time);
+		}
+
+		let mut events = Vec::new();
+		for (action_id, arc_state) in self.action_states.iter() {
+			for event in arc_state.write().unwrap().take_events() {
+				events.push((*action_id, event));
+			}
+		}
+		self.notify(events);
+		self.publish();
+		Ok(())
+	}
+
+	/// Returns the active layout followed by the layouts it inherits bindings from.
+	fn layout_chain(&self) -> Vec<binding::LayoutId> {
+		match self.config.upgrade() {
+			Some(config) => config.read().unwrap().layout_chain(self.active_layout),
+			None => vec![self.active_layout],
+		}
+	}
+
+	/// Returns the binding of each action in an enabled action set for the active layout, with the user's rebinds applied.
+	fn resolve_bindings(
+		&self,
+		set_id: &binding::ActionSetId,
+	) -> HashMap<action::Id, action::BehaviorBinding> {
+		let mut resolved = HashMap::new();
+		let action_set = match self.enabled_action_sets.get(set_id) {
+			Some(action_set) => action_set,
+			None => return resolved,
+		};
+		// Each action is bound by the first layout in the chain which has a binding for it.
+		for layout in self.layout_chain() {
+			if let Some(action_binding_map) = action_set.get(&layout) {
+				for (action_id, behavior_binding) in action_binding_map.iter() {
+					resolved
+						.entry(*action_id)
+						.or_insert_with(|| behavior_binding.clone());
 				}
-				self.action_states.insert(action_id, arc);
 			}
 		}
+		for (action_id, behavior_binding) in resolved.iter_mut() {
+			if let Some(rebinds) = self.rebinds.get(action_id) {
+				behavior_binding.replace_sources(rebinds);
+			}
+		}
+		resolved
+	}
+
+	fn add_action_states(&mut self, set_id: binding::ActionSetId) {
+		for (action_id, behavior_binding) in self.resolve_bindings(&set_id).into_iter() {
+			let sources = behavior_binding.sources();
+			Self::index_sources(&mut self.source_actions, &sources, action_id);
+			self.bound_actions.insert(
+				action_id,
+				ActionBinding {
+					set_id: set_id,
+					layout: self.active_layout,
+					sources,
+				},
+			);
+			let action_state = action::State::new(behavior_binding);
+			let must_tick = action_state.requires_updates();
+			let arc = action_state.arclocked();
+			if must_tick {
+				self.ticking_states.insert(action_id);
+			}
+			self.action_states.insert(action_id, arc);
+		}
 	}
 
 	fn remove_action_states(&mut self, set_id: &binding::ActionSetId) {
-		// TODO: Can use `drain_filter` (https://github.com/rust-lang/rust/issues/59618) when stablized
-		let mut retained_actions = HashMap::new();
-		let mut removed_actions = HashMap::new();
-		for (bound_state_key, action_id) in self.bound_actions.drain() {
-			if bound_state_key.set_id == *set_id {
-				removed_actions.insert(bound_state_key, action_id);
-			} else {
-				retained_actions.insert(bound_state_key, action_id);
-			}
-		}
-		self.bound_actions = retained_actions;
-		for (_, action_id) in removed_actions {
+		let removed_actions = self
+			.bound_actions
+			.iter()
+			.filter(|(_, bound)| bound.set_id == *set_id)
+			.map(|(action_id, _)| *action_id)
+			.collect::<Vec<_>>();
+		// Only the removed actions are unindexed, so actions which share sources with them keep receiving input.
+		for action_id in removed_actions.iter() {
+			let bound = self.bound_actions.remove(action_id).unwrap();
+			for source in bound.sources.iter() {
+				if let Some(action_ids) = self.source_actions.get_mut(source) {
+					action_ids.retain(|id| id != action_id);
+					if action_ids.is_empty() {
+						self.source_actions.remove(source);
+					}
+				}
+			}
+		}
+		self.cancel_actions(removed_actions.clone());
+		for action_id in removed_actions {
 			self.action_states.remove(action_id);
 			self.ticking_states.remove(action_id);
 		}
 	}
 
-	fn screen_size(&self) -> (f64, f64) {
-		let arc_consts = self.consts.upgrade().unwrap();
+	fn index_sources(
+		source_actions: &mut HashMap<binding::Source, Vec<action::Id>>,
+		sources: &[binding::Source],
+		action_id: action::Id,
+	) {
+		for source in sources.iter() {
+			let action_ids = source_actions.entry(*source).or_default();
+			if !action_ids.contains(&action_id) {
+				action_ids.push(action_id);
+			}
+		}
+	}
+
+	/// Returns the size of the screen (in logical pixels) for the [`DeviceCache`](crate::DeviceCache)
+	/// this user was created with.
+	pub fn screen_size(&self) -> Result<(f64, f64), Error> {
+		let arc_consts = self.consts.upgrade().ok_or(Error::ConstsDropped)?;
 		let consts = arc_consts.read().unwrap();
-		consts.screen_size
+		Ok(consts.screen_size)
 	}
 
 	pub(crate) fn process_event(
 		&mut self,
 		source: binding::Source,
 		state: &event::State,
-		time: &Instant,
+		time: &action::Time,
 	) {
-		let action_ids_bound_to_source =
-			self.bound_actions
-				.iter()
-				.filter_map(|(key, action_id)| match key.contains(source) {
-					true => Some(action_id),
-					false => None,
-				});
-		let screen_size = self.screen_size();
+		if self.text_output.is_some() && text::is_text_key(&source) {
+			return;
+		}
+		let action_ids_bound_to_source = match self.source_actions.get(&source) {
+			Some(action_ids) => action_ids,
+			None => return,
+		};
+		// Without a screen size, screen-based behaviors produce a value of 0.
+		let screen_size = self.screen_size().unwrap_or_default();
 		for action_id in action_ids_bound_to_source {
 			if let Some(arc_state) = self.action_states.get_mut(action_id) {
 				let mut action_state = arc_state.write().unwrap();
-				action_state.process_event(source, state.clone(), &time, screen_size);
+				action_state.process_event(
+					source,
+					state.clone(),
+					time,
+					screen_size,
+					&self.settings,
+				);
 			}
 		}
 	}
 
+	/// Processes all pending input and updates action states, using wall-clock time.
+	/// Call once per render frame.
+	///
+	/// Does nothing while the [`DeviceCache`](crate::DeviceCache) is [`playing back`](crate::DeviceCache::start_playback)
+	/// a recording, since the cache updates the user for each recorded frame at the time it was recorded.
 	pub fn update(&mut self, time: &Instant) {
+		if !self.playback_active {
+			self.update_at(&action::Time::Instant(*time));
+		}
+	}
+
+	/// Processes all pending input and updates action states for a fixed simulation step.
+	/// Use instead of [`update`](User::update) when actions are sampled by a simulation or rollback netcode;
+	/// button presses and releases are held until consumed by the next tick,
+	/// and time-based behaviors measure time as `delta` per tick.
+	/// Axis values are [`quantized`](crate::snapshot::quantize_axis) to the precision of a [`Snapshot`],
+	/// so peers applying this user's snapshots simulate the same values.
+	/// Like [`update`](User::update), this does nothing during playback.
+	pub fn update_tick(&mut self, tick: u64, delta: Duration) {
+		if !self.playback_active {
+			self.update_at(&action::Time::Tick { index: tick, delta });
+		}
+	}
+
+	pub(crate) fn set_playback_active(&mut self, active: bool) {
+		self.playback_active = active;
+	}
+
+	/// Updates action states for a frame of a recording which is being played back, at the time the frame was recorded.
+	pub(crate) fn update_playback_frame(&mut self, time: &Instant) {
+		self.update_at(&action::Time::Instant(*time));
+	}
+
+	fn update_at(&mut self, time: &action::Time) {
+		if self.release_text_keys {
+			self.release_text_keys = false;
+			self.release_keyboard_actions(time);
+		}
+		let releases = std::mem::take(&mut self.pending_releases);
+		self.release_sources(releases, time);
+
+		// Consecutive mouse movements are summed per axis, so that high-frequency mouse motion
+		// only needs to be processed once per update.
+		let mut mouse_moves: Vec<(binding::Source, f64)> = Vec::new();
 		while let Ok((source, state)) = self.input_receiver.try_recv() {
+			if let event::State::MouseMove(delta) = state {
+				match mouse_moves
+					.iter_mut()
+					.find(|(move_source, _)| *move_source == source)
+				{
+					Some((_, total)) => *total += delta,
+					None => mouse_moves.push((source, delta)),
+				}
+				continue;
+			}
+			for (move_source, total) in mouse_moves.drain(..) {
+				let state = event::State::MouseMove(total);
+				self.track_device(move_source, &state);
+				self.process_event(move_source, &state, time);
+			}
+			// Switching devices can change the layout, so the input is processed with the new bindings.
+			self.track_device(source, &state);
 			self.process_event(source, &state, &time);
 		}
+		for (move_source, total) in mouse_moves.drain(..) {
+			let state = event::State::MouseMove(total);
+			self.track_device(move_source, &state);
+			self.process_event(move_source, &state, time);
+		}
+		self.update_text_input(time);
 
+		let mut events = Vec::new();
 		for action_id in self.ticking_states.iter() {
 			let arc_state = self.action_states.get(action_id).unwrap();
 			let mut action_state = arc_state.write().unwrap();
 			action_state.update(time);
+			for event in action_state.take_events() {
+				events.push((*action_id, event));
+			}
+		}
+		self.notify(events);
+		self.publish();
+	}
+
+	/// Releases the actions bound to keys which type or edit text, so they are not stuck down while text input is active.
+	fn release_keyboard_actions(&mut self, time: &action::Time) {
+		let releases = self
+			.source_actions
+			.iter()
+			.filter(|(source, _)| text::is_text_key(source))
+			.flat_map(|(source, action_ids)| action_ids.iter().map(move |id| (*id, *source)))
+			.collect::<Vec<_>>();
+		self.release_sources(releases, time);
+	}
+
+	/// Sends each action the input its source would send when let go (a button release, or an axis returning to 0).
+	fn release_sources(
+		&mut self,
+		releases: Vec<(action::Id, binding::Source)>,
+		time: &action::Time,
+	) {
+		let screen_size = self.screen_size().unwrap_or_default();
+		for (action_id, source) in releases {
+			let state = match source.kind() {
+				source::Kind::Button => event::State::ButtonState(event::ButtonState::Released),
+				source::Kind::Axis => event::State::ValueChanged(0.0),
+			};
+			if let Some(arc_state) = self.action_states.get(action_id) {
+				arc_state.write().unwrap().process_event(
+					source,
+					state,
+					time,
+					screen_size,
+					&self.settings,
+				);
+			}
+		}
+	}
+
+	fn update_text_input(&mut self, time: &action::Time) {
+		let mut pending = Vec::new();
+		while let Ok(event) = self.text_receiver.try_recv() {
+			if self.text_output.is_none() {
+				continue;
+			}
+			match event {
+				text::TextEvent::Input(input) => pending.push(input),
+				text::TextEvent::EditKey(key, event::ButtonState::Pressed) => {
+					pending.push(text::TextInput::Edit(key));
+					self.held_edit_key = if key.repeats() {
+						Some((key, *time, 0))
+					} else {
+						None
+					};
+				}
+				text::TextEvent::EditKey(key, event::ButtonState::Released) => {
+					if matches!(self.held_edit_key, Some((held, _, _)) if held == key) {
+						self.held_edit_key = None;
+					}
+				}
+			}
+		}
+
+		if let Some((key, pressed_at, repeats)) = self.held_edit_key.as_mut() {
+			let held_for = time.duration_since(pressed_at);
+			if held_for >= self.key_repeat.delay {
+				let interval = self.key_repeat.interval.max(Duration::from_millis(1));
+				let due = 1
+					+ ((held_for - self.key_repeat.delay).as_secs_f64() / interval.as_secs_f64())
+						as u32;
+				while *repeats < due {
+					pending.push(text::TextInput::Edit(*key));
+					*repeats += 1;
+				}
+			}
+		}
+
+		if let Some(output) = self.text_output.as_ref() {
+			for input in pending {
+				if output.try_send(input).is_err() {
+					self.stop_text_input();
+					break;
+				}
+			}
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		binding::{ActionMap, ActionSet, ActionSetId, LayoutId},
+		source::{Axis, Key},
+		ArcLockConfig, Config,
+	};
+
+	fn config_with_sets(sets: Vec<(ActionSetId, ActionMap)>) -> ArcLockConfig {
+		let mut config = Config::default()
+			.add_action("jump", source::Kind::Button)
+			.add_action("confirm", source::Kind::Button)
+			.add_action("back", source::Kind::Button)
+			.add_action("move", source::Kind::Axis)
+			.add_layout(LayoutId::default());
+		for (id, map) in sets {
+			config = config.add_action_set(id, ActionSet::default().with(LayoutId::default(), map));
+		}
+		Arc::new(RwLock::new(config))
+	}
+
+	fn config(map: ActionMap) -> ArcLockConfig {
+		config_with_sets(vec![(ActionSetId::default(), map)])
+	}
+
+	fn press(user: &mut User, source: binding::Source, state: event::ButtonState) {
+		user.input_sender()
+			.try_send((source, event::State::ButtonState(state)))
+			.unwrap();
+		user.update(&Instant::now());
+	}
+
+	fn is_down(user: &User, action: action::Id) -> bool {
+		let state = user.get_action(action).unwrap().upgrade().unwrap();
+		let down = state.read().unwrap().is_button_down();
+		down
+	}
+
+	fn user(config: &ArcLockConfig) -> User {
+		let mut user = User::new("player".to_owned()).with_config(Arc::downgrade(config));
+		user.enable_action_set(ActionSetId::default());
+		user
+	}
+
+	fn gamepad_axis(axis: Axis) -> binding::Source {
+		binding::Source::Gamepad(
+			device::GamepadKind::DualAxisGamepad,
+			binding::Gamepad::Axis(axis),
+		)
+	}
+
+	#[test]
+	fn apply_snapshot_keeps_unbounded_axes() {
+		let config = config(
+			ActionMap::default()
+				.bind("jump", binding::Source::Keyboard(Key::Space))
+				.bind("move", gamepad_axis(Axis::LThumbstickX)),
+		);
+		let mut user = user(&config);
+		let mut snapshot = Snapshot::new(7);
+		snapshot.push_button(crate::snapshot::ButtonSnapshot {
+			down: true,
+			pressed: true,
+			released: false,
+		});
+		snapshot.push_axis(3.5);
+		user.apply_snapshot(&snapshot, Duration::from_millis(16))
+			.unwrap();
+		let state = user.get_action("move").unwrap().upgrade().unwrap();
+		assert_eq!(state.read().unwrap().axis_value(), 3.5);
+		let state = user.get_action("jump").unwrap().upgrade().unwrap();
+		assert!(state.read().unwrap().on_button_pressed());
+	}
+
+	#[test]
+	fn apply_snapshot_rejects_mismatched_actions() {
+		let config = config(
+			ActionMap::default()
+				.bind("jump", binding::Source::Keyboard(Key::Space))
+				.bind("move", gamepad_axis(Axis::LThumbstickX)),
+		);
+		let mut user = user(&config);
+		let mut snapshot = Snapshot::new(7);
+		snapshot.push_axis(1.0);
+		assert!(matches!(
+			user.apply_snapshot(&snapshot, Duration::from_millis(16)),
+			Err(Error::MalformedSnapshot)
+		));
+		let state = user.get_action("move").unwrap().upgrade().unwrap();
+		assert_eq!(state.read().unwrap().value(), 0.0);
+	}
+
+	#[test]
+	fn disabling_a_set_keeps_actions_sharing_sources() {
+		let space = binding::Source::Keyboard(Key::Space);
+		let config = config_with_sets(vec![
+			(
+				ActionSetId::default(),
+				ActionMap::default()
+					.bind("jump", space)
+					.bind("confirm", space),
+			),
+			(
+				Some("menu"),
+				ActionMap::default().bind("back", binding::Source::Keyboard(Key::Escape)),
+			),
+		]);
+		let mut user = user(&config);
+		user.enable_action_set(Some("menu"));
+		user.disable_action_set(Some("menu"));
+		assert!(user.get_action("back").is_none());
+
+		press(&mut user, space, event::ButtonState::Pressed);
+		assert!(is_down(&user, "jump"));
+		assert!(is_down(&user, "confirm"));
+	}
+
+	#[test]
+	fn behaviors_read_the_user_settings() {
+		let look = binding::Source::Mouse(binding::Mouse::Move(binding::MouseAxis::MouseX));
+		let config = config(
+			ActionMap::default().bind(
+				"move",
+				action::BehaviorBinding::from(look)
+					.with_behavior(action::behavior::SettingMultiplier::new("look_sensitivity")),
+			),
+		);
+		let mut user =
+			user(&config).with_settings(Settings::default().with_float("look_sensitivity", 2.5));
+		user.input_sender()
+			.try_send((look, event::State::MouseMove(4.0)))
+			.unwrap();
+		user.update(&Instant::now());
+		let state = user.get_action("move").unwrap().upgrade().unwrap();
+		assert_eq!(state.read().unwrap().value(), 10.0);
+
+		user.settings_mut().set_float("look_sensitivity", 0.5);
+		user.input_sender()
+			.try_send((look, event::State::MouseMove(4.0)))
+			.unwrap();
+		user.update(&Instant::now());
+		assert_eq!(state.read().unwrap().value(), 2.0);
+	}
+
+	#[test]
+	fn axis_behaviors_apply_to_gamepad_values() {
+		let stick = gamepad_axis(Axis::LThumbstickX);
+		let config = config(ActionMap::default().bind(
+			"move",
+			action::BehaviorBinding::from(stick).with_behavior(action::behavior::Deadzone(0.5)),
+		));
+		let mut user = user(&config);
+		let state = user.get_action("move").unwrap().upgrade().unwrap();
+		for (input, value) in [(0.25, 0.0), (0.75, 0.5), (-1.0, -1.0)].iter() {
+			user.input_sender()
+				.try_send((stick, event::State::ValueChanged(*input)))
+				.unwrap();
+			user.update(&Instant::now());
+			assert_eq!(state.read().unwrap().value(), *value);
+		}
+	}
+
+	#[test]
+	fn layouts_fall_back_to_parent_bindings() {
+		let stick = gamepad_axis(Axis::LThumbstickX);
+		let config = Arc::new(RwLock::new(
+			Config::default()
+				.add_action("jump", source::Kind::Button)
+				.add_action("move", source::Kind::Axis)
+				.add_layout(None)
+				.add_layout_with_parent(Some("left_handed"), None)
+				.add_action_set(
+					None,
+					ActionSet::default()
+						.with(
+							None,
+							ActionMap::default()
+								.bind("jump", binding::Source::Keyboard(Key::Space))
+								.bind("move", stick),
+						)
+						.with(
+							Some("left_handed"),
+							ActionMap::default().bind("jump", binding::Source::Keyboard(Key::J)),
+						),
+				),
+		));
+		let mut user = user(&config);
+		user.set_layout(Some("left_handed"));
+		press(
+			&mut user,
+			binding::Source::Keyboard(Key::Space),
+			event::ButtonState::Pressed,
+		);
+		assert!(!is_down(&user, "jump"));
+		press(
+			&mut user,
+			binding::Source::Keyboard(Key::J),
+			event::ButtonState::Pressed,
+		);
+		assert!(is_down(&user, "jump"));
+		user.input_sender()
+			.try_send((stick, event::State::ValueChanged(0.5)))
+			.unwrap();
+		user.update(&Instant::now());
+		let state = user.get_action("move").unwrap().upgrade().unwrap();
+		assert_eq!(state.read().unwrap().value(), 0.5);
+	}
+
+	#[test]
+	fn keyboard_and_mouse_count_as_one_device() {
+		let mouse_x = binding::Source::Mouse(binding::Mouse::Move(binding::MouseAxis::MouseX));
+		let face_bottom = binding::Source::Gamepad(
+			device::GamepadKind::DualAxisGamepad,
+			binding::Gamepad::Button(source::Button::FaceBottom),
+		);
+		let config = config(ActionMap::default());
+		let mut user = user(&config);
+		let changes = user.subscribe_to_device_changes();
+		let send = |user: &mut User, source, state| {
+			user.input_sender().try_send((source, state)).unwrap();
+			user.update(&Instant::now());
+			changes.try_iter().collect::<Vec<_>>()
+		};
+		let pressed = event::State::ButtonState(event::ButtonState::Pressed);
+
+		let key = binding::Source::Keyboard(Key::W);
+		assert_eq!(send(&mut user, key, pressed), vec![device::Kind::Keyboard]);
+		assert_eq!(
+			send(&mut user, mouse_x, event::State::MouseMove(20.0)),
+			vec![]
+		);
+		assert_eq!(
+			send(&mut user, face_bottom, pressed),
+			vec![device::Kind::Gamepad(device::GamepadKind::DualAxisGamepad)]
+		);
+		assert_eq!(
+			send(&mut user, mouse_x, event::State::MouseMove(1.0)),
+			vec![]
+		);
+		assert_eq!(
+			send(&mut user, mouse_x, event::State::MouseMove(-6.0)),
+			vec![device::Kind::Mouse]
+		);
+		assert_eq!(send(&mut user, key, pressed), vec![]);
+		assert_eq!(user.last_device(), Some(device::Kind::Mouse));
+	}
+
+	#[test]
+	fn prompts_show_keyboard_sources_after_mouse_movement() {
+		let space = binding::Source::Keyboard(Key::Space);
+		let click = binding::Source::Mouse(binding::Mouse::Button(source::MouseButton::Left));
+		let face_bottom = binding::Source::Gamepad(
+			device::GamepadKind::DualAxisGamepad,
+			binding::Gamepad::Button(source::Button::FaceBottom),
+		);
+		let config = config(ActionMap::default().bind("jump", space + click + face_bottom));
+		let mut user = user(&config);
+		assert_eq!(user.prompt_sources("jump").len(), 3);
+		user.input_sender()
+			.try_send((
+				binding::Source::Mouse(binding::Mouse::Move(binding::MouseAxis::MouseY)),
+				event::State::MouseMove(10.0),
+			))
+			.unwrap();
+		user.update(&Instant::now());
+		assert_eq!(user.prompt_sources("jump"), vec![space, click]);
+	}
+
+	#[test]
+	fn rebinds_swap_sources() {
+		let (w, s) = (
+			binding::Source::Keyboard(Key::W),
+			binding::Source::Keyboard(Key::S),
+		);
+		let config = config(ActionMap::default().bind("jump", w + s));
+		let mut user = user(&config);
+		user.rebind("jump", w, s);
+		user.rebind("jump", s, w);
+		assert_eq!(user.bound_sources("jump"), vec![s, w]);
+		assert_eq!(user.rebinds()["jump"], vec![(w, s), (s, w)]);
+
+		user.rebind("jump", w, w);
+		user.rebind("jump", s, s);
+		assert_eq!(user.bound_sources("jump"), vec![w, s]);
+		assert!(user.rebinds().is_empty());
+	}
+
+	#[test]
+	fn rebinding_keeps_action_handles_and_releases_unbound_sources() {
+		let (w, s) = (
+			binding::Source::Keyboard(Key::W),
+			binding::Source::Keyboard(Key::S),
+		);
+		let config = config(ActionMap::default().bind("jump", w));
+		let mut user = user(&config);
+		let handle = user.get_action("jump").unwrap();
+		press(&mut user, w, event::ButtonState::Pressed);
+		assert!(is_down(&user, "jump"));
+
+		user.rebind("jump", w, s);
+		assert!(handle.upgrade().is_some());
+		user.update(&Instant::now());
+		assert!(!is_down(&user, "jump"));
+		press(&mut user, s, event::ButtonState::Pressed);
+		assert!(handle.upgrade().unwrap().read().unwrap().is_button_down());
+
+		user.clear_rebinds("jump");
+		user.reset_action_states();
+		assert!(handle.upgrade().is_some());
+		assert!(!is_down(&user, "jump"));
+	}
+
+	#[test]
+	fn releasing_a_device_keeps_actions_of_other_devices() {
+		let face_bottom = binding::Source::Gamepad(
+			device::GamepadKind::DualAxisGamepad,
+			binding::Gamepad::Button(source::Button::FaceBottom),
+		);
+		let space = binding::Source::Keyboard(Key::Space);
+		let config = config(
+			ActionMap::default()
+				.bind("jump", face_bottom)
+				.bind("confirm", space),
+		);
+		let mut user = user(&config);
+		press(&mut user, face_bottom, event::ButtonState::Pressed);
+		press(&mut user, space, event::ButtonState::Pressed);
+		let events = user.subscribe();
+
+		user.release_device(device::Kind::Gamepad(device::GamepadKind::DualAxisGamepad));
+		user.update(&Instant::now());
+		assert!(!is_down(&user, "jump"));
+		assert!(is_down(&user, "confirm"));
+		let events = events.try_iter().collect::<Vec<_>>();
+		assert!(events.iter().all(|(id, _)| *id == "jump"));
+		assert!(matches!(
+			events.last(),
+			Some(("jump", action::Event::Performed))
+		));
+	}
+
+	#[test]
+	fn snapshots_give_peers_the_same_axis_values() {
+		let config = config(ActionMap::default().bind("move", gamepad_axis(Axis::LThumbstickX)));
+		let (mut local, mut remote) = (user(&config), user(&config));
+		local
+			.input_sender()
+			.try_send((
+				gamepad_axis(Axis::LThumbstickX),
+				event::State::ValueChanged(0.3),
+			))
+			.unwrap();
+		local.update_tick(1, Duration::from_millis(16));
+		let bytes = local.snapshot(1).to_bytes();
+		remote
+			.apply_snapshot(
+				&Snapshot::from_bytes(&bytes).unwrap(),
+				Duration::from_millis(16),
+			)
+			.unwrap();
+		let axis_value = |user: &User| {
+			let state = user.get_action("move").unwrap().upgrade().unwrap();
+			let value = state.read().unwrap().axis_value();
+			value
+		};
+		assert_ne!(axis_value(&local), 0.0);
+		assert_eq!(axis_value(&local), axis_value(&remote));
+	}
+
+	#[test]
+	fn predictions_before_any_snapshot_release_everything() {
+		let config = config(
+			ActionMap::default()
+				.bind("jump", binding::Source::Keyboard(Key::Space))
+				.bind("move", gamepad_axis(Axis::LThumbstickX)),
+		);
+		let mut user = user(&config);
+		press(
+			&mut user,
+			binding::Source::Keyboard(Key::Space),
+			event::ButtonState::Pressed,
+		);
+		let mut remote = crate::snapshot::RemoteInput::default();
+		let (prediction, is_prediction) = remote.snapshot(1);
+		assert!(is_prediction);
+		user.apply_snapshot(&prediction, Duration::from_millis(16))
+			.unwrap();
+		assert!(!is_down(&user, "jump"));
+	}
+
+	#[test]
+	fn snapshots_are_readable_while_the_user_is_locked() {
+		let config =
+			config(ActionMap::default().bind("jump", binding::Source::Keyboard(Key::Space)));
+		let mut user = user(&config);
+		let handle = user.snapshot_handle();
+		assert!(!handle.latest().down("jump"));
+		press(
+			&mut user,
+			binding::Source::Keyboard(Key::Space),
+			event::ButtonState::Pressed,
+		);
+
+		let user = user.arclocked();
+		let _locked = user.write().unwrap();
+		let reader = std::thread::spawn(move || {
+			let latest = handle.latest();
+			(latest.down("jump"), latest.pressed("jump"))
+		});
+		assert_eq!(reader.join().unwrap(), (true, true));
+	}
+
+	fn send_text(user: &User, event: text::TextEvent) {
+		user.text_sender().try_send(event).unwrap();
+	}
+
+	#[test]
+	fn starting_text_input_suspends_text_keys() {
+		let w = binding::Source::Keyboard(Key::W);
+		let click = binding::Source::Mouse(binding::Mouse::Button(source::MouseButton::Left));
+		let config = config(ActionMap::default().bind("jump", w).bind("confirm", click));
+		let mut user = user(&config);
+		press(&mut user, w, event::ButtonState::Pressed);
+		press(&mut user, click, event::ButtonState::Pressed);
+
+		let _text = user.start_text_input();
+		user.update(&Instant::now());
+		assert!(!is_down(&user, "jump"));
+		assert!(is_down(&user, "confirm"));
+		press(&mut user, w, event::ButtonState::Pressed);
+		assert!(!is_down(&user, "jump"));
+
+		user.stop_text_input();
+		press(&mut user, w, event::ButtonState::Pressed);
+		assert!(is_down(&user, "jump"));
+	}
+
+	#[test]
+	fn text_and_ime_input_pass_through_in_order() {
+		let config = config(ActionMap::default());
+		let mut user = user(&config);
+		// Text typed before the input is focused is discarded.
+		send_text(&user, text::TextEvent::Input(text::TextInput::Char('x')));
+		let text = user.start_text_input();
+		let inputs = vec![
+			text::TextInput::Preedit {
+				text: "にほ".to_owned(),
+				cursor: Some((6, 6)),
+			},
+			text::TextInput::Commit("日本".to_owned()),
+			text::TextInput::Char('a'),
+		];
+		for input in inputs.iter() {
+			send_text(&user, text::TextEvent::Input(input.clone()));
+		}
+		user.update(&Instant::now());
+		assert_eq!(text.try_iter().collect::<Vec<_>>(), inputs);
+
+		user.stop_text_input();
+		send_text(&user, text::TextEvent::Input(text::TextInput::Char('b')));
+		user.update(&Instant::now());
+		assert!(text.try_recv().is_err());
+	}
+
+	#[test]
+	fn held_edit_keys_repeat_after_the_delay() {
+		let config = config(ActionMap::default());
+		let mut user = user(&config);
+		let repeat = text::KeyRepeat::default();
+		let text = user.start_text_input();
+		let start = Instant::now();
+		let edits = |text: &text::TextReceiver| text.try_iter().count();
+
+		send_text(
+			&user,
+			text::TextEvent::EditKey(text::EditKey::Backspace, event::ButtonState::Pressed),
+		);
+		user.update(&start);
+		assert_eq!(edits(&text), 1);
+		user.update(&(start + repeat.delay / 2));
+		assert_eq!(edits(&text), 0);
+		// The first repeat is due after the delay, and another after each interval.
+		user.update(&(start + repeat.delay + repeat.interval * 2));
+		assert_eq!(edits(&text), 3);
+
+		send_text(
+			&user,
+			text::TextEvent::EditKey(text::EditKey::Backspace, event::ButtonState::Released),
+		);
+		user.update(&(start + repeat.delay * 4));
+		assert_eq!(edits(&text), 0);
+
+		// Keys like Enter never repeat.
+		send_text(
+			&user,
+			text::TextEvent::EditKey(text::EditKey::Enter, event::ButtonState::Pressed),
+		);
+		user.update(&(start + repeat.delay * 4));
+		user.update(&(start + repeat.delay * 8));
+		assert_eq!(
+			text.try_iter().collect::<Vec<_>>(),
+			vec![text::TextInput::Edit(text::EditKey::Enter)]
+		);
+	}
+}
@@ -1,13 +1,33 @@
 use crate::{
 	binding, device, event,
 	source::{Key, MouseButton},
+	text,
 };
-use std::convert::{TryFrom, TryInto};
+use std::convert::TryFrom;
 use winit::event::VirtualKeyCode;
 
-// TODO: Winit gamepad support is still in progress https://github.com/rust-windowing/winit/issues/944
+/// Which winit events keyboard input is read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyboardEvents {
+	/// `WindowEvent::KeyboardInput`, which is only sent while the window is focused.
+	/// When the window loses focus, winit releases any held keys so actions don't get stuck.
+	Window,
+	/// `DeviceEvent::Key`, which is sent even while the window is not focused.
+	Device,
+}
+
+/// Parses a winit event, reading keyboard input from [`window events`](KeyboardEvents::Window).
 pub fn parse_winit_event<'a, T>(event: &winit::event::Event<'a, T>) -> Result<event::Event, ()> {
-	use winit::event::{DeviceEvent, ElementState, KeyboardInput};
+	parse_winit_event_with(event, KeyboardEvents::Window)
+}
+
+// TODO: Winit gamepad support is still in progress https://github.com/rust-windowing/winit/issues/944
+#[allow(clippy::result_unit_err)]
+pub fn parse_winit_event_with<'a, T>(
+	event: &winit::event::Event<'a, T>,
+	keyboard: KeyboardEvents,
+) -> Result<event::Event, ()> {
+	use winit::event::{DeviceEvent, ElementState, Ime};
 	match event {
 		// resolution changed
 		winit::event::Event::WindowEvent {
@@ -32,6 +52,27 @@ pub fn parse_winit_event<'a, T>(event: &winit::event::Event<'a, T>) -> Result<ev
 				*scale_factor,
 			),
 		)),
+		// focus changed
+		winit::event::Event::WindowEvent {
+			event: winit::event::WindowEvent::Focused(focused),
+			..
+		} => Ok(event::Event::Window(event::WindowEvent::Focused(*focused))),
+		// typed text; control characters (like backspace) are sent as edit keys by keyboard input instead
+		winit::event::Event::WindowEvent {
+			event: winit::event::WindowEvent::ReceivedCharacter(c),
+			..
+		} if !c.is_control() => Ok(event::Event::Text(text::TextInput::Char(*c))),
+		winit::event::Event::WindowEvent {
+			event: winit::event::WindowEvent::Ime(ime),
+			..
+		} => match ime {
+			Ime::Preedit(text, cursor) => Ok(event::Event::Text(text::TextInput::Preedit {
+				text: text.clone(),
+				cursor: *cursor,
+			})),
+			Ime::Commit(text) => Ok(event::Event::Text(text::TextInput::Commit(text.clone()))),
+			Ime::Enabled | Ime::Disabled => Err(()),
+		},
 		winit::event::Event::DeviceEvent {
 			event: DeviceEvent::Motion { axis, value },
 			..
@@ -94,35 +135,34 @@ pub fn parse_winit_event<'a, T>(event: &winit::event::Event<'a, T>) -> Result<ev
 					}),
 				)
 			})
-			.map_err(|id| {
-				println!("ERROR failed to parse button id {:?}", id);
-				()
+			.map_err(|_id| {
+				#[cfg(feature = "log")]
+				log::warn!(target: crate::LOG, "Failed to parse mouse button id {:?}", _id);
 			}),
+		winit::event::Event::WindowEvent {
+			event: winit::event::WindowEvent::KeyboardInput { input, .. },
+			..
+		} if keyboard == KeyboardEvents::Window => Ok(key_event(input)),
 		winit::event::Event::DeviceEvent {
-			event:
-				DeviceEvent::Key(KeyboardInput {
-					state,
-					virtual_keycode: Some(keycode),
-					..
-				}),
+			event: DeviceEvent::Key(input),
 			..
-		} => (*keycode)
-			.try_into()
-			.map(|keycode| {
-				event::Event::Input(
-					device::Id::Keyboard,
-					binding::Source::Keyboard(keycode),
-					event::State::ButtonState(match state {
-						ElementState::Pressed => event::ButtonState::Pressed,
-						ElementState::Released => event::ButtonState::Released,
-					}),
-				)
-			})
-			.map_err(|_| ()),
+		} if keyboard == KeyboardEvents::Device => Ok(key_event(input)),
 		_ => Err(()),
 	}
 }
 
+fn key_event(input: &winit::event::KeyboardInput) -> event::Event {
+	event::Event::Key {
+		// Keys which winit can't identify have no logical key, but are still bound by their scancode as a physical key.
+		logical: input.virtual_keycode.map(Key::from),
+		scancode: input.scancode,
+		state: match input.state {
+			winit::event::ElementState::Pressed => event::ButtonState::Pressed,
+			winit::event::ElementState::Released => event::ButtonState::Released,
+		},
+	}
+}
+
 impl TryFrom<winit::event::ButtonId> for MouseButton {
 	type Error = winit::event::ButtonId;
 	fn try_from(id: winit::event::ButtonId) -> Result<Self, Self::Error> {
@@ -135,173 +175,208 @@ impl TryFrom<winit::event::ButtonId> for MouseButton {
 	}
 }
 
-impl TryFrom<VirtualKeyCode> for Key {
-	type Error = ();
-	fn try_from(winit: VirtualKeyCode) -> Result<Self, Self::Error> {
+impl From<VirtualKeyCode> for Key {
+	fn from(winit: VirtualKeyCode) -> Self {
 		match winit {
-			VirtualKeyCode::Key1 => Ok(Key::Key1),
-			VirtualKeyCode::Key2 => Ok(Key::Key2),
-			VirtualKeyCode::Key3 => Ok(Key::Key3),
-			VirtualKeyCode::Key4 => Ok(Key::Key4),
-			VirtualKeyCode::Key5 => Ok(Key::Key5),
-			VirtualKeyCode::Key6 => Ok(Key::Key6),
-			VirtualKeyCode::Key7 => Ok(Key::Key7),
-			VirtualKeyCode::Key8 => Ok(Key::Key8),
-			VirtualKeyCode::Key9 => Ok(Key::Key9),
-			VirtualKeyCode::Key0 => Ok(Key::Key0),
-			VirtualKeyCode::A => Ok(Key::A),
-			VirtualKeyCode::B => Ok(Key::B),
-			VirtualKeyCode::C => Ok(Key::C),
-			VirtualKeyCode::D => Ok(Key::D),
-			VirtualKeyCode::E => Ok(Key::E),
-			VirtualKeyCode::F => Ok(Key::F),
-			VirtualKeyCode::G => Ok(Key::G),
-			VirtualKeyCode::H => Ok(Key::H),
-			VirtualKeyCode::I => Ok(Key::I),
-			VirtualKeyCode::J => Ok(Key::J),
-			VirtualKeyCode::K => Ok(Key::K),
-			VirtualKeyCode::L => Ok(Key::L),
-			VirtualKeyCode::M => Ok(Key::M),
-			VirtualKeyCode::N => Ok(Key::N),
-			VirtualKeyCode::O => Ok(Key::O),
-			VirtualKeyCode::P => Ok(Key::P),
-			VirtualKeyCode::Q => Ok(Key::Q),
-			VirtualKeyCode::R => Ok(Key::R),
-			VirtualKeyCode::S => Ok(Key::S),
-			VirtualKeyCode::T => Ok(Key::T),
-			VirtualKeyCode::U => Ok(Key::U),
-			VirtualKeyCode::V => Ok(Key::V),
-			VirtualKeyCode::W => Ok(Key::W),
-			VirtualKeyCode::X => Ok(Key::X),
-			VirtualKeyCode::Y => Ok(Key::Y),
-			VirtualKeyCode::Z => Ok(Key::Z),
-			VirtualKeyCode::Escape => Ok(Key::Escape),
-			VirtualKeyCode::F1 => Ok(Key::F1),
-			VirtualKeyCode::F2 => Ok(Key::F2),
-			VirtualKeyCode::F3 => Ok(Key::F3),
-			VirtualKeyCode::F4 => Ok(Key::F4),
-			VirtualKeyCode::F5 => Ok(Key::F5),
-			VirtualKeyCode::F6 => Ok(Key::F6),
-			VirtualKeyCode::F7 => Ok(Key::F7),
-			VirtualKeyCode::F8 => Ok(Key::F8),
-			VirtualKeyCode::F9 => Ok(Key::F9),
-			VirtualKeyCode::F10 => Ok(Key::F10),
-			VirtualKeyCode::F11 => Ok(Key::F11),
-			VirtualKeyCode::F12 => Ok(Key::F12),
-			VirtualKeyCode::F13 => Ok(Key::F13),
-			VirtualKeyCode::F14 => Ok(Key::F14),
-			VirtualKeyCode::F15 => Ok(Key::F15),
-			VirtualKeyCode::F16 => Ok(Key::F16),
-			VirtualKeyCode::F17 => Ok(Key::F17),
-			VirtualKeyCode::F18 => Ok(Key::F18),
-			VirtualKeyCode::F19 => Ok(Key::F19),
-			VirtualKeyCode::F20 => Ok(Key::F20),
-			VirtualKeyCode::F21 => Ok(Key::F21),
-			VirtualKeyCode::F22 => Ok(Key::F22),
-			VirtualKeyCode::F23 => Ok(Key::F23),
-			VirtualKeyCode::F24 => Ok(Key::F24),
-			VirtualKeyCode::Snapshot => Ok(Key::Snapshot),
-			VirtualKeyCode::Scroll => Ok(Key::ScrollLock),
-			VirtualKeyCode::Pause => Ok(Key::Pause),
-			VirtualKeyCode::Insert => Ok(Key::Insert),
-			VirtualKeyCode::Home => Ok(Key::Home),
-			VirtualKeyCode::Delete => Ok(Key::Delete),
-			VirtualKeyCode::End => Ok(Key::End),
-			VirtualKeyCode::PageDown => Ok(Key::PageDown),
-			VirtualKeyCode::PageUp => Ok(Key::PageUp),
-			VirtualKeyCode::Left => Ok(Key::Left),
-			VirtualKeyCode::Up => Ok(Key::Up),
-			VirtualKeyCode::Right => Ok(Key::Right),
-			VirtualKeyCode::Down => Ok(Key::Down),
-			VirtualKeyCode::Back => Ok(Key::Back),
-			VirtualKeyCode::Return => Ok(Key::Return),
-			VirtualKeyCode::Space => Ok(Key::Space),
-			VirtualKeyCode::Compose => Err(()),
-			VirtualKeyCode::Caret => Err(()),
-			VirtualKeyCode::Numlock => Ok(Key::Numlock),
-			VirtualKeyCode::Numpad0 => Ok(Key::Numpad0),
-			VirtualKeyCode::Numpad1 => Ok(Key::Numpad1),
-			VirtualKeyCode::Numpad2 => Ok(Key::Numpad2),
-			VirtualKeyCode::Numpad3 => Ok(Key::Numpad3),
-			VirtualKeyCode::Numpad4 => Ok(Key::Numpad4),
-			VirtualKeyCode::Numpad5 => Ok(Key::Numpad5),
-			VirtualKeyCode::Numpad6 => Ok(Key::Numpad6),
-			VirtualKeyCode::Numpad7 => Ok(Key::Numpad7),
-			VirtualKeyCode::Numpad8 => Ok(Key::Numpad8),
-			VirtualKeyCode::Numpad9 => Ok(Key::Numpad9),
-			VirtualKeyCode::NumpadAdd => Ok(Key::NumpadPlus),
-			VirtualKeyCode::NumpadDivide => Ok(Key::NumpadSlash),
-			VirtualKeyCode::NumpadDecimal => Err(()),
-			VirtualKeyCode::NumpadComma => Err(()),
-			VirtualKeyCode::NumpadEnter => Ok(Key::NumpadEnter),
-			VirtualKeyCode::NumpadEquals => Err(()),
-			VirtualKeyCode::NumpadMultiply => Ok(Key::NumpadAsterisk),
-			VirtualKeyCode::NumpadSubtract => Ok(Key::NumpadMinus),
-			VirtualKeyCode::AbntC1 => Err(()),
-			VirtualKeyCode::AbntC2 => Err(()),
-			VirtualKeyCode::Apostrophe => Ok(Key::Apostrophe),
-			VirtualKeyCode::Apps => Err(()),
-			VirtualKeyCode::Asterisk => Err(()),
-			VirtualKeyCode::At => Err(()),
-			VirtualKeyCode::Ax => Err(()),
-			VirtualKeyCode::Backslash => Ok(Key::Backslash),
-			VirtualKeyCode::Calculator => Err(()),
-			VirtualKeyCode::Capital => Ok(Key::CapitalLock),
-			VirtualKeyCode::Colon => Err(()),
-			VirtualKeyCode::Comma => Ok(Key::Comma),
-			VirtualKeyCode::Convert => Err(()),
-			VirtualKeyCode::Equals => Ok(Key::Equals),
-			VirtualKeyCode::Grave => Ok(Key::Grave),
-			VirtualKeyCode::Kana => Err(()),
-			VirtualKeyCode::Kanji => Err(()),
-			VirtualKeyCode::LAlt => Ok(Key::LAlt),
-			VirtualKeyCode::LBracket => Ok(Key::LBracket),
-			VirtualKeyCode::LControl => Ok(Key::LControl),
-			VirtualKeyCode::LShift => Ok(Key::LShift),
-			VirtualKeyCode::LWin => Ok(Key::LWin),
-			VirtualKeyCode::Mail => Err(()),
-			VirtualKeyCode::MediaSelect => Err(()),
-			VirtualKeyCode::MediaStop => Err(()),
-			VirtualKeyCode::Minus => Ok(Key::Minus),
-			VirtualKeyCode::Mute => Err(()),
-			VirtualKeyCode::MyComputer => Err(()),
-			VirtualKeyCode::NavigateForward => Err(()),
-			VirtualKeyCode::NavigateBackward => Err(()),
-			VirtualKeyCode::NextTrack => Err(()),
-			VirtualKeyCode::NoConvert => Err(()),
-			VirtualKeyCode::OEM102 => Err(()),
-			VirtualKeyCode::Period => Ok(Key::Period),
-			VirtualKeyCode::PlayPause => Err(()),
-			VirtualKeyCode::Plus => Err(()),
-			VirtualKeyCode::Power => Err(()),
-			VirtualKeyCode::PrevTrack => Err(()),
-			VirtualKeyCode::RAlt => Ok(Key::RAlt),
-			VirtualKeyCode::RBracket => Ok(Key::RBracket),
-			VirtualKeyCode::RControl => Ok(Key::RControl),
-			VirtualKeyCode::RShift => Ok(Key::RShift),
-			VirtualKeyCode::RWin => Ok(Key::RWin),
-			VirtualKeyCode::Semicolon => Ok(Key::Semicolon),
-			VirtualKeyCode::Slash => Ok(Key::Slash),
-			VirtualKeyCode::Sleep => Err(()),
-			VirtualKeyCode::Stop => Err(()),
-			VirtualKeyCode::Sysrq => Err(()),
-			VirtualKeyCode::Tab => Ok(Key::Tab),
-			VirtualKeyCode::Underline => Err(()),
-			VirtualKeyCode::Unlabeled => Err(()),
-			VirtualKeyCode::VolumeDown => Err(()),
-			VirtualKeyCode::VolumeUp => Err(()),
-			VirtualKeyCode::Wake => Err(()),
-			VirtualKeyCode::WebBack => Err(()),
-			VirtualKeyCode::WebFavorites => Err(()),
-			VirtualKeyCode::WebForward => Err(()),
-			VirtualKeyCode::WebHome => Err(()),
-			VirtualKeyCode::WebRefresh => Err(()),
-			VirtualKeyCode::WebSearch => Err(()),
-			VirtualKeyCode::WebStop => Err(()),
-			VirtualKeyCode::Yen => Err(()),
-			VirtualKeyCode::Copy => Err(()),
-			VirtualKeyCode::Paste => Err(()),
-			VirtualKeyCode::Cut => Err(()),
+			VirtualKeyCode::Key1 => Key::Key1,
+			VirtualKeyCode::Key2 => Key::Key2,
+			VirtualKeyCode::Key3 => Key::Key3,
+			VirtualKeyCode::Key4 => Key::Key4,
+			VirtualKeyCode::Key5 => Key::Key5,
+			VirtualKeyCode::Key6 => Key::Key6,
+			VirtualKeyCode::Key7 => Key::Key7,
+			VirtualKeyCode::Key8 => Key::Key8,
+			VirtualKeyCode::Key9 => Key::Key9,
+			VirtualKeyCode::Key0 => Key::Key0,
+			VirtualKeyCode::A => Key::A,
+			VirtualKeyCode::B => Key::B,
+			VirtualKeyCode::C => Key::C,
+			VirtualKeyCode::D => Key::D,
+			VirtualKeyCode::E => Key::E,
+			VirtualKeyCode::F => Key::F,
+			VirtualKeyCode::G => Key::G,
+			VirtualKeyCode::H => Key::H,
+			VirtualKeyCode::I => Key::I,
+			VirtualKeyCode::J => Key::J,
+			VirtualKeyCode::K => Key::K,
+			VirtualKeyCode::L => Key::L,
+			VirtualKeyCode::M => Key::M,
+			VirtualKeyCode::N => Key::N,
+			VirtualKeyCode::O => Key::O,
+			VirtualKeyCode::P => Key::P,
+			VirtualKeyCode::Q => Key::Q,
+			VirtualKeyCode::R => Key::R,
+			VirtualKeyCode::S => Key::S,
+			VirtualKeyCode::T => Key::T,
+			VirtualKeyCode::U => Key::U,
+			VirtualKeyCode::V => Key::V,
+			VirtualKeyCode::W => Key::W,
+			VirtualKeyCode::X => Key::X,
+			VirtualKeyCode::Y => Key::Y,
+			VirtualKeyCode::Z => Key::Z,
+			VirtualKeyCode::Escape => Key::Escape,
+			VirtualKeyCode::F1 => Key::F1,
+			VirtualKeyCode::F2 => Key::F2,
+			VirtualKeyCode::F3 => Key::F3,
+			VirtualKeyCode::F4 => Key::F4,
+			VirtualKeyCode::F5 => Key::F5,
+			VirtualKeyCode::F6 => Key::F6,
+			VirtualKeyCode::F7 => Key::F7,
+			VirtualKeyCode::F8 => Key::F8,
+			VirtualKeyCode::F9 => Key::F9,
+			VirtualKeyCode::F10 => Key::F10,
+			VirtualKeyCode::F11 => Key::F11,
+			VirtualKeyCode::F12 => Key::F12,
+			VirtualKeyCode::F13 => Key::F13,
+			VirtualKeyCode::F14 => Key::F14,
+			VirtualKeyCode::F15 => Key::F15,
+			VirtualKeyCode::F16 => Key::F16,
+			VirtualKeyCode::F17 => Key::F17,
+			VirtualKeyCode::F18 => Key::F18,
+			VirtualKeyCode::F19 => Key::F19,
+			VirtualKeyCode::F20 => Key::F20,
+			VirtualKeyCode::F21 => Key::F21,
+			VirtualKeyCode::F22 => Key::F22,
+			VirtualKeyCode::F23 => Key::F23,
+			VirtualKeyCode::F24 => Key::F24,
+			VirtualKeyCode::Snapshot => Key::Snapshot,
+			VirtualKeyCode::Scroll => Key::ScrollLock,
+			VirtualKeyCode::Pause => Key::Pause,
+			VirtualKeyCode::Insert => Key::Insert,
+			VirtualKeyCode::Home => Key::Home,
+			VirtualKeyCode::Delete => Key::Delete,
+			VirtualKeyCode::End => Key::End,
+			VirtualKeyCode::PageDown => Key::PageDown,
+			VirtualKeyCode::PageUp => Key::PageUp,
+			VirtualKeyCode::Left => Key::Left,
+			VirtualKeyCode::Up => Key::Up,
+			VirtualKeyCode::Right => Key::Right,
+			VirtualKeyCode::Down => Key::Down,
+			VirtualKeyCode::Back => Key::Back,
+			VirtualKeyCode::Return => Key::Return,
+			VirtualKeyCode::Space => Key::Space,
+			VirtualKeyCode::Compose => Key::Compose,
+			VirtualKeyCode::Caret => Key::Caret,
+			VirtualKeyCode::Numlock => Key::Numlock,
+			VirtualKeyCode::Numpad0 => Key::Numpad0,
+			VirtualKeyCode::Numpad1 => Key::Numpad1,
+			VirtualKeyCode::Numpad2 => Key::Numpad2,
+			VirtualKeyCode::Numpad3 => Key::Numpad3,
+			VirtualKeyCode::Numpad4 => Key::Numpad4,
+			VirtualKeyCode::Numpad5 => Key::Numpad5,
+			VirtualKeyCode::Numpad6 => Key::Numpad6,
+			VirtualKeyCode::Numpad7 => Key::Numpad7,
+			VirtualKeyCode::Numpad8 => Key::Numpad8,
+			VirtualKeyCode::Numpad9 => Key::Numpad9,
+			VirtualKeyCode::NumpadAdd => Key::NumpadPlus,
+			VirtualKeyCode::NumpadDivide => Key::NumpadSlash,
+			VirtualKeyCode::NumpadDecimal => Key::NumpadDecimal,
+			VirtualKeyCode::NumpadComma => Key::NumpadComma,
+			VirtualKeyCode::NumpadEnter => Key::NumpadEnter,
+			VirtualKeyCode::NumpadEquals => Key::NumpadEquals,
+			VirtualKeyCode::NumpadMultiply => Key::NumpadAsterisk,
+			VirtualKeyCode::NumpadSubtract => Key::NumpadMinus,
+			VirtualKeyCode::AbntC1 => Key::AbntC1,
+			VirtualKeyCode::AbntC2 => Key::AbntC2,
+			VirtualKeyCode::Apostrophe => Key::Apostrophe,
+			VirtualKeyCode::Apps => Key::Apps,
+			VirtualKeyCode::Asterisk => Key::Asterisk,
+			VirtualKeyCode::At => Key::At,
+			VirtualKeyCode::Ax => Key::Ax,
+			VirtualKeyCode::Backslash => Key::Backslash,
+			VirtualKeyCode::Calculator => Key::Calculator,
+			VirtualKeyCode::Capital => Key::CapitalLock,
+			VirtualKeyCode::Colon => Key::Colon,
+			VirtualKeyCode::Comma => Key::Comma,
+			VirtualKeyCode::Convert => Key::Convert,
+			VirtualKeyCode::Equals => Key::Equals,
+			VirtualKeyCode::Grave => Key::Grave,
+			VirtualKeyCode::Kana => Key::Kana,
+			VirtualKeyCode::Kanji => Key::Kanji,
+			VirtualKeyCode::LAlt => Key::LAlt,
+			VirtualKeyCode::LBracket => Key::LBracket,
+			VirtualKeyCode::LControl => Key::LControl,
+			VirtualKeyCode::LShift => Key::LShift,
+			VirtualKeyCode::LWin => Key::LWin,
+			VirtualKeyCode::Mail => Key::Mail,
+			VirtualKeyCode::MediaSelect => Key::MediaSelect,
+			VirtualKeyCode::MediaStop => Key::MediaStop,
+			VirtualKeyCode::Minus => Key::Minus,
+			VirtualKeyCode::Mute => Key::Mute,
+			VirtualKeyCode::MyComputer => Key::MyComputer,
+			VirtualKeyCode::NavigateForward => Key::NavigateForward,
+			VirtualKeyCode::NavigateBackward => Key::NavigateBackward,
+			VirtualKeyCode::NextTrack => Key::NextTrack,
+			VirtualKeyCode::NoConvert => Key::NoConvert,
+			VirtualKeyCode::OEM102 => Key::OEM102,
+			VirtualKeyCode::Period => Key::Period,
+			VirtualKeyCode::PlayPause => Key::PlayPause,
+			VirtualKeyCode::Plus => Key::Plus,
+			VirtualKeyCode::Power => Key::Power,
+			VirtualKeyCode::PrevTrack => Key::PrevTrack,
+			VirtualKeyCode::RAlt => Key::RAlt,
+			VirtualKeyCode::RBracket => Key::RBracket,
+			VirtualKeyCode::RControl => Key::RControl,
+			VirtualKeyCode::RShift => Key::RShift,
+			VirtualKeyCode::RWin => Key::RWin,
+			VirtualKeyCode::Semicolon => Key::Semicolon,
+			VirtualKeyCode::Slash => Key::Slash,
+			VirtualKeyCode::Sleep => Key::Sleep,
+			VirtualKeyCode::Stop => Key::Stop,
+			VirtualKeyCode::Sysrq => Key::Sysrq,
+			VirtualKeyCode::Tab => Key::Tab,
+			VirtualKeyCode::Underline => Key::Underline,
+			VirtualKeyCode::Unlabeled => Key::Unlabeled,
+			VirtualKeyCode::VolumeDown => Key::VolumeDown,
+			VirtualKeyCode::VolumeUp => Key::VolumeUp,
+			VirtualKeyCode::Wake => Key::Wake,
+			VirtualKeyCode::WebBack => Key::WebBack,
+			VirtualKeyCode::WebFavorites => Key::WebFavorites,
+			VirtualKeyCode::WebForward => Key::WebForward,
+			VirtualKeyCode::WebHome => Key::WebHome,
+			VirtualKeyCode::WebRefresh => Key::WebRefresh,
+			VirtualKeyCode::WebSearch => Key::WebSearch,
+			VirtualKeyCode::WebStop => Key::WebStop,
+			VirtualKeyCode::Yen => Key::Yen,
+			VirtualKeyCode::Copy => Key::Copy,
+			VirtualKeyCode::Paste => Key::Paste,
+			VirtualKeyCode::Cut => Key::Cut,
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn focus_changes_are_window_events() {
+		let focused: winit::event::Event<()> = winit::event::Event::WindowEvent {
+			window_id: unsafe { winit::window::WindowId::dummy() },
+			event: winit::event::WindowEvent::Focused(false),
+		};
+		assert!(matches!(
+			parse_winit_event_with(&focused, KeyboardEvents::Window),
+			Ok(event::Event::Window(event::WindowEvent::Focused(false)))
+		));
+	}
+
+	#[test]
+	#[allow(deprecated)]
+	fn unidentified_keys_have_no_logical_key() {
+		let input = winit::event::KeyboardInput {
+			scancode: 0x56,
+			state: winit::event::ElementState::Pressed,
+			virtual_keycode: None,
+			modifiers: Default::default(),
+		};
+		assert!(matches!(
+			key_event(&input),
+			event::Event::Key {
+				logical: None,
+				scancode: 0x56,
+				..
+			}
+		));
+	}
+}
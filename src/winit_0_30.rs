@@ -0,0 +1,326 @@
+use crate::{
+	binding, device, event,
+	keymap::{self, key_from_code, key_from_named, key_from_native, PIXELS_PER_LINE},
+	source::{Key, MouseButton, ScanCode},
+	text,
+};
+use ::winit_0_30 as winit;
+use winit::{
+	event::{DeviceEvent, ElementState, Ime, KeyEvent, MouseScrollDelta, WindowEvent},
+	keyboard::{KeyCode, KeyLocation, NamedKey, NativeKeyCode, PhysicalKey},
+};
+
+/// Parses an event from the winit event loop.
+/// Prefer [`parse_window_event`] and [`parse_device_event`] when implementing `ApplicationHandler`.
+pub fn parse_event<T>(event: &winit::event::Event<T>) -> Vec<event::Event> {
+	match event {
+		winit::event::Event::WindowEvent { event, .. } => parse_window_event(event),
+		winit::event::Event::DeviceEvent { event, .. } => parse_device_event(event),
+		_ => Vec::new(),
+	}
+}
+
+/// Parses the events sent to `ApplicationHandler::window_event`.
+/// Keyboard input is read from window events, so it is ignored while the window is not focused.
+/// A single winit event can be sent as several events (like a key press which also types text).
+///
+/// ```rust,ignore
+/// impl ApplicationHandler for App {
+///     fn window_event(&mut self, _: &ActiveEventLoop, _: WindowId, event: WindowEvent) {
+///         for input_event in input_actions::winit_0_30::parse_window_event(&event) {
+///             self.input_sys.send_event(input_event);
+///         }
+///     }
+/// }
+/// ```
+pub fn parse_window_event(event: &WindowEvent) -> Vec<event::Event> {
+	match event {
+		WindowEvent::Resized(size) => vec![event::Event::Window(
+			event::WindowEvent::ResolutionChanged(size.width, size.height),
+		)],
+		// The new size is sent in a `Resized` event afterwards.
+		WindowEvent::ScaleFactorChanged { scale_factor, .. } => vec![event::Event::Window(
+			event::WindowEvent::ScaleFactor(*scale_factor),
+		)],
+		WindowEvent::Focused(focused) => {
+			vec![event::Event::Window(event::WindowEvent::Focused(*focused))]
+		}
+		WindowEvent::KeyboardInput { event, .. } => key_events(event),
+		WindowEvent::Ime(Ime::Preedit(text, cursor)) => {
+			vec![event::Event::Text(text::TextInput::Preedit {
+				text: text.clone(),
+				cursor: *cursor,
+			})]
+		}
+		WindowEvent::Ime(Ime::Commit(text)) => {
+			vec![event::Event::Text(text::TextInput::Commit(text.clone()))]
+		}
+		WindowEvent::MouseInput { state, button, .. } => match mouse_button(*button) {
+			Some(button) => vec![event::Event::Input(
+				device::Id::Mouse,
+				binding::Source::Mouse(binding::Mouse::Button(button)),
+				event::State::ButtonState(button_state(*state)),
+			)],
+			None => Vec::new(),
+		},
+		WindowEvent::MouseWheel { delta, .. } => {
+			let (horizontal, vertical) = scroll_lines(delta);
+			let mut events = Vec::with_capacity(2);
+			if horizontal != 0.0 {
+				events.push(mouse_scroll(binding::MouseAxis::MouseX, horizontal));
+			}
+			if vertical != 0.0 {
+				events.push(mouse_scroll(binding::MouseAxis::MouseY, vertical));
+			}
+			events
+		}
+		_ => Vec::new(),
+	}
+}
+
+/// Parses the events sent to `ApplicationHandler::device_event`, which is where raw mouse movement comes from.
+pub fn parse_device_event(event: &DeviceEvent) -> Vec<event::Event> {
+	match event {
+		DeviceEvent::MouseMotion { delta: (x, y) } => {
+			let mut events = Vec::with_capacity(2);
+			if *x != 0.0 {
+				events.push(mouse_move(binding::MouseAxis::MouseX, *x));
+			}
+			if *y != 0.0 {
+				events.push(mouse_move(binding::MouseAxis::MouseY, *y));
+			}
+			events
+		}
+		_ => Vec::new(),
+	}
+}
+
+fn key_events(key_event: &KeyEvent) -> Vec<event::Event> {
+	keymap::key_events(
+		physical_key(key_event.physical_key),
+		logical_key(
+			&key_event.logical_key,
+			key_event.physical_key,
+			key_event.location,
+		),
+		scancode(key_event.physical_key),
+		button_state(key_event.state),
+		key_event.repeat,
+		key_event.text.as_deref(),
+	)
+}
+
+fn button_state(state: ElementState) -> event::ButtonState {
+	match state {
+		ElementState::Pressed => event::ButtonState::Pressed,
+		ElementState::Released => event::ButtonState::Released,
+	}
+}
+
+fn mouse_button(button: winit::event::MouseButton) -> Option<MouseButton> {
+	match button {
+		winit::event::MouseButton::Left => Some(MouseButton::Left),
+		winit::event::MouseButton::Middle => Some(MouseButton::Center),
+		winit::event::MouseButton::Right => Some(MouseButton::Right),
+		_ => None,
+	}
+}
+
+fn mouse_move(axis: binding::MouseAxis, delta: f64) -> event::Event {
+	event::Event::Input(
+		device::Id::Mouse,
+		binding::Source::Mouse(binding::Mouse::Move(axis)),
+		event::State::MouseMove(delta),
+	)
+}
+
+/// Returns the horizontal and vertical distance scrolled, in lines.
+fn scroll_lines(delta: &MouseScrollDelta) -> (f32, f32) {
+	match delta {
+		MouseScrollDelta::LineDelta(horizontal, vertical) => (*horizontal, *vertical),
+		MouseScrollDelta::PixelDelta(position) => (
+			(position.x / PIXELS_PER_LINE) as f32,
+			(position.y / PIXELS_PER_LINE) as f32,
+		),
+	}
+}
+
+fn mouse_scroll(axis: binding::MouseAxis, delta: f32) -> event::Event {
+	event::Event::Input(
+		device::Id::Mouse,
+		binding::Source::Mouse(binding::Mouse::Scroll(axis)),
+		event::State::MouseScroll(delta),
+	)
+}
+
+/// Returns the same scancode that winit 0.27 reported, which is what [`Key::from_scancode`] expects.
+#[cfg(any(
+	target_os = "windows",
+	target_os = "macos",
+	target_os = "linux",
+	target_os = "dragonfly",
+	target_os = "freebsd",
+	target_os = "netbsd",
+	target_os = "openbsd"
+))]
+fn scancode(key: PhysicalKey) -> Option<ScanCode> {
+	use winit::platform::scancode::PhysicalKeyExtScancode;
+	key.to_scancode()
+}
+
+#[cfg(not(any(
+	target_os = "windows",
+	target_os = "macos",
+	target_os = "linux",
+	target_os = "dragonfly",
+	target_os = "freebsd",
+	target_os = "netbsd",
+	target_os = "openbsd"
+)))]
+fn scancode(key: PhysicalKey) -> Option<ScanCode> {
+	match key {
+		PhysicalKey::Unidentified(NativeKeyCode::Android(code)) => Some(code),
+		_ => None,
+	}
+}
+
+/// Returns the key at the position of a physical key on a US-QWERTY keyboard.
+fn physical_key(key: PhysicalKey) -> Option<Key> {
+	match key {
+		PhysicalKey::Code(code) => Key::try_from_code(code),
+		PhysicalKey::Unidentified(native) => key_from_native!(NativeKeyCode, native),
+	}
+}
+
+/// Returns the key which typed the logical key on a US-QWERTY keyboard.
+/// Numpad keys are always their physical key, since numpads don't differ between layouts.
+fn logical_key(
+	logical: &winit::keyboard::Key,
+	physical: PhysicalKey,
+	location: KeyLocation,
+) -> Option<Key> {
+	use winit::keyboard::Key as Logical;
+	if location == KeyLocation::Numpad {
+		return physical_key(physical);
+	}
+	match logical {
+		Logical::Named(named) => named_key(*named, location),
+		Logical::Character(text) => keymap::character_key(text),
+		Logical::Unidentified(_) | Logical::Dead(_) => None,
+	}
+}
+
+fn named_key(key: NamedKey, location: KeyLocation) -> Option<Key> {
+	let right = location == KeyLocation::Right;
+	match key {
+		NamedKey::Alt | NamedKey::AltGraph if right => Some(Key::RAlt),
+		NamedKey::Alt => Some(Key::LAlt),
+		NamedKey::Control if right => Some(Key::RControl),
+		NamedKey::Control => Some(Key::LControl),
+		NamedKey::Shift if right => Some(Key::RShift),
+		NamedKey::Shift => Some(Key::LShift),
+		NamedKey::Super | NamedKey::Meta if right => Some(Key::RWin),
+		NamedKey::Super | NamedKey::Meta => Some(Key::LWin),
+		_ => key_from_named!(NamedKey, key),
+	}
+}
+
+impl Key {
+	/// Returns the key at the position of a winit 0.30 key code on a US-QWERTY keyboard.
+	pub fn try_from_code(code: KeyCode) -> Option<Self> {
+		key_from_code!(KeyCode, code)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ::winit_0_30::{
+		dpi::PhysicalPosition,
+		keyboard::{Key as Logical, NativeKey},
+	};
+
+	#[test]
+	fn numpad_keys_are_their_physical_key() {
+		let logical = Logical::Character("7".into());
+		let physical = PhysicalKey::Code(KeyCode::Numpad7);
+		assert_eq!(
+			logical_key(&logical, physical, KeyLocation::Numpad),
+			Some(Key::Numpad7)
+		);
+		assert_eq!(
+			logical_key(
+				&Logical::Named(NamedKey::Enter),
+				PhysicalKey::Code(KeyCode::NumpadEnter),
+				KeyLocation::Numpad
+			),
+			Some(Key::NumpadEnter)
+		);
+	}
+
+	#[test]
+	fn modifiers_use_their_location() {
+		let key = |named, code, location| {
+			logical_key(&Logical::Named(named), PhysicalKey::Code(code), location)
+		};
+		assert_eq!(
+			key(NamedKey::Shift, KeyCode::ShiftLeft, KeyLocation::Left),
+			Some(Key::LShift)
+		);
+		assert_eq!(
+			key(NamedKey::Shift, KeyCode::ShiftRight, KeyLocation::Right),
+			Some(Key::RShift)
+		);
+		assert_eq!(
+			key(NamedKey::Control, KeyCode::ControlRight, KeyLocation::Right),
+			Some(Key::RControl)
+		);
+		assert_eq!(
+			key(NamedKey::AltGraph, KeyCode::AltRight, KeyLocation::Right),
+			Some(Key::RAlt)
+		);
+		assert_eq!(
+			key(NamedKey::Super, KeyCode::SuperLeft, KeyLocation::Left),
+			Some(Key::LWin)
+		);
+	}
+
+	#[test]
+	fn dead_and_unidentified_keys_have_no_logical_key() {
+		let physical = PhysicalKey::Code(KeyCode::BracketLeft);
+		assert_eq!(
+			logical_key(&Logical::Dead(Some('^')), physical, KeyLocation::Standard),
+			None
+		);
+		assert_eq!(
+			logical_key(
+				&Logical::Unidentified(NativeKey::Unidentified),
+				physical,
+				KeyLocation::Standard
+			),
+			None
+		);
+		// Characters are the key which types them on US-QWERTY, wherever they are on the keyboard.
+		assert_eq!(
+			logical_key(
+				&Logical::Character("a".into()),
+				PhysicalKey::Code(KeyCode::KeyQ),
+				KeyLocation::Standard
+			),
+			Some(Key::A)
+		);
+	}
+
+	#[test]
+	fn pixel_scrolling_is_converted_to_lines() {
+		let pixels = MouseScrollDelta::PixelDelta(PhysicalPosition::new(
+			PIXELS_PER_LINE * 2.0,
+			-PIXELS_PER_LINE / 2.0,
+		));
+		assert_eq!(scroll_lines(&pixels), (2.0, -0.5));
+		assert_eq!(
+			scroll_lines(&MouseScrollDelta::LineDelta(0.0, 3.0)),
+			(0.0, 3.0)
+		);
+	}
+}